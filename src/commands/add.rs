@@ -0,0 +1,103 @@
+//! `cyrus add` / `cyrus rm`: change dependencies through the project's package manager
+//! src/commands/add.rs
+//!
+//! The package manager edits the native manifest (package.json, pyproject.toml,
+//! Cargo.toml, ...) first; cyrus.toml is only updated once that succeeded, so
+//! the two stay consistent.
+
+use crate::core::manifest::NativeManifest;
+use crate::core::venv;
+use crate::core::{CyrusCore, Environment, Project};
+use crate::error::CyrusError;
+use crate::languages::{self, DependencyChange, LanguageHandler};
+use super::run::{ensure_python_venv, python_toolchain};
+use super::{AddCommand, RmCommand};
+use anyhow::{Context, Result};
+use colored::*;
+use std::path::{Path, PathBuf};
+
+pub async fn execute(cmd: AddCommand, core: &CyrusCore) -> Result<()> {
+    let (root, mut project, handler, toolchain) = prepare(core)?;
+
+    for spec in &cmd.packages {
+        let change = DependencyChange::add(spec, cmd.dev);
+        println!("{} Adding {}{} with {}",
+                 "📦".blue(),
+                 spec.yellow(),
+                 if cmd.dev { " (dev)" } else { "" },
+                 project.package_manager.cyan());
+
+        handler.apply_dependency_change(&root, &toolchain, &project.package_manager, &change)?;
+        project.add_dependency(spec, cmd.dev);
+        save(&project, &root)?;
+    }
+
+    println!("{} Updated {}", "✅".green(), "cyrus.toml".blue());
+    Ok(())
+}
+
+pub async fn execute_rm(cmd: RmCommand, core: &CyrusCore) -> Result<()> {
+    let (root, mut project, handler, toolchain) = prepare(core)?;
+    // The manifest is what the package manager edits, so its group wins when cyrus.toml has drifted
+    let native = NativeManifest::for_language(&root, &project.language)?;
+
+    for name in &cmd.packages {
+        let listed = project.remove_dependency(name);
+        if listed.is_none() {
+            println!("{} {} is not in cyrus.toml; removing it from the manifest anyway",
+                     "💡".yellow(), name.yellow());
+        }
+
+        let change = DependencyChange::Remove {
+            name: name.clone(),
+            dev: native.as_ref()
+                .and_then(|manifest| manifest.is_dev_dependency(name))
+                .or(listed)
+                .unwrap_or(false),
+        };
+        println!("{} Removing {} with {}", "🗑️".blue(), name.yellow(), project.package_manager.cyan());
+
+        handler.apply_dependency_change(&root, &toolchain, &project.package_manager, &change)?;
+        save(&project, &root)?;
+    }
+
+    println!("{} Updated {}", "✅".green(), "cyrus.toml".blue());
+    Ok(())
+}
+
+/// The project, its language handler and the toolchain its package manager runs in
+fn prepare(core: &CyrusCore) -> Result<(PathBuf, Project, Box<dyn LanguageHandler + Send + Sync>, Environment)> {
+    let root = Project::find_project_root()
+        .context("No cyrus.toml found. Run 'cyrus init' first.")?;
+    let project = Project::load_from_file(Project::config_path(&root)?)?;
+
+    let handler = languages::get_language_handler(&project.language)
+        .ok_or_else(|| CyrusError::UnsupportedLanguage { language: project.language.clone() })?;
+
+    if !core.is_language_installed(&project.language, &project.version) {
+        return Err(CyrusError::LanguageNotInstalled {
+            language: project.language.clone(),
+            version: project.version.clone(),
+        }
+        .into());
+    }
+
+    let mut toolchain = core.toolchain_environment(&project.language, &project.version, &project.package_manager);
+
+    // pip installs into the project's .venv, never the shared interpreter
+    if project.language == "python" {
+        if let Some(python) = python_toolchain(&project) {
+            ensure_python_venv(core, &project, &python, &root)?;
+        }
+        let venv_path = venv::venv_path(&root);
+        if venv_path.join("pyvenv.cfg").exists() {
+            venv::activate(&mut toolchain, &venv_path);
+        }
+    }
+
+    Ok((root, project, handler, toolchain))
+}
+
+fn save(project: &Project, root: &Path) -> Result<()> {
+    project.save_to_file(Project::config_path(root)?)
+}
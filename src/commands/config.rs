@@ -1,11 +1,27 @@
 //! Config command implementation
 
+use crate::config::GlobalConfig;
+use crate::core::config_format::{self, ConfigFormat};
+use crate::core::{document, extends, lockfile, migration};
 use crate::core::{CyrusCore, Project};
-use super::ConfigCommand;
-use anyhow::Result;
+use super::{ConfigAction, ConfigCommand};
+use anyhow::{Context, Result};
 use colored::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use toml::value::Table;
 
 pub async fn execute(cmd: ConfigCommand, core: &CyrusCore) -> Result<()> {
+    match cmd.action {
+        Some(ConfigAction::Migrate { check }) => return migrate(check),
+        Some(ConfigAction::Convert { to }) => return convert(to),
+        Some(ConfigAction::Get { key }) => return get(&key, cmd.global),
+        Some(ConfigAction::Set { key, value }) => return set(&key, &value, cmd.global),
+        Some(ConfigAction::Unset { key }) => return unset(&key, cmd.global),
+        Some(ConfigAction::Show { merged: true }) => return show_merged(),
+        Some(ConfigAction::Show { merged: false }) | None => {},
+    }
+
     if cmd.global {
         println!("{}", "🌐 Global Configuration:".cyan().bold());
         println!("Cyrus Directory: {}", core.cyrus_dir.display().to_string().blue());
@@ -13,23 +29,40 @@ pub async fn execute(cmd: ConfigCommand, core: &CyrusCore) -> Result<()> {
         println!("Languages Directory: {}", core.languages_dir.display().to_string().blue());
     } else {
         // Show project configuration
-        if let Some(project_root) = Project::find_project_root() {
-            let project = Project::load_from_file(project_root.join("cyrus.toml"))?;
-            
+        if let Some((_, project)) = Project::discover()? {
             println!("{}", "📁 Project Configuration:".cyan().bold());
             println!("Name: {}", project.name.yellow());
             println!("Language: {}", project.language.yellow());
             println!("Version: {}", project.version.yellow());
             println!("Package Manager: {}", project.package_manager.yellow());
             
+            if !project.toolchains.is_empty() {
+                println!("Toolchains:");
+                for toolchain in lockfile::requested_toolchains(&project).iter().skip(1) {
+                    println!("  {} {} ({})", toolchain.language.green(), toolchain.version.cyan(), toolchain.package_manager);
+                }
+            }
+            
             if !project.dependencies.is_empty() {
                 println!("Dependencies: {}", project.dependencies.join(", ").cyan());
             }
             
             if !project.scripts.is_empty() {
                 println!("Scripts:");
-                for (name, command) in &project.scripts {
-                    println!("  {}: {}", name.green(), command.cyan());
+                for (name, script) in &project.scripts {
+                    println!("  {}: {}", name.green(), script.to_string().cyan());
+                }
+            }
+            
+            if !project.environment.is_empty() {
+                println!("Environment:");
+                for (key, value) in &project.environment {
+                    // Secret references are shown as-is; vault values are never resolved here
+                    if value.starts_with("secret://") {
+                        println!("  {} = {} {}", key.green(), value.magenta(), "🔒".dimmed());
+                    } else {
+                        println!("  {} = {}", key.green(), value.cyan());
+                    }
                 }
             }
         } else {
@@ -39,3 +72,193 @@ pub async fn execute(cmd: ConfigCommand, core: &CyrusCore) -> Result<()> {
     
     Ok(())
 }
+
+/// The effective cyrus.toml after `extends`, one key per line with its origin
+fn show_merged() -> Result<()> {
+    let Some(root) = Project::find_project_root() else {
+        anyhow::bail!("No cyrus.toml found. Run 'cyrus init' first.");
+    };
+    let (project, merged) = Project::load_merged(Project::config_path(&root)?)?;
+
+    println!("{}", "📁 Effective Configuration:".cyan().bold());
+    if !project.extends.is_empty() {
+        println!("Extends: {}", project.extends.join(" → ").yellow());
+    }
+
+    for (key, value) in &merged.table {
+        match value {
+            toml::Value::Table(entries) if extends::MERGED_TABLES.contains(&key.as_str()) => {
+                println!("[{}]", key.green());
+                for (name, entry) in entries {
+                    let dotted = format!("{}.{}", key, name);
+                    println!("  {} = {}  {}", name, entry.to_string().cyan(), format!("# {}", merged.origin(&dotted)).dimmed());
+                }
+            },
+            _ => println!("{} = {}  {}", key.green(), value.to_string().cyan(), format!("# {}", merged.origin(key)).dimmed()),
+        }
+    }
+    Ok(())
+}
+
+fn migrate(check: bool) -> Result<()> {
+    let Some(root) = Project::find_project_root() else {
+        anyhow::bail!("No cyrus.toml found. Run 'cyrus init' first.");
+    };
+    let path = Project::config_path(&root)?;
+
+    let (_, report) = migration::migrate_file(&path, check)?;
+    if !report.is_needed() {
+        println!("{} cyrus.toml is already at schema version {}", "✅".green(), report.to);
+        return Ok(());
+    }
+
+    println!("{} cyrus.toml schema {} → {}:", "📦".blue(), report.from, report.to);
+    for step in &report.applied {
+        println!("  • {}", step);
+    }
+
+    if check {
+        anyhow::bail!("cyrus.toml needs migrating; run 'cyrus config migrate'");
+    }
+
+    if let Some(backup) = &report.backup {
+        println!("{} Migrated; the original is at {}", "✅".green(), backup.display().to_string().dimmed());
+    }
+    Ok(())
+}
+
+/// Rewrite the project file in another format, keeping the original as `<file>.bak`
+fn convert(to: ConfigFormat) -> Result<()> {
+    let Some(root) = Project::find_project_root() else {
+        anyhow::bail!("No cyrus.toml found. Run 'cyrus init' first.");
+    };
+    let source = Project::config_path(&root)?;
+    if ConfigFormat::from_path(&source) == to {
+        println!("{} {} is already {}", "✅".green(), source.display(), to);
+        return Ok(());
+    }
+
+    // The raw table, so nothing is dropped or filled in by the Project struct
+    let table = config_format::read_table(&source)?;
+    let target = root.join(to.file_name());
+    std::fs::write(&target, to.serialize(&table)?)?;
+
+    if let Err(e) = Project::load_from_file(&target) {
+        let _ = std::fs::remove_file(&target);
+        return Err(e.context(format!("Converted {} does not load; left {} untouched", target.display(), source.display())));
+    }
+
+    let backup = source.with_file_name(format!(
+        "{}.bak",
+        source.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    std::fs::rename(&source, &backup)?;
+
+    println!("{} Converted {} → {}", "✅".green(), source.display(), target.display().to_string().blue());
+    println!("   The original is at {}", backup.display().to_string().dimmed());
+    Ok(())
+}
+
+/// The file `config get/set/unset` work on
+fn target(global: bool) -> Result<PathBuf> {
+    if global {
+        return Ok(GlobalConfig::config_path()?);
+    }
+    let Some(root) = Project::find_project_root() else {
+        anyhow::bail!("No cyrus.toml found. Run 'cyrus init' first, or pass --global.");
+    };
+    Project::config_path(&root)
+}
+
+fn get(key: &str, global: bool) -> Result<()> {
+    // Effective values: defaults for the global config, `extends` applied for the project
+    let table = if global {
+        to_table(&GlobalConfig::load_or_default()?)?
+    } else {
+        Project::load_merged(target(false)?)?.1.table
+    };
+
+    match document::get(&table, key).with_context(|| format!("{} is not set", key))? {
+        toml::Value::String(value) => println!("{}", value),
+        value => println!("{}", value),
+    }
+    Ok(())
+}
+
+fn set(key: &str, raw: &str, global: bool) -> Result<()> {
+    let path = target(global)?;
+    let format = ConfigFormat::from_path(&path);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(_) if global => toml::to_string_pretty(&GlobalConfig::new())?,
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+
+    // `3000` may be meant as a number or as a string; keep whichever the schema accepts
+    let mut candidates: Vec<toml::Value> = document::parse_value(raw).into_iter().collect();
+    if !matches!(candidates.first(), Some(toml::Value::String(_))) {
+        candidates.push(toml::Value::String(raw.to_string()));
+    }
+
+    let mut rejected = None;
+    for value in candidates {
+        let edited = document::set(format, &content, key, value.clone())?;
+        match check(&path, &edited, format, key, global, true) {
+            Ok(()) => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&path, edited)?;
+                println!("{} Set {} = {} in {}", "✅".green(), key.green(), value.to_string().cyan(), path.display());
+                return Ok(());
+            },
+            Err(e) => rejected = Some(e),
+        }
+    }
+    Err(rejected.expect("a string is always tried"))
+}
+
+fn unset(key: &str, global: bool) -> Result<()> {
+    let path = target(global)?;
+    let format = ConfigFormat::from_path(&path);
+    let content = std::fs::read_to_string(&path).unwrap_or_default();
+
+    let Some(edited) = document::unset(format, &content, key)? else {
+        println!("{} {} is not set in {}", "💡".yellow(), key, path.display());
+        return Ok(());
+    };
+    check(&path, &edited, format, key, global, false)?;
+    std::fs::write(&path, edited)?;
+
+    println!("{} Unset {} in {}", "✅".green(), key.green(), path.display());
+    Ok(())
+}
+
+/// The edited file at `path` still fits its schema and, when setting, `key` is one the schema knows.
+/// Projects are checked the way they load: migrated, with their `extends` chain applied.
+fn check(path: &Path, content: &str, format: ConfigFormat, key: &str, global: bool, set: bool) -> Result<()> {
+    let mut table = format.parse(content)?;
+    let known = if global {
+        let config: GlobalConfig = toml::Value::Table(table).try_into()
+            .with_context(|| format!("{} doesn't fit the global config schema", key))?;
+        config.validate()?;
+        to_table(&config)?
+    } else {
+        migration::migrate(&mut table)?;
+        let (project, _) = Project::from_table(path, table)
+            .with_context(|| format!("{} doesn't fit the cyrus.toml schema", key))?;
+        to_table(&project)?
+    };
+
+    if set && document::get(&known, key).is_none() {
+        anyhow::bail!("Unknown key '{}'", key);
+    }
+    Ok(())
+}
+
+fn to_table<T: Serialize>(value: &T) -> Result<Table> {
+    match toml::Value::try_from(value)? {
+        toml::Value::Table(table) => Ok(table),
+        _ => unreachable!("configs serialize to tables"),
+    }
+}
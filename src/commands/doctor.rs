@@ -0,0 +1,375 @@
+//! `cyrus doctor`: diagnose the local cyrus setup
+//! src/commands/doctor.rs
+
+use crate::config::GlobalConfig;
+use crate::core::{lockfile, venv, CyrusCore, Project};
+use crate::plugins::{self, PluginManager};
+use crate::utils::platform::Platform;
+use super::run::python_toolchain;
+use super::DoctorCommand;
+use anyhow::Result;
+use colored::*;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Languages whose toolchains are built from source on Unix-like platforms
+const SOURCE_BUILT: &[&str] = &["python", "ruby", "php"];
+
+/// Tools a source build needs, with the package that usually provides them
+const BUILD_TOOLS: &[(&str, &str)] = &[
+    ("cc", "a C compiler (build-essential / Xcode command line tools)"),
+    ("make", "make"),
+    ("pkg-config", "pkg-config"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub category: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Check {
+    pub fn ok(category: &'static str, message: impl Into<String>) -> Self {
+        Self { category, status: CheckStatus::Ok, message: message.into(), fix: None }
+    }
+
+    pub fn warning(category: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { category, status: CheckStatus::Warning, message: message.into(), fix: Some(fix.into()) }
+    }
+
+    pub fn error(category: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { category, status: CheckStatus::Error, message: message.into(), fix: Some(fix.into()) }
+    }
+}
+
+pub async fn execute(cmd: DoctorCommand, core: &CyrusCore) -> Result<()> {
+    let path_entries: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+
+    let mut checks = Vec::new();
+    check_path(core, &path_entries, &mut checks);
+    check_installs(core, &mut checks);
+    check_build_dependencies(core, &mut checks);
+    check_configs(&mut checks);
+    check_venvs(core, &mut checks);
+    check_plugins(core, &mut checks).await;
+
+    let errors = count(&checks, CheckStatus::Error);
+    let warnings = count(&checks, CheckStatus::Warning);
+
+    if cmd.json {
+        println!("{}", serde_json::to_string_pretty(&json_report(&checks))?);
+    } else {
+        print_report(&checks);
+        println!();
+        if errors == 0 && warnings == 0 {
+            println!("{} Everything looks good!", "✅".green());
+        } else {
+            println!("{} {} error(s), {} warning(s)", "🩺".blue(), errors.to_string().red(), warnings.to_string().yellow());
+        }
+    }
+
+    outcome(&checks)
+}
+
+fn count(checks: &[Check], status: CheckStatus) -> usize {
+    checks.iter().filter(|c| c.status == status).count()
+}
+
+/// The `--json` document
+pub fn json_report(checks: &[Check]) -> serde_json::Value {
+    serde_json::json!({
+        "checks": checks,
+        "errors": count(checks, CheckStatus::Error),
+        "warnings": count(checks, CheckStatus::Warning),
+    })
+}
+
+/// Fails, and so exits non-zero, when any check is an error
+pub fn outcome(checks: &[Check]) -> Result<()> {
+    let errors = count(checks, CheckStatus::Error);
+    if errors > 0 {
+        anyhow::bail!("cyrus doctor found {} error(s)", errors);
+    }
+    Ok(())
+}
+
+fn print_report(checks: &[Check]) {
+    println!("{}", "🩺 Cyrus Doctor".cyan().bold());
+
+    let mut category = "";
+    for check in checks {
+        if check.category != category {
+            category = check.category;
+            println!("\n{}", category.green().bold());
+        }
+
+        let icon = match check.status {
+            CheckStatus::Ok => "✅",
+            CheckStatus::Warning => "⚠️ ",
+            CheckStatus::Error => "❌",
+        };
+        println!("  {} {}", icon, check.message);
+        if let Some(fix) = &check.fix {
+            println!("     {} {}", "💡".yellow(), fix.dimmed());
+        }
+    }
+}
+
+/// Cyrus toolchain directories put on PATH by hand, and whatever shadows them there.
+/// Without that, toolchains only reach PATH through `cyrus run` and `cyrus exec`.
+pub fn check_path(core: &CyrusCore, path_entries: &[PathBuf], checks: &mut Vec<Check>) {
+    const CATEGORY: &str = "PATH";
+    let before = checks.len();
+    let mut on_path = 0;
+
+    for (language, version, _) in installed_toolchains(core) {
+        let environment = core.toolchain_environment(&language, &version, "");
+        for dir in environment.paths.iter().filter(|dir| path_entries.contains(dir)) {
+            on_path += 1;
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let Ok(found) = which::which_in(&name, std::env::join_paths(path_entries).ok(), dir) else {
+                    continue;
+                };
+                if !found.starts_with(dir) {
+                    let shadowing = found.parent().unwrap_or(&found);
+                    checks.push(Check::warning(
+                        CATEGORY,
+                        format!("`{}` resolves to {} instead of cyrus {} {}", name, found.display(), language, version),
+                        format!("Move {} ahead of {} on PATH, or use `cyrus run`", dir.display(), shadowing.display()),
+                    ));
+                }
+            }
+        }
+    }
+
+    if on_path == 0 {
+        checks.push(Check::ok(CATEGORY, "No cyrus toolchain on PATH; `cyrus run` and `cyrus exec` add them per command"));
+    } else if checks.len() == before {
+        checks.push(Check::ok(CATEGORY, format!("{} cyrus toolchain director{} on PATH, none shadowed",
+                                                 on_path, if on_path == 1 { "y" } else { "ies" })));
+    }
+}
+
+/// Broken or partial installs under ~/.cyrus/languages
+fn check_installs(core: &CyrusCore, checks: &mut Vec<Check>) {
+    const CATEGORY: &str = "Installations";
+    let toolchains = installed_toolchains(core);
+
+    if toolchains.is_empty() {
+        checks.push(Check::ok(CATEGORY, "No toolchains installed yet"));
+        return;
+    }
+
+    for (language, version, install_path) in toolchains {
+        let label = format!("{} {}", language, version);
+        let reinstall = format!("cyrus remove {0}{1} && cyrus install {0}{1}", language, version);
+
+        let leftover_archive = std::fs::read_dir(&install_path)
+            .map(|entries| entries.flatten().any(|e| e.path().extension().map(|ext| ext == "archive").unwrap_or(false)))
+            .unwrap_or(false);
+        let environment = core.toolchain_environment(&language, &version, "");
+        let bin_empty = !environment.paths
+            .iter()
+            .any(|dir| std::fs::read_dir(dir).map(|mut e| e.next().is_some()).unwrap_or(false));
+
+        if leftover_archive {
+            checks.push(Check::error(CATEGORY, format!("{} looks like an interrupted install (download archive left behind)", label), reinstall));
+        } else if bin_empty {
+            checks.push(Check::error(CATEGORY, format!("{} has no executables in {}", label, install_path.display()), reinstall));
+        } else {
+            checks.push(Check::ok(CATEGORY, format!("{} installed", label)));
+        }
+    }
+}
+
+/// Compilers and tools needed for toolchains that are built from source
+fn check_build_dependencies(core: &CyrusCore, checks: &mut Vec<Check>) {
+    const CATEGORY: &str = "Build dependencies";
+
+    if !matches!(Platform::current(), Platform::Linux | Platform::MacOS) {
+        checks.push(Check::ok(CATEGORY, "Prebuilt toolchains are used on this platform"));
+        return;
+    }
+
+    // Only matter if a source-built language is installed or used by the current project
+    let project_languages: Vec<String> = Project::discover()
+        .ok()
+        .flatten()
+        .map(|(_, project)| lockfile::requested_toolchains(&project).into_iter().map(|t| t.language).collect())
+        .unwrap_or_default();
+    let relevant = SOURCE_BUILT.iter().any(|language| {
+        core.languages_dir.join(language).exists() || project_languages.iter().any(|l| l == language)
+    });
+
+    for (tool, provider) in BUILD_TOOLS {
+        match which::which(tool) {
+            Ok(path) => checks.push(Check::ok(CATEGORY, format!("{} found at {}", tool, path.display()))),
+            Err(_) if relevant => checks.push(Check::error(
+                CATEGORY,
+                format!("{} is missing; python, ruby and php are built from source here", tool),
+                format!("Install {}", provider),
+            )),
+            Err(_) => checks.push(Check::warning(
+                CATEGORY,
+                format!("{} is missing (needed to install python, ruby or php)", tool),
+                format!("Install {}", provider),
+            )),
+        }
+    }
+}
+
+/// cyrus.toml and the global config parse
+fn check_configs(checks: &mut Vec<Check>) {
+    const CATEGORY: &str = "Configuration";
+
+    match GlobalConfig::config_path() {
+        Ok(path) if path.exists() => match GlobalConfig::load_from_file(&path) {
+            Ok(_) => checks.push(Check::ok(CATEGORY, format!("Global config {} is valid", path.display()))),
+            Err(e) => checks.push(Check::error(
+                CATEGORY,
+                format!("Global config {} is invalid: {}", path.display(), e),
+                "Fix the file, or move it aside to regenerate defaults",
+            )),
+        },
+        Ok(path) => checks.push(Check::ok(CATEGORY, format!("No global config at {} (defaults in use)", path.display()))),
+        Err(e) => checks.push(Check::error(CATEGORY, e.to_string(), "Set CYRUS_CONFIG or pass --config")),
+    }
+
+    match Project::find_project_root() {
+        Some(root) => {
+            let path = match Project::config_path(&root) {
+                Ok(path) => path,
+                Err(e) => {
+                    checks.push(Check::error(CATEGORY, e.to_string(), "Remove all but one of cyrus.toml, cyrus.yaml and cyrus.json"));
+                    return;
+                }
+            };
+            match Project::load_from_file(&path) {
+                Ok(_) => checks.push(Check::ok(CATEGORY, format!("{} is valid", path.display()))),
+                Err(e) => checks.push(Check::error(
+                    CATEGORY,
+                    format!("{} is invalid: {:#}", path.display(), e),
+                    "Fix the reported key, or run `cyrus dev validate` for details",
+                )),
+            }
+        }
+        None => checks.push(Check::ok(CATEGORY, "Not inside a cyrus project")),
+    }
+}
+
+/// Project venvs built with a different interpreter than cyrus.toml asks for
+fn check_venvs(core: &CyrusCore, checks: &mut Vec<Check>) {
+    const CATEGORY: &str = "Virtual environments";
+
+    let Ok(Some((root, project))) = Project::discover() else {
+        return;
+    };
+    let Some(python) = python_toolchain(&project) else {
+        return;
+    };
+
+    let Some(info) = venv::VenvInfo::load(&venv::venv_path(&root)) else {
+        checks.push(Check::ok(CATEGORY, "No .venv in this project"));
+        return;
+    };
+
+    let home = info.home;
+    let version = info.cyrus_version.or(info.version);
+
+    if let Some(home) = home.as_ref().filter(|home| !home.exists()) {
+        checks.push(Check::error(
+            CATEGORY,
+            format!(".venv points at a missing interpreter ({})", home.display()),
+            "Run `cyrus run` and accept the rebuild prompt",
+        ));
+    } else if let Some(version) = version.filter(|v| !venv::version_matches(v, &python.version)) {
+        checks.push(Check::warning(
+            CATEGORY,
+            format!(".venv was built with Python {} but cyrus.toml pins {}", version, python.version),
+            "Run `cyrus run` and accept the rebuild prompt",
+        ));
+    } else if home.map(|home| !home.starts_with(&core.languages_dir)).unwrap_or(false) {
+        checks.push(Check::warning(
+            CATEGORY,
+            ".venv was not created with a cyrus-managed interpreter",
+            "Delete .venv and run `cyrus run` to rebuild it",
+        ));
+    } else {
+        checks.push(Check::ok(CATEGORY, format!(".venv matches Python {}", python.version)));
+    }
+}
+
+/// Plugins that fail to load
+async fn check_plugins(core: &CyrusCore, checks: &mut Vec<Check>) {
+    const CATEGORY: &str = "Plugins";
+    let directories = plugins::default_plugin_directories(&core.cyrus_dir);
+    let mut manager = PluginManager::new(directories.clone());
+    let mut found = 0;
+
+    for directory in directories.iter().filter(|d| d.exists()) {
+        let Ok(entries) = std::fs::read_dir(directory) else {
+            continue;
+        };
+
+        for path in entries.flatten().map(|e| e.path()) {
+            if !path.join("cyrus-plugin.toml").exists() {
+                continue;
+            }
+            found += 1;
+
+            match manager.load_plugin_from_directory(&path).await {
+                Ok(()) => checks.push(Check::ok(CATEGORY, format!("Loaded {}", path.display()))),
+                Err(e) => checks.push(Check::error(
+                    CATEGORY,
+                    format!("Failed to load {}: {}", path.display(), e),
+                    "Reinstall the plugin or remove its directory",
+                )),
+            }
+        }
+    }
+
+    if found == 0 {
+        checks.push(Check::ok(CATEGORY, "No plugins installed"));
+    }
+}
+
+fn installed_toolchains(core: &CyrusCore) -> Vec<(String, String, PathBuf)> {
+    let mut toolchains = Vec::new();
+    let Ok(languages) = std::fs::read_dir(&core.languages_dir) else {
+        return toolchains;
+    };
+
+    for language in languages.flatten().filter(|e| e.path().is_dir()) {
+        let Ok(versions) = std::fs::read_dir(language.path()) else {
+            continue;
+        };
+        for version in versions.flatten().filter(|e| e.path().is_dir()) {
+            toolchains.push((
+                language.file_name().to_string_lossy().into_owned(),
+                version.file_name().to_string_lossy().into_owned(),
+                version.path(),
+            ));
+        }
+    }
+
+    toolchains.sort();
+    toolchains
+}
@@ -0,0 +1,246 @@
+//! `cyrus exec`: run a command with one or more toolchains, no project needed
+//! src/commands/exec.rs
+
+use crate::config::GlobalConfig;
+use crate::core::{CyrusCore, Environment};
+use crate::error::CyrusError;
+use crate::languages;
+use crate::runtime::process::{self, ProcessOutcome};
+use super::ExecCommand;
+use anyhow::{Context, Result};
+use colored::*;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Marker written once a throwaway dependency environment is fully set up
+const READY_MARKER: &str = ".cyrus-ready";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolchainSpec {
+    pub language: String,
+    pub version: String,
+}
+
+impl ToolchainSpec {
+    /// Parse `python@3.9`, `node@20` or a bare `ruby` (global default, then language default)
+    pub fn parse(spec: &str) -> Result<Self> {
+        Self::parse_with(spec, &GlobalConfig::load_or_default()?.default_versions)
+    }
+
+    /// Like [`ToolchainSpec::parse`], with the global config's `default_versions` given
+    pub fn parse_with(spec: &str, default_versions: &HashMap<String, String>) -> Result<Self> {
+        let (name, version) = match spec.split_once('@') {
+            Some((name, version)) if !version.is_empty() => (name, Some(version)),
+            Some(_) => anyhow::bail!("Missing version in '{}'", spec),
+            None => (spec, None),
+        };
+
+        let language = languages::canonical_language_name(name)
+            .ok_or_else(|| CyrusError::UnsupportedLanguage { language: name.to_string() })?;
+
+        let version = match version {
+            Some(version) => version.to_string(),
+            None => match default_versions.get(language) {
+                Some(version) => version.clone(),
+                None => languages::get_language_handler(language)
+                    .map(|handler| handler.get_config().default_version.clone())
+                    .context("Unsupported language")?,
+            },
+        };
+
+        Ok(Self {
+            language: language.to_string(),
+            version,
+        })
+    }
+}
+
+pub async fn execute(cmd: ExecCommand, core: &CyrusCore) -> Result<()> {
+    let primary = ToolchainSpec::parse(&cmd.toolchain)?;
+    let extra = cmd.with.iter()
+        .map(|spec| ToolchainSpec::parse(spec))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut environment = Environment::new(primary.language.clone(), primary.version.clone(), String::new());
+    for spec in std::iter::once(&primary).chain(&extra) {
+        ensure_installed(core, spec).await?;
+        environment.merge(&core.toolchain_environment(&spec.language, &spec.version, ""));
+    }
+
+    if cmd.requirements.is_some() || !cmd.packages.is_empty() {
+        let deps = dependency_environment(core, &primary, cmd.requirements.as_deref(), &cmd.packages).await?;
+        // Dependency binaries win over the bare toolchain
+        let toolchain = environment;
+        environment = deps;
+        environment.merge(&toolchain);
+    }
+
+    let envs = environment.to_env_vars()?;
+    let (program, args) = cmd.command.split_first().context("No command given")?;
+
+    eprintln!("{} Running with {}: {}",
+              "🚀".blue(),
+              std::iter::once(&primary).chain(&extra)
+                  .map(|spec| format!("{}@{}", spec.language, spec.version))
+                  .collect::<Vec<_>>()
+                  .join(", ")
+                  .yellow(),
+              shell_words::join(&cmd.command).cyan());
+
+    let timeout = cmd.timeout.map(Duration::from_secs);
+    let outcome = process::run_foreground(program, args, &envs, timeout).await?;
+
+    if outcome == ProcessOutcome::TimedOut {
+        eprintln!("{} {} timed out after {}s",
+                  "⏱️".red(),
+                  program.yellow(),
+                  cmd.timeout.unwrap_or_default());
+    }
+
+    if !outcome.success() {
+        return Err(CyrusError::CommandFailed {
+            command: shell_words::join(&cmd.command),
+            code: Some(outcome.code()),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Install a toolchain if it isn't already, cleaning up after a failed attempt
+async fn ensure_installed(core: &CyrusCore, spec: &ToolchainSpec) -> Result<()> {
+    if core.is_language_installed(&spec.language, &spec.version) {
+        return Ok(());
+    }
+
+    let handler = languages::get_language_handler(&spec.language)
+        .context("Unsupported language")?;
+    let install_path = core.language_path(&spec.language, &spec.version);
+
+    eprintln!("{} {} {} is not installed, installing...",
+              "📦".blue(),
+              spec.language.yellow(),
+              spec.version.yellow());
+
+    if let Err(e) = handler.install(&spec.version, &install_path, None).await {
+        let _ = std::fs::remove_dir_all(&install_path);
+        return Err(e.context(format!("Failed to install {} {}", spec.language, spec.version)));
+    }
+
+    Ok(())
+}
+
+/// A cached venv / node_modules for the given requirements, keyed by their content
+async fn dependency_environment(
+    core: &CyrusCore,
+    toolchain: &ToolchainSpec,
+    requirements: Option<&Path>,
+    packages: &[String],
+) -> Result<Environment> {
+    let requirements_content = match requirements {
+        Some(path) => Some(std::fs::read(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?),
+        None => None,
+    };
+
+    let env_dir = dependency_cache_dir(&core.cyrus_dir, toolchain, requirements_content.as_deref(), packages);
+    let toolchain_env = core.toolchain_environment(&toolchain.language, &toolchain.version, "");
+    let setup_env = toolchain_env.to_env_vars()?;
+
+    let mut environment = Environment::new(toolchain.language.clone(), toolchain.version.clone(), String::new());
+
+    match toolchain.language.as_str() {
+        "python" => {
+            let venv_bin = env_dir.join(if cfg!(windows) { "Scripts" } else { "bin" });
+            if !env_dir.join(READY_MARKER).exists() {
+                eprintln!("{} Creating throwaway venv {}", "🐍".blue(), env_dir.display().to_string().dimmed());
+                let _ = std::fs::remove_dir_all(&env_dir);
+
+                let python = find_binary(&toolchain_env, &["python3", "python"])?;
+                run_setup(Command::new(&python).envs(&setup_env).arg("-m").arg("venv").arg(&env_dir)).await?;
+
+                let pip = venv_bin.join("pip");
+                if let Some(path) = requirements {
+                    run_setup(Command::new(&pip).envs(&setup_env).arg("install").arg("-r").arg(path)).await?;
+                }
+                if !packages.is_empty() {
+                    run_setup(Command::new(&pip).envs(&setup_env).arg("install").args(packages)).await?;
+                }
+                std::fs::write(env_dir.join(READY_MARKER), "")?;
+            }
+
+            environment.add_variable("VIRTUAL_ENV".to_string(), env_dir.to_string_lossy().into_owned());
+            environment.add_path(venv_bin);
+        },
+        "javascript" => {
+            if !env_dir.join(READY_MARKER).exists() {
+                eprintln!("{} Creating throwaway node_modules {}", "🟢".blue(), env_dir.display().to_string().dimmed());
+                let _ = std::fs::remove_dir_all(&env_dir);
+                std::fs::create_dir_all(&env_dir)?;
+
+                let npm = find_binary(&toolchain_env, &["npm"])?;
+                if let Some(content) = &requirements_content {
+                    std::fs::write(env_dir.join("package.json"), content)?;
+                    run_setup(Command::new(&npm).envs(&setup_env).arg("install").current_dir(&env_dir)).await?;
+                }
+                if !packages.is_empty() {
+                    run_setup(Command::new(&npm).envs(&setup_env).arg("install").arg("--no-save").args(packages).current_dir(&env_dir)).await?;
+                }
+                std::fs::write(env_dir.join(READY_MARKER), "")?;
+            }
+
+            let node_modules = env_dir.join("node_modules");
+            environment.add_variable("NODE_PATH".to_string(), node_modules.to_string_lossy().into_owned());
+            environment.add_path(node_modules.join(".bin"));
+        },
+        other => anyhow::bail!(
+            "Throwaway dependency environments are only supported for python and javascript, not {}",
+            other
+        ),
+    }
+
+    Ok(environment)
+}
+
+/// Where the environment for these requirements is cached; package order doesn't matter
+pub fn dependency_cache_dir(
+    cyrus_dir: &Path,
+    toolchain: &ToolchainSpec,
+    requirements: Option<&[u8]>,
+    packages: &[String],
+) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}@{}\0", toolchain.language, toolchain.version));
+    if let Some(content) = requirements {
+        hasher.update(content);
+    }
+    let mut sorted = packages.to_vec();
+    sorted.sort();
+    hasher.update(sorted.join("\0"));
+    let key: String = hasher.finalize().iter().take(12).map(|b| format!("{:02x}", b)).collect();
+
+    cyrus_dir.join("cache").join("exec")
+        .join(format!("{}-{}-{}", toolchain.language, toolchain.version, key))
+}
+
+fn find_binary(environment: &Environment, names: &[&str]) -> Result<PathBuf> {
+    names.iter()
+        .find_map(|name| environment.find_executable(name))
+        .with_context(|| format!(
+            "{} not found in {} {}",
+            names[0], environment.language, environment.version
+        ))
+}
+
+async fn run_setup(command: &mut Command) -> Result<()> {
+    let status = command.status().await
+        .with_context(|| format!("Failed to run {:?}", command))?;
+    if !status.success() {
+        anyhow::bail!("Setup command failed: {:?}", command);
+    }
+    Ok(())
+}
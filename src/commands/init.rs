@@ -1,6 +1,11 @@
 //! Enhanced init command implementation with extended language support
 //! src/commands/init.rs
 
+use crate::core::config_format;
+use crate::core::detect::{self, Inferred};
+use crate::core::manifest::{self, NativeManifest};
+use crate::core::toolchain::ToolchainSpec;
+use crate::core::version_files;
 use crate::core::{CyrusCore, Project};
 use crate::languages;
 use super::InitCommand;
@@ -10,13 +15,27 @@ use dialoguer::{Input, Select, MultiSelect, Confirm};
 use std::env;
 
 pub async fn execute(cmd: InitCommand, core: &CyrusCore) -> Result<()> {
+    if cmd.from_version_files {
+        return init_from_version_files(cmd, core).await;
+    }
+    if cmd.from_existing {
+        return init_from_existing(cmd, core).await;
+    }
+    if cmd.detect {
+        return init_detect(cmd, core).await;
+    }
+
+    let current_dir = env::current_dir()?;
+    if let Some(existing) = config_format::find(&current_dir)? {
+        anyhow::bail!("{} already exists", existing.display());
+    }
+
     println!("{}", "🎯 Initializing new project...".cyan().bold());
     
     // Get project name
     let project_name = if let Some(name) = cmd.name {
         name
     } else {
-        let current_dir = env::current_dir()?;
         let default_name = current_dir
             .file_name()
             .and_then(|n| n.to_str())
@@ -165,14 +184,14 @@ pub async fn execute(cmd: InitCommand, core: &CyrusCore) -> Result<()> {
     project.dependencies = dependencies;
     
     // Save project configuration
-    let current_dir = env::current_dir()?;
     let config_path = current_dir.join("cyrus.toml");
     
     project.save_to_file(&config_path)
         .context("Failed to save project configuration")?;
     
     // Setup language environment
-    handler.setup_environment(&current_dir).await
+    let toolchain = core.toolchain_environment(&language, &version, &package_manager);
+    handler.setup_environment(&current_dir, &toolchain).await
         .context("Failed to setup language environment")?;
     
     // Show summary
@@ -202,3 +221,239 @@ pub async fn execute(cmd: InitCommand, core: &CyrusCore) -> Result<()> {
     
     Ok(())
 }
+
+/// Write cyrus.toml from the version files in the current directory
+async fn init_from_version_files(cmd: InitCommand, core: &CyrusCore) -> Result<()> {
+    let current_dir = env::current_dir()?;
+    if let Some(existing) = config_format::find(&current_dir)? {
+        anyhow::bail!("{} already exists", existing.display());
+    }
+    let config_path = current_dir.join("cyrus.toml");
+
+    let pins = version_files::read_pins(&current_dir)?;
+    if pins.is_empty() {
+        anyhow::bail!(
+            "No version files found in {}. Looked for: {}",
+            current_dir.display(),
+            version_files::VERSION_FILES.join(", ")
+        );
+    }
+
+    println!("{}", "🔎 Found version files:".cyan().bold());
+    for pin in &pins {
+        println!("  {} {} {}",
+                 languages::get_language_display_name(&pin.language).blue(),
+                 pin.version.cyan(),
+                 format!("({})", pin.source.file_name().unwrap_or_default().to_string_lossy()).dimmed());
+    }
+
+    // The project's language: --language, the only pin, or the user's pick
+    let primary = match &cmd.language {
+        Some(language) => {
+            let language = languages::canonical_language_name(language)
+                .with_context(|| format!("Unsupported language: {}", language))?;
+            pins.iter()
+                .position(|pin| pin.language == language)
+                .with_context(|| format!("No version file pins {}", language))?
+        },
+        None if pins.len() == 1 => 0,
+        None => Select::new()
+            .with_prompt("Primary language")
+            .items(&pins.iter().map(|pin| format!("{} {}", pin.language, pin.version)).collect::<Vec<_>>())
+            .default(0)
+            .interact()?,
+    };
+    let pin = &pins[primary];
+
+    let name = cmd.name.unwrap_or_else(|| {
+        current_dir.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("my-project")
+            .to_string()
+    });
+
+    let handler = languages::get_language_handler(&pin.language)
+        .context("Unsupported language")?;
+    let config = handler.get_config();
+    let package_manager = match cmd.package_manager {
+        Some(pm) if !config.package_managers.contains(&pm) => anyhow::bail!(
+            "Unsupported package manager {} for {}. Available: {}",
+            pm, pin.language, config.package_managers.join(", ")
+        ),
+        Some(pm) => pm,
+        None => config.default_package_manager.clone(),
+    };
+
+    let mut project = Project::new(name, pin.language.clone(), pin.version.clone(), package_manager);
+    project.enable_aliases = true;
+    // The other pinned languages become [toolchains]
+    for (index, other) in pins.iter().enumerate() {
+        if index != primary {
+            project.toolchains.insert(other.language.clone(), ToolchainSpec {
+                version: other.version.clone(),
+                package_manager: None,
+            });
+        }
+    }
+    project.save_to_file(&config_path)
+        .context("Failed to save project configuration")?;
+
+    println!("\n{} Wrote {} for {} {}",
+             "✅".green(),
+             config_path.display().to_string().blue(),
+             pin.language.yellow(),
+             pin.version.cyan());
+
+    for (language, spec) in &project.toolchains {
+        println!("  {} also {} {}", "➕".blue(), language.yellow(), spec.version.cyan());
+    }
+
+    let missing = pins.iter().any(|pin| !core.is_language_installed(&pin.language, &pin.version));
+    if missing {
+        println!("  • Install the toolchains: {}", "cyrus install".cyan());
+    }
+
+    Ok(())
+}
+
+/// Write cyrus.toml from the native manifest in the current directory
+async fn init_from_existing(cmd: InitCommand, core: &CyrusCore) -> Result<()> {
+    let current_dir = env::current_dir()?;
+    if let Some(existing) = config_format::find(&current_dir)? {
+        anyhow::bail!("{} already exists; use `cyrus sync` to update it", existing.display());
+    }
+    let config_path = current_dir.join("cyrus.toml");
+
+    let native = match &cmd.language {
+        Some(language) => {
+            let language = languages::canonical_language_name(language)
+                .with_context(|| format!("Unsupported language: {}", language))?;
+            NativeManifest::for_language(&current_dir, language)?
+        },
+        None => NativeManifest::detect(&current_dir)?,
+    };
+    let Some(native) = native else {
+        let names: Vec<&str> = manifest::MANIFESTS.iter().map(|kind| kind.file_name()).collect();
+        anyhow::bail!("No native manifest found in {}. Looked for: {}", current_dir.display(), names.join(", "));
+    };
+
+    let language = native.kind.language();
+    let handler = languages::get_language_handler(language)
+        .context("Unsupported language")?;
+    let config = handler.get_config();
+
+    // Version: --version, a version file, the manifest's own constraint, or the default
+    let pinned = version_files::read_pins(&current_dir)?
+        .into_iter()
+        .find(|pin| pin.language == language)
+        .map(|pin| pin.version);
+    let version = cmd.version.clone()
+        .or(pinned)
+        .or_else(|| native.version.clone())
+        .unwrap_or_else(|| config.default_version.clone());
+
+    let package_manager = match cmd.package_manager {
+        Some(pm) if !config.package_managers.contains(&pm) => anyhow::bail!(
+            "Unsupported package manager {} for {}. Available: {}",
+            pm, language, config.package_managers.join(", ")
+        ),
+        Some(pm) => pm,
+        None => native.package_manager.clone()
+            .filter(|pm| config.package_managers.contains(pm))
+            .unwrap_or_else(|| config.default_package_manager.clone()),
+    };
+
+    // `@scope/web` and `vendor/web` are both just `web`
+    let name = cmd.name
+        .or_else(|| native.name.as_ref().and_then(|name| name.rsplit('/').next()).map(str::to_string))
+        .unwrap_or_else(|| {
+            current_dir.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("my-project")
+                .to_string()
+        });
+
+    // Scripts come from the manifest rather than the language defaults
+    let mut project = Project::new(name.clone(), language.to_string(), version.clone(), package_manager.clone());
+    project.scripts.clear();
+    project.custom_aliases.clear();
+    manifest::plan(&project, &native, None).apply_to(&mut project);
+
+    project.save_to_file(&config_path)
+        .context("Failed to save project configuration")?;
+
+    println!("{} Wrote {} from {}", "✅".green(), config_path.display().to_string().blue(), native.kind.file_name().yellow());
+    println!("  Name: {}", name.yellow());
+    println!("  Language: {} {}", languages::get_language_display_name(language).blue(), version.cyan());
+    println!("  Package Manager: {}", package_manager.magenta());
+    println!("  Scripts: {}", project.scripts.len());
+    println!("  Dependencies: {} (+{} dev)", project.dependencies.len(), project.dev_dependencies.len());
+
+    println!("\n{}", "🚀 Next Steps:".yellow().bold());
+    if !core.is_language_installed(language, &version) {
+        println!("  • Install the toolchain: {}", "cyrus install".cyan());
+    }
+    println!("  • Keep both files in step: {}", "cyrus sync".cyan());
+
+    Ok(())
+}
+
+/// Write cyrus.toml from everything detectable in the current directory, without prompting
+async fn init_detect(cmd: InitCommand, core: &CyrusCore) -> Result<()> {
+    let current_dir = env::current_dir()?;
+    if let Some(existing) = config_format::find(&current_dir)? {
+        anyhow::bail!("{} already exists", existing.display());
+    }
+    let config_path = current_dir.join("cyrus.toml");
+
+    let language = cmd.language.as_deref()
+        .map(|language| {
+            languages::canonical_language_name(language)
+                .with_context(|| format!("Unsupported language: {}", language))
+        })
+        .transpose()?;
+    let mut detection = detect::detect_as(&current_dir, language)?
+        .context("Could not detect a project here: no package.json, pyproject.toml, Cargo.toml, go.mod, pom.xml, composer.json or Gemfile")?;
+
+    // Explicit flags beat anything inferred
+    if let Some(name) = cmd.name {
+        detection.name = Inferred { value: name, reason: "argument".to_string() };
+    }
+    if let Some(version) = cmd.version {
+        detection.version = Inferred { value: version, reason: "--version".to_string() };
+    }
+    if let Some(pm) = cmd.package_manager {
+        detection.package_manager = Inferred { value: pm, reason: "--package-manager".to_string() };
+    }
+
+    let project = detection.to_project();
+    project.validate()?;
+    project.save_to_file(&config_path)
+        .context("Failed to save project configuration")?;
+
+    println!("{}", "🔎 Detected:".cyan().bold());
+    let row = |field: &str, inferred: &Inferred| {
+        println!("  {:<16} {:<20} {}", field, inferred.value.yellow(), format!("({})", inferred.reason).dimmed());
+    };
+    row("Name", &detection.name);
+    row("Language", &detection.language);
+    row("Version", &detection.version);
+    row("Package Manager", &detection.package_manager);
+
+    if !detection.scripts.is_empty() {
+        println!("  Scripts:");
+        for (name, command) in &detection.scripts {
+            println!("    {:<14} {:<30} {}", name.green(), command.value, format!("({})", command.reason).dimmed());
+        }
+    }
+    if !project.dependencies.is_empty() || !project.dev_dependencies.is_empty() {
+        println!("  Dependencies: {} (+{} dev)", project.dependencies.len(), project.dev_dependencies.len());
+    }
+
+    println!("\n{} Wrote {}", "✅".green(), config_path.display().to_string().blue());
+    if !core.is_language_installed(&project.language, &project.version) {
+        println!("  • Install the toolchain: {}", "cyrus install".cyan());
+    }
+
+    Ok(())
+}
@@ -1,17 +1,25 @@
 //! Install command implementation
 
-use crate::core::CyrusCore;
+use crate::core::lockfile::{self, LockedArtifact, LockedToolchain, Lockfile};
+use crate::core::{CyrusCore, Project};
 use crate::languages;
+use crate::utils::downloader;
+use crate::utils::platform::Platform;
 use super::InstallCommand;
 use anyhow::{Context, Result};
 use colored::*;
 use dialoguer::{Select, Confirm};
+use std::collections::{BTreeMap, HashMap};
 
 pub async fn execute(cmd: InstallCommand, core: &CyrusCore) -> Result<()> {
+    let Some(language_version) = &cmd.language_version else {
+        return install_from_lock(cmd.frozen, core).await;
+    };
+
     println!("{}", "🚀 Installing language...".cyan().bold());
     
     // Parse language and version
-    let (language, version) = parse_language_version(&cmd.language_version)?;
+    let (language, version) = parse_language_version(language_version)?;
     
     // Check if already installed
     if core.is_language_installed(&language, &version) {
@@ -63,8 +71,11 @@ pub async fn execute(cmd: InstallCommand, core: &CyrusCore) -> Result<()> {
              version.yellow(), 
              install_path);
     
-    handler.install(&version, &install_path).await
-        .context("Failed to install language")?;
+    let release = handler.resolve_release(&version).await?;
+    if let Err(e) = handler.install(&release, &install_path, None).await {
+        let _ = std::fs::remove_dir_all(&install_path);
+        return Err(e.context("Failed to install language"));
+    }
     
     println!("{} {} {} installed successfully!", 
              "✅".green(), 
@@ -86,3 +97,162 @@ fn parse_language_version(input: &str) -> Result<(String, String)> {
         anyhow::bail!("Invalid language version format: {}", input);
     }
 }
+
+/// Install exactly what cyrus.lock says, resolving and writing it first if needed
+async fn install_from_lock(frozen: bool, core: &CyrusCore) -> Result<()> {
+    let (project_root, project) = Project::discover()?
+        .context("No cyrus.toml or version file found. Run 'cyrus init' first, or name a language to install.")?;
+    let requests = lockfile::requested_toolchains(&project);
+
+    let existing = Lockfile::load(&project_root)?;
+    let problems = match &existing {
+        Some(lock) => lock.mismatches(&requests),
+        None => vec![format!("{} does not exist", lockfile::LOCK_FILE)],
+    };
+
+    if frozen && !problems.is_empty() {
+        anyhow::bail!(
+            "cyrus.lock is out of date (--frozen):\n  - {}\nRun `cyrus install` without --frozen to update it.",
+            problems.join("\n  - ")
+        );
+    }
+
+    let mut lock = match existing {
+        Some(lock) if problems.is_empty() => lock,
+        existing => {
+            println!("{} Resolving toolchains for {}", "🔒".blue(), lockfile::LOCK_FILE.yellow());
+            let existing = existing.unwrap_or_default();
+
+            // Look up the newest matching release for everything relock will replace
+            let mut releases = HashMap::new();
+            for request in &requests {
+                let kept = existing.toolchain(&request.language).is_some_and(|locked| {
+                    locked.requested == request.version && locked.package_manager == request.package_manager
+                });
+                if kept {
+                    continue;
+                }
+                let release = match languages::get_language_handler(&request.language) {
+                    Some(handler) => handler.resolve_release(&request.version).await
+                        .with_context(|| format!("Failed to resolve {} {}", request.language, request.version))?,
+                    None => request.version.clone(),
+                };
+                releases.insert(request.language.clone(), release);
+            }
+
+            existing.relock(&requests, |request| LockedToolchain {
+                language: request.language.clone(),
+                requested: request.version.clone(),
+                resolved: releases.get(&request.language).cloned().unwrap_or_else(|| request.version.clone()),
+                package_manager: request.package_manager.clone(),
+                package_manager_version: None,
+                artifacts: BTreeMap::new(),
+            })
+        }
+    };
+    let before = lock.clone();
+
+    for locked in &mut lock.toolchains {
+        install_locked(core, locked, frozen).await?;
+    }
+
+    if lock != before {
+        if frozen {
+            println!("{} cyrus.lock is missing details for this platform; not updating it (--frozen)", "💡".yellow());
+        } else {
+            lock.save(&project_root)?;
+            println!("{} Wrote {}", "🔒".green(), project_root.join(lockfile::LOCK_FILE).display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Install one locked toolchain, verifying the archive against the recorded digest
+async fn install_locked(core: &CyrusCore, locked: &mut LockedToolchain, frozen: bool) -> Result<()> {
+    let handler = languages::get_language_handler(&locked.language)
+        .with_context(|| format!("Unsupported language: {}", locked.language))?;
+    let target = Platform::target();
+    let install_path = core.language_path(&locked.language, &locked.requested);
+    let label = format!("{} {}", locked.language, locked.resolved);
+
+    let downloads = handler.download_url(&locked.resolved);
+    if !locked.artifacts.contains_key(&target) {
+        if let Some(url) = &downloads {
+            locked.artifacts.insert(target.clone(), LockedArtifact { url: url.clone(), sha256: None });
+        }
+    }
+
+    // --frozen installs only what the lock can vouch for
+    let artifact = locked.artifacts.get(&target).cloned();
+    let expected = artifact.as_ref().and_then(|artifact| artifact.sha256.clone());
+    if frozen && downloads.is_some() && expected.is_none() {
+        anyhow::bail!(
+            "cyrus.lock records no sha256 for {} on {} (--frozen). Run `cyrus install` without --frozen to record it.",
+            label, target
+        );
+    }
+
+    // Installs live under the requested version, so an earlier install may be another release
+    if core.is_language_installed(&locked.language, &locked.requested) {
+        match (&artifact, downloader::read_download_record(&install_path)) {
+            (Some(artifact), Some(record))
+                if record.url != artifact.url || expected.as_ref().is_some_and(|sha256| *sha256 != record.sha256) =>
+            {
+                println!("{} Installed {} differs from cyrus.lock, reinstalling", "🔄".blue(), locked.language.yellow());
+                std::fs::remove_dir_all(&install_path)
+                    .with_context(|| format!("Failed to remove {}", install_path.display()))?;
+            },
+            (Some(_), None) if frozen && expected.is_some() => anyhow::bail!(
+                "{} has no download record to check against cyrus.lock (--frozen). Reinstall with `cyrus remove {}{}`",
+                label, locked.language, locked.requested
+            ),
+            _ => println!("{} {} is already installed", "✅".green(), label.yellow()),
+        }
+    }
+
+    if !core.is_language_installed(&locked.language, &locked.requested) {
+        println!("{} Installing {} (locked)", "📦".blue(), label.yellow());
+        if let Err(e) = handler.install(&locked.resolved, &install_path, expected.as_deref()).await {
+            let _ = std::fs::remove_dir_all(&install_path);
+            return Err(e.context(format!("Failed to install {}", label)));
+        }
+    }
+
+    // First install on this target: record the digest for everyone else
+    if let (Some(artifact), Some(record)) = (locked.artifacts.get_mut(&target), downloader::read_download_record(&install_path)) {
+        if artifact.sha256.is_none() && record.url == artifact.url {
+            artifact.sha256 = Some(record.sha256);
+        }
+    }
+
+    let toolchain = core.toolchain_environment(&locked.language, &locked.requested, &locked.package_manager);
+    if let Some(version) = package_manager_version(&locked.package_manager, &toolchain) {
+        match &locked.package_manager_version {
+            Some(expected) if *expected != version => println!(
+                "{} {} is {} but cyrus.lock records {}",
+                "⚠️".yellow(), locked.package_manager, version, expected
+            ),
+            Some(_) => {},
+            None => locked.package_manager_version = Some(version),
+        }
+    }
+
+    Ok(())
+}
+
+/// `<pm> --version` within the toolchain, e.g. `pip 23.2.1 from ...` gives `23.2.1`
+fn package_manager_version(package_manager: &str, toolchain: &crate::core::Environment) -> Option<String> {
+    let program = toolchain.find_executable(package_manager)?;
+    let output = std::process::Command::new(program)
+        .arg("--version")
+        .envs(toolchain.to_env_vars().ok()?)
+        .output()
+        .ok()?;
+
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .map(|word| word.trim_start_matches('v').trim_matches(|c: char| !c.is_ascii_alphanumeric() && c != '.'))
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+        .map(str::to_string)
+}
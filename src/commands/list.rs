@@ -1,5 +1,6 @@
 //! List command implementation
 
+use crate::core::external;
 use crate::core::CyrusCore;
 use crate::languages;
 use super::ListCommand;
@@ -28,9 +29,16 @@ pub async fn execute(cmd: ListCommand, core: &CyrusCore) -> Result<()> {
             // List versions for this language
             for version_entry in fs::read_dir(entry.path())? {
                 let version_entry = version_entry?;
-                if version_entry.file_type()?.is_dir() {
-                    let version = version_entry.file_name().to_string_lossy();
-                    println!("  {} {}", "📦".green(), version.cyan());
+                // Follows symlinks, so installs adopted by `cyrus migrate` show up too
+                if version_entry.path().is_dir() {
+                    let version = version_entry.file_name().to_string_lossy().into_owned();
+                    match external::read_receipt(&core.cyrus_dir, &language_name, &version) {
+                        Some(receipt) => println!("  {} {} {}",
+                                                  "📦".green(),
+                                                  version.cyan(),
+                                                  format!("(external: {})", receipt.tool).dimmed()),
+                        None => println!("  {} {}", "📦".green(), version.cyan()),
+                    }
                     found_any = true;
                 }
             }
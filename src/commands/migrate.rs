@@ -0,0 +1,107 @@
+//! `cyrus migrate`: adopt toolchains from another version manager
+//! src/commands/migrate.rs
+
+use crate::config::GlobalConfig;
+use crate::core::external::{self, AdoptMode, ExternalInstall};
+use crate::core::CyrusCore;
+use super::MigrateCommand;
+use anyhow::Result;
+use colored::*;
+use std::collections::HashSet;
+
+pub async fn execute(cmd: MigrateCommand, core: &CyrusCore) -> Result<()> {
+    let tool = cmd.from;
+    let root = tool.root(&core.home_dir);
+    if !root.exists() {
+        anyhow::bail!("No {} installation found at {}", tool, root.display());
+    }
+
+    let mode = if cmd.copy { AdoptMode::Copy } else { AdoptMode::Symlink };
+    let installs = tool.installs(&core.home_dir);
+
+    println!("{} Adopting {} toolchains from {}",
+             "🚚".blue(),
+             tool.to_string().yellow(),
+             root.display().to_string().dimmed());
+
+    if installs.is_empty() {
+        println!("  {} No usable installs found", "⚠️".yellow());
+    }
+
+    let mut adopted = 0;
+    for install in &installs {
+        if register(core, tool, install, &install.version, mode, cmd.dry_run)? {
+            adopted += 1;
+        }
+    }
+
+    // Projects usually pin `3.11` or `20`: point those at the newest matching install.
+    // Claims are tracked here too, so a dry run reports what a real run would register.
+    let mut claimed = HashSet::new();
+    for install in installs.iter().rev() {
+        let Some(short) = external::short_version(&install.language, &install.version) else {
+            continue;
+        };
+        if !claimed.insert((install.language.clone(), short.clone())) {
+            continue;
+        }
+        if !core.is_language_installed(&install.language, &short) {
+            register(core, tool, install, &short, mode, cmd.dry_run)?;
+        }
+    }
+
+    let globals = tool.global_versions(&core.home_dir);
+    if !globals.is_empty() {
+        let config_path = GlobalConfig::config_path()?;
+        let mut config = GlobalConfig::load_or_default()?;
+
+        println!("\n{} Global versions:", "🌐".blue());
+        for (language, version) in globals {
+            println!("  {} {}", language.yellow(), version.cyan());
+            config.default_versions.insert(language, version);
+        }
+
+        if !cmd.dry_run {
+            if let Some(parent) = config_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            config.save_to_file(&config_path)?;
+        }
+    }
+
+    println!("\n{} {} toolchain(s) {}from {}",
+             "✅".green(),
+             adopted,
+             if cmd.dry_run { "would be adopted " } else { "adopted " },
+             tool);
+    if mode == AdoptMode::Symlink && adopted > 0 {
+        println!("{} Installs are symlinked; keep {} around or re-run with --copy before removing it",
+                 "💡".yellow(), tool);
+    }
+
+    Ok(())
+}
+
+/// Register `install` as `language/version`; false when that slot is already taken
+fn register(
+    core: &CyrusCore,
+    tool: external::ExternalTool,
+    install: &ExternalInstall,
+    version: &str,
+    mode: AdoptMode,
+    dry_run: bool,
+) -> Result<bool> {
+    let target = core.language_path(&install.language, version);
+    let label = format!("{} {}", install.language, version);
+
+    if target.exists() || target.is_symlink() {
+        println!("  {} {} {}", "⏭️".dimmed(), label, "already installed".dimmed());
+        return Ok(false);
+    }
+
+    if !dry_run {
+        external::adopt(&core.cyrus_dir, tool, install, version, &target, mode)?;
+    }
+    println!("  {} {} → {}", "📦".green(), label.yellow(), install.path.display().to_string().dimmed());
+    Ok(true)
+}
@@ -3,18 +3,25 @@
 pub mod install;
 pub mod init;
 pub mod run;
+pub mod exec;
+pub mod up;
 pub mod list;
 pub mod update;
 pub mod remove;
 pub mod config;
+pub mod doctor;
+pub mod migrate;
+pub mod add;
+pub mod sync;
+pub mod validate;
 pub mod version;
 
 use clap::Args;
 
 #[derive(Args)]
 pub struct InstallCommand {
-    /// Language and version to install (e.g., python3.11)
-    pub language_version: String,
+    /// Language and version to install (e.g., python3.11); omit to install from cyrus.lock
+    pub language_version: Option<String>,
     
     /// Package manager to use
     #[arg(short, long)]
@@ -23,6 +30,10 @@ pub struct InstallCommand {
     /// Use default configuration without prompts
     #[arg(short, long)]
     pub default: bool,
+
+    /// Fail if cyrus.toml and cyrus.lock disagree instead of updating the lock
+    #[arg(long, conflicts_with = "language_version")]
+    pub frozen: bool,
 }
 
 #[derive(Args)]
@@ -41,6 +52,18 @@ pub struct InitCommand {
     /// Package manager
     #[arg(short, long)]
     pub package_manager: Option<String>,
+
+    /// Convert .nvmrc, .python-version, .tool-versions and similar files into cyrus.toml
+    #[arg(long, conflicts_with = "version")]
+    pub from_version_files: bool,
+
+    /// Build cyrus.toml from an existing package.json, pyproject.toml, Cargo.toml, go.mod, composer.json or Gemfile
+    #[arg(long, conflicts_with = "from_version_files")]
+    pub from_existing: bool,
+
+    /// Infer language, version, package manager and scripts from the repository, without prompts
+    #[arg(long, conflicts_with_all = ["from_version_files", "from_existing"])]
+    pub detect: bool,
 }
 
 #[derive(Args)]
@@ -48,8 +71,131 @@ pub struct RunCommand {
     /// Command to run
     pub command: String,
     
-    /// Arguments for the command
+    /// Arguments for the command (passed through verbatim)
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     pub args: Vec<String>,
+    
+    /// Kill the command if it runs longer than this many seconds (exit code 124)
+    #[arg(long, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+    
+    /// Re-run whenever project files change, restarting the command if it is still running
+    #[arg(short, long)]
+    pub watch: bool,
+    
+    /// Only react to changes matching this glob (repeatable; implies --watch)
+    #[arg(long, value_name = "GLOB")]
+    pub glob: Vec<String>,
+    
+    /// Clear the screen before each run in watch mode
+    #[arg(long)]
+    pub clear: bool,
+}
+
+#[derive(Args)]
+pub struct ExecCommand {
+    /// Toolchain to run with, e.g. python@3.9 or node@20 (version defaults to the language default)
+    pub toolchain: String,
+    
+    /// Additional toolchains to put on PATH (repeatable)
+    #[arg(long = "with", value_name = "TOOLCHAIN")]
+    pub with: Vec<String>,
+    
+    /// Dependencies file for a cached throwaway environment (requirements.txt or package.json)
+    #[arg(short, long, value_name = "FILE")]
+    pub requirements: Option<std::path::PathBuf>,
+    
+    /// Packages for a cached throwaway environment (repeatable)
+    #[arg(long = "pkg", value_name = "PACKAGE")]
+    pub packages: Vec<String>,
+    
+    /// Kill the command if it runs longer than this many seconds (exit code 124)
+    #[arg(long, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+    
+    /// Command to run, after `--`
+    #[arg(last = true, required = true)]
+    pub command: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct UpCommand {
+    /// Only start these processes (default: all)
+    pub processes: Vec<String>,
+    
+    /// Start the processes of every workspace member, even from inside a member
+    #[arg(short, long)]
+    pub workspace: bool,
+}
+
+#[derive(Args)]
+pub struct DoctorCommand {
+    /// Output results as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct ValidateCommand {
+    /// Output findings as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Treat warnings as failures (exit code 2)
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(Args)]
+pub struct MigrateCommand {
+    /// Version manager to adopt installs from: asdf, pyenv, nvm, rbenv or sdkman
+    #[arg(long)]
+    pub from: crate::core::external::ExternalTool,
+    
+    /// Copy installs instead of symlinking them (survives uninstalling the old tool)
+    #[arg(long)]
+    pub copy: bool,
+    
+    /// Show what would be registered without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args)]
+pub struct AddCommand {
+    /// Packages to add, optionally pinned as name@version
+    #[arg(required = true)]
+    pub packages: Vec<String>,
+    
+    /// Add as development dependencies
+    #[arg(short = 'D', long)]
+    pub dev: bool,
+}
+
+#[derive(Args)]
+pub struct RmCommand {
+    /// Packages to remove
+    #[arg(required = true)]
+    pub packages: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct SyncCommand {
+    /// Only copy scripts and dependencies from the native manifest into cyrus.toml
+    #[arg(long, conflicts_with = "export")]
+    pub import: bool,
+    
+    /// Only copy cyrus.toml scripts into the native manifest
+    #[arg(long)]
+    pub export: bool,
+    
+    /// Side that wins when a script differs: cyrus or native
+    #[arg(long)]
+    pub prefer: Option<crate::core::manifest::Prefer>,
+    
+    /// Show what would change without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 #[derive(Args)]
@@ -73,9 +219,48 @@ pub struct RemoveCommand {
 
 #[derive(Args)]
 pub struct ConfigCommand {
-    /// Show global configuration
-    #[arg(short, long)]
+    /// Show or edit the global configuration instead of the project's
+    #[arg(short, long, global = true)]
     pub global: bool,
+
+    #[command(subcommand)]
+    pub action: Option<ConfigAction>,
+}
+
+#[derive(clap::Subcommand)]
+pub enum ConfigAction {
+    /// Show the project configuration (the default)
+    Show {
+        /// Apply `extends` and show where each key came from
+        #[arg(long)]
+        merged: bool,
+    },
+    /// Upgrade cyrus.toml to the current schema, keeping a backup
+    Migrate {
+        /// Only report whether a migration is needed; exits non-zero if it is
+        #[arg(long)]
+        check: bool,
+    },
+    /// Print the value at a dotted key, e.g. `environment.PORT`
+    Get {
+        key: String,
+    },
+    /// Set a dotted key, keeping the file's comments and layout
+    Set {
+        key: String,
+        /// TOML literal (`3000`, `true`, `["a"]`); anything else is taken as a string
+        value: String,
+    },
+    /// Remove a dotted key
+    Unset {
+        key: String,
+    },
+    /// Rewrite the project file as cyrus.toml, cyrus.yaml or cyrus.json
+    Convert {
+        /// Target format: toml, yaml or json
+        #[arg(long)]
+        to: crate::core::config_format::ConfigFormat,
+    },
 }
 
 #[derive(Args)]
@@ -1,5 +1,6 @@
 //! Remove command implementation
 
+use crate::core::external::{self, AdoptMode};
 use crate::core::CyrusCore;
 use super::RemoveCommand;
 use anyhow::{Context, Result};
@@ -30,10 +31,27 @@ pub async fn execute(cmd: RemoveCommand, core: &CyrusCore) -> Result<()> {
         return Ok(());
     }
     
-    // Remove the installation
+    // Remove the installation; adopted symlinks lose only the link, never the original
     let install_path = core.language_path(&language, &version);
-    fs::remove_dir_all(&install_path)
-        .context("Failed to remove language installation")?;
+    let receipt = external::read_receipt(&core.cyrus_dir, &language, &version);
+    if install_path.is_symlink() {
+        fs::remove_file(&install_path)
+            .or_else(|_| fs::remove_dir(&install_path))
+            .context("Failed to remove language installation")?;
+    } else {
+        fs::remove_dir_all(&install_path)
+            .context("Failed to remove language installation")?;
+    }
+    
+    if let Some(receipt) = receipt {
+        let _ = fs::remove_file(external::receipt_path(&core.cyrus_dir, &language, &version));
+        if receipt.mode == AdoptMode::Symlink {
+            println!("{} The {} install at {} was left untouched",
+                     "💡".yellow(),
+                     receipt.tool,
+                     receipt.source.display());
+        }
+    }
     
     println!("{} {} {} removed successfully!", 
              "✅".green(), 
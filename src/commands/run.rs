@@ -1,63 +1,375 @@
 //! Enhanced run command implementation with alias support
 //! src/commands/run.rs
 
-use crate::core::{CyrusCore, Project};
-use crate::languages;
+use crate::config::GlobalConfig;
+use crate::core::lockfile::{self, ToolchainRequest};
+use crate::core::script::ScriptStep;
+use crate::core::task::{self, TaskState};
+use crate::core::venv::{self, VenvStatus};
+use crate::core::{alias, CyrusCore, Project};
+use crate::error::CyrusError;
+use crate::languages::{self, python::PythonHandler};
+use crate::runtime::process::{self, ProcessOutcome};
+use crate::runtime::watch::FileWatcher;
 use super::RunCommand;
 use anyhow::{Context, Result};
 use colored::*;
+use dialoguer::Confirm;
+use std::collections::HashMap;
 use std::env;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 pub async fn execute(cmd: RunCommand, core: &CyrusCore) -> Result<()> {
-    // Find project root
-    let project_root = Project::find_project_root()
-        .context("No cyrus.toml found. Run 'cyrus init' first.")?;
+    // Find the project: cyrus.toml, or version files such as .nvmrc and .python-version
+    let (project_root, project) = Project::discover()?
+        .context("No cyrus.toml or version file (.nvmrc, .python-version, ...) found. Run 'cyrus init' first.")?;
     
     env::set_current_dir(&project_root)?;
     
-    // Load project configuration
-    let project = Project::load_from_file(project_root.join("cyrus.toml"))?;
+    // Every toolchain must be one cyrus manages, and installed
+    for toolchain in lockfile::requested_toolchains(&project) {
+        if !languages::is_language_supported(&toolchain.language) {
+            return Err(CyrusError::UnsupportedLanguage {
+                language: toolchain.language,
+            }
+            .into());
+        }
+        
+        if !core.is_language_installed(&toolchain.language, &toolchain.version) {
+            return Err(CyrusError::LanguageNotInstalled {
+                language: toolchain.language,
+                version: toolchain.version,
+            }
+            .into());
+        }
+    }
+
+    // Export project environment, resolving secret:// references from the vault
+    let environment = resolve_project_environment(core, &project_root, &project.environment)?;
+    for (key, value) in &environment {
+        env::set_var(key, value);
+    }
+
+    if let Some(python) = python_toolchain(&project) {
+        ensure_python_venv(core, &project, &python, &project_root)?;
+    }
+
+    if cmd.watch || !cmd.glob.is_empty() {
+        return watch(&cmd, core, project, &project_root).await;
+    }
     
-    // Get language handler
-    let handler = languages::get_language_handler(&project.language)
-        .context("Unsupported language in project")?;
+    run_target(&cmd, core, &project, &project_root, None).await
+}
+
+/// Run the requested task, alias, script or command once
+async fn run_target(
+    cmd: &RunCommand,
+    core: &CyrusCore,
+    project: &Project,
+    project_root: &Path,
+    stop: Option<StopSignal>,
+) -> Result<()> {
+    // An optional timeout covers the whole run, hooks, sequences and dependencies included
+    let deadline = cmd.timeout.map(|secs| Instant::now() + Duration::from_secs(secs));
     
-    // Check if language is installed
-    if !core.is_language_installed(&project.language, &project.version) {
-        println!("{} {} {} is not installed. Run 'cyrus install {}{}'", 
-                 "❌".red(),
-                 project.language.yellow(),
-                 project.version.yellow(),
-                 project.language,
-                 project.version);
-        return Ok(());
+    // `[tasks]` run with their dependencies and are skipped when up to date
+    if project.tasks.contains_key(&cmd.command) {
+        return run_tasks(cmd, core, project, project_root, deadline, stop).await;
     }
+    
+    // Expand aliases, scripts (with pre/post hooks and references) and package manager commands
+    let inherited = inherited_alias_layers();
+    let steps = project.plan_command(&cmd.command, &cmd.args, &inherited)?;
+    
+    let envs = toolchain_env_vars(core, project, project_root)?;
+    run_steps(cmd, &steps, &envs, deadline, stop).await
+}
+
+/// Profile and global alias layers. A broken global config or unknown profile
+/// only loses those aliases; project scripts and aliases still run.
+fn inherited_alias_layers() -> Vec<alias::AliasLayer> {
+    match GlobalConfig::load_or_default().and_then(|config| config.alias_layers()) {
+        Ok(layers) => layers,
+        Err(e) => {
+            eprintln!("{} Ignoring profile and global aliases: {}", "⚠️".yellow(), e);
+            Vec::new()
+        }
+    }
+}
+
+/// Receiver that flips to `true` when the current run should stop
+type StopSignal = tokio::sync::watch::Receiver<bool>;
 
-    // Resolve command through project aliases and package manager integration
-    let (resolved_command, resolved_args) = project.resolve_command(&cmd.command, &cmd.args);
+/// Re-run on file changes until interrupted
+async fn watch(
+    cmd: &RunCommand,
+    core: &CyrusCore,
+    mut project: Project,
+    project_root: &Path,
+) -> Result<()> {
+    let mut watcher = FileWatcher::new(project_root, &cmd.glob)?;
     
-    // Show what we're actually executing
-    if resolved_command != cmd.command || resolved_args != cmd.args {
-        println!("{} Aliased: {} {} → {} {}", 
-                 "🔗".blue(),
-                 cmd.command.yellow(),
-                 cmd.args.join(" ").cyan(),
-                 resolved_command.yellow(),
-                 resolved_args.join(" ").cyan());
+    loop {
+        if cmd.clear {
+            // Clear screen and scrollback, move the cursor home
+            eprint!("\x1B[2J\x1B[3J\x1B[H");
+        }
+        
+        let changes = {
+            let (stop_tx, stop_rx) = tokio::sync::watch::channel(false);
+            let run = run_target(cmd, core, &project, project_root, Some(stop_rx));
+            tokio::pin!(run);
+        
+            tokio::select! {
+                result = &mut run => {
+                    match result {
+                        Ok(()) => eprintln!("{} {}", "✅".green(), "Finished, waiting for changes...".dimmed()),
+                        Err(e) if is_interrupt(&e) => return Err(e),
+                        Err(e) => eprintln!("{} {} {}", "❌".red(), e, "(waiting for changes...)".dimmed()),
+                    }
+                
+                    tokio::select! {
+                        changes = watcher.changed() => changes,
+                        _ = tokio::signal::ctrl_c() => return Ok(()),
+                    }
+                }
+                changes = watcher.changed() => {
+                    // Still running (a dev server, a slow test suite): stop it first
+                    let _ = stop_tx.send(true);
+                    let _ = run.await;
+                    changes
+                }
+            }
+        };
+        
+        eprintln!("{} {} changed, restarting {}",
+                  "🔄".blue(),
+                  describe_changes(&changes).yellow(),
+                  cmd.command.cyan());
+        
+        // Pick up edits to cyrus.toml (or the version files) itself
+        match Project::load_from_dir(project_root) {
+            Ok(reloaded) => project = reloaded,
+            Err(e) => eprintln!("{} {:#}", "⚠️".yellow(), e),
+        }
+    }
+}
+
+fn describe_changes(changes: &[std::path::PathBuf]) -> String {
+    match changes {
+        [single] => single.display().to_string(),
+        [first, rest @ ..] => format!("{} (+{} more)", first.display(), rest.len()),
+        [] => "files".to_string(),
     }
+}
+
+/// Whether a run ended because the user hit Ctrl-C
+fn is_interrupt(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<CyrusError>(),
+        Some(CyrusError::CommandFailed { code: Some(130), .. })
+    )
+}
+
+async fn run_tasks(
+    cmd: &RunCommand,
+    core: &CyrusCore,
+    project: &Project,
+    project_root: &Path,
+    deadline: Option<Instant>,
+    stop: Option<StopSignal>,
+) -> Result<()> {
+    let order = task::execution_order(&project.tasks, &cmd.command)?;
+    let mut state = TaskState::load(project_root);
     
-    println!("{} Running: {} {}", 
-             "🚀".blue(), 
-             resolved_command.yellow(), 
-             resolved_args.join(" ").cyan());
+    for name in &order {
+        let task = &project.tasks[name];
+        
+        // Extra arguments only go to the task that was asked for
+        let args: &[String] = if name == &cmd.command { &cmd.args } else { &[] };
+        let steps = project.plan_task(name, task, args)?;
+        
+        let command = steps.iter()
+            .map(|step| step.invocation.display())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let fingerprint = task::fingerprint(project_root, task, &command)?;
+        
+        if !task.inputs.is_empty()
+            && state.is_fresh(name, &fingerprint)
+            && task::outputs_present(project_root, task)?
+        {
+            eprintln!("{} {} {}", "✅".green(), name.yellow(), "up to date".dimmed());
+            continue;
+        }
+        
+        let mut envs = toolchain_env_vars(core, project, project_root)?;
+        envs.extend(resolve_project_environment(core, project_root, &task.env)?);
+        if let Err(e) = run_steps(cmd, &steps, &envs, deadline, stop.clone()).await {
+            state.invalidate(name);
+            state.save(project_root)?;
+            return Err(e);
+        }
+        
+        state.record(name, fingerprint);
+        state.save(project_root)?;
+    }
     
-    // Execute command with project environment
-    handler.run_command(&resolved_command, &resolved_args).await
-        .context("Failed to execute command")?;
+    Ok(())
+}
+
+async fn run_steps(
+    cmd: &RunCommand,
+    steps: &[ScriptStep],
+    envs: &HashMap<String, String>,
+    deadline: Option<Instant>,
+    stop: Option<StopSignal>,
+) -> Result<()> {
+    for step in steps {
+        let (program, args) = step.invocation.to_program_args();
+        
+        // Progress goes to stderr so the child's stdout stays clean
+        match &step.script {
+            Some(script) => eprintln!("{} {} → {}", 
+                                      "🚀".blue(), 
+                                      script.yellow(), 
+                                      step.invocation.display().cyan()),
+            None => eprintln!("{} Running: {}", 
+                              "🚀".blue(), 
+                              step.invocation.display().cyan()),
+        }
+        
+        let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+        let outcome = process::run_until(&program, &args, envs, remaining, stop_requested(stop.clone())).await?;
+        
+        if outcome == ProcessOutcome::Stopped {
+            return Err(CyrusError::CommandFailed {
+                command: step.invocation.display(),
+                code: Some(outcome.code()),
+            }
+            .into());
+        }
+        
+        if outcome == ProcessOutcome::TimedOut {
+            eprintln!("{} {} timed out after {}s", 
+                      "⏱️".red(), 
+                      cmd.command.yellow(), 
+                      cmd.timeout.unwrap_or_default());
+        }
+        
+        if !outcome.success() {
+            return Err(CyrusError::CommandFailed {
+                command: step.invocation.display(),
+                code: Some(outcome.code()),
+            }
+            .into());
+        }
+    }
     
     Ok(())
 }
 
+/// PATH and variables for all of the project's toolchains (and its .venv), shared by run and up
+pub fn toolchain_env_vars(core: &CyrusCore, project: &Project, project_root: &Path) -> Result<HashMap<String, String>> {
+    // The primary toolchain keeps PATH precedence over `[toolchains]`
+    let mut environment = core.toolchain_environment(&project.language, &project.version, &project.package_manager);
+    for toolchain in lockfile::requested_toolchains(project).iter().skip(1) {
+        environment.merge(&core.toolchain_environment(&toolchain.language, &toolchain.version, &toolchain.package_manager));
+    }
+    
+    let venv_path = venv::venv_path(project_root);
+    if python_toolchain(project).is_some() && venv_path.join("pyvenv.cfg").exists() {
+        venv::activate(&mut environment, &venv_path);
+    }
+    
+    environment.to_env_vars()
+}
+
+/// The project's Python toolchain, primary or from `[toolchains]`
+pub fn python_toolchain(project: &Project) -> Option<ToolchainRequest> {
+    lockfile::requested_toolchains(project)
+        .into_iter()
+        .find(|toolchain| toolchain.language == "python")
+}
+
+/// Create the project's .venv with the cyrus interpreter, offering to rebuild a stale one
+pub fn ensure_python_venv(core: &CyrusCore, project: &Project, python: &ToolchainRequest, project_root: &Path) -> Result<()> {
+    let rebuild = match venv::status(project_root, &python.version) {
+        VenvStatus::Current => return Ok(()),
+        VenvStatus::Missing => false,
+        VenvStatus::Stale { reason } => {
+            eprintln!("{} .venv is stale: {}", "⚠️".yellow(), reason);
+            
+            let confirmed = std::io::stdin().is_terminal()
+                && Confirm::new()
+                    .with_prompt(format!("Rebuild .venv with Python {}?", python.version))
+                    .default(true)
+                    .interact()?;
+            if !confirmed {
+                eprintln!("{} Keeping the existing .venv", "💡".yellow());
+                return Ok(());
+            }
+            true
+        },
+    };
+    
+    let venv_path = venv::venv_path(project_root);
+    if rebuild {
+        std::fs::remove_dir_all(&venv_path)
+            .with_context(|| format!("Failed to remove {}", venv_path.display()))?;
+    }
+    
+    let toolchain = core.toolchain_environment(&python.language, &python.version, &python.package_manager);
+    // cyrus.toml `dependencies` belong to the primary language
+    let declared: &[String] = if project.language == "python" { &project.dependencies } else { &[] };
+    let handler = PythonHandler::new();
+    handler.create_venv(project_root, &toolchain)?;
+    if let Err(e) = handler.install_dependencies(project_root, &toolchain, declared) {
+        eprintln!("{} {:#}", "⚠️".yellow(), e);
+    }
+    
+    Ok(())
+}
+
+/// Completes once a stop is requested; never completes without a signal
+async fn stop_requested(stop: Option<StopSignal>) {
+    let Some(mut stop) = stop else {
+        return std::future::pending().await;
+    };
+    
+    while !*stop.borrow() {
+        if stop.changed().await.is_err() {
+            return std::future::pending().await;
+        }
+    }
+}
+
+#[cfg(feature = "security-audit")]
+pub fn resolve_project_environment(
+    core: &CyrusCore,
+    project_root: &Path,
+    environment: &HashMap<String, String>,
+) -> Result<HashMap<String, String>> {
+    Ok(crate::secrets::resolve_environment(&core.cyrus_dir, Some(project_root), environment)?)
+}
+
+#[cfg(not(feature = "security-audit"))]
+pub fn resolve_project_environment(
+    _core: &CyrusCore,
+    _project_root: &Path,
+    environment: &HashMap<String, String>,
+) -> Result<HashMap<String, String>> {
+    if let Some((key, _)) = environment.iter().find(|(_, v)| v.starts_with("secret://")) {
+        anyhow::bail!(
+            "${} references a secret, but this build of cyrus lacks the 'security-audit' feature",
+            key
+        );
+    }
+    Ok(environment.clone())
+}
+
 // Add a new command for managing aliases
 #[derive(clap::Args)]
 pub struct AliasCommand {
@@ -68,33 +380,110 @@ pub struct AliasCommand {
 #[derive(clap::Subcommand)]
 pub enum AliasAction {
     /// List all aliases
-    List,
+    List {
+        /// Show project, profile and global aliases with their source and shadowing
+        #[arg(long)]
+        resolved: bool,
+    },
     /// Add a new alias
     Add {
         /// Alias name
         alias: String,
         /// Command to alias to
         command: String,
+        /// Add to the global config instead of cyrus.toml
+        #[arg(short, long)]
+        global: bool,
     },
     /// Remove an alias
     Remove {
         /// Alias name to remove
         alias: String,
+        /// Remove from the global config instead of cyrus.toml
+        #[arg(short, long)]
+        global: bool,
     },
     /// Toggle alias functionality
     Toggle,
+    /// Show what an alias expands to without running it
+    Expand {
+        /// Alias name
+        alias: String,
+        /// Arguments to substitute for $1, $2, $@
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
 }
 
 pub async fn execute_alias(cmd: AliasCommand, _core: &CyrusCore) -> Result<()> {
+    // Global aliases don't need a project
+    match &cmd.action {
+        AliasAction::Add { alias, command, global: true } => {
+            let config_path = GlobalConfig::config_path()?;
+            let mut config = GlobalConfig::load_or_default()?;
+            config.add_global_alias(alias.clone(), command.clone());
+            if let Some(parent) = config_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            config.save_to_file(&config_path)?;
+
+            println!("{} Added global alias: {} → {}",
+                     "✅".green(),
+                     alias.blue(),
+                     command.cyan());
+            return Ok(());
+        },
+        AliasAction::Remove { alias, global: true } => {
+            let config_path = GlobalConfig::config_path()?;
+            let mut config = GlobalConfig::load_or_default()?;
+            if config.remove_global_alias(alias) {
+                config.save_to_file(&config_path)?;
+                println!("{} Removed global alias: {}", "✅".green(), alias.blue());
+            } else {
+                println!("{} Global alias not found: {}", "❌".red(), alias.blue());
+            }
+            return Ok(());
+        },
+        _ => {}
+    }
+
     // Find project root
     let project_root = Project::find_project_root()
         .context("No cyrus.toml found. Run 'cyrus init' first.")?;
     
-    let config_path = project_root.join("cyrus.toml");
+    let config_path = Project::config_path(&project_root)?;
     let mut project = Project::load_from_file(&config_path)?;
+    let inherited = inherited_alias_layers();
     
     match cmd.action {
-        AliasAction::List => {
+        AliasAction::List { resolved: true } => {
+            println!("{}", "📋 Resolved Aliases:".cyan().bold());
+            
+            if !project.enable_aliases {
+                println!("{}", "❌ Aliases are currently disabled".yellow());
+                return Ok(());
+            }
+            
+            let resolved = alias::resolve_layers(&project.resolution_layers(&inherited));
+            if resolved.is_empty() {
+                println!("  {}", "No aliases defined".yellow());
+            }
+            
+            for entry in resolved {
+                println!("  {} → {}  {}",
+                         entry.name.blue(),
+                         entry.command.cyan(),
+                         format!("({})", entry.source).dimmed());
+                for (source, command) in &entry.shadowed {
+                    println!("      {} {} → {}",
+                             "shadows".yellow(),
+                             source.to_string().dimmed(),
+                             command.dimmed());
+                }
+            }
+        },
+        
+        AliasAction::List { resolved: false } => {
             println!("{}", "📋 Project Aliases:".cyan().bold());
             
             if !project.enable_aliases {
@@ -115,13 +504,13 @@ pub async fn execute_alias(cmd: AliasCommand, _core: &CyrusCore) -> Result<()> {
             if project.scripts.is_empty() {
                 println!("  {}", "No scripts defined".yellow());
             } else {
-                for (script, command) in &project.scripts {
-                    println!("  {} → {}", script.blue(), command.cyan());
+                for (name, script) in &project.scripts {
+                    println!("  {} → {}", name.blue(), script.to_string().cyan());
                 }
             }
         },
         
-        AliasAction::Add { alias, command } => {
+        AliasAction::Add { alias, command, .. } => {
             project.add_alias(alias.clone(), command.clone());
             project.save_to_file(&config_path)?;
             
@@ -131,7 +520,7 @@ pub async fn execute_alias(cmd: AliasCommand, _core: &CyrusCore) -> Result<()> {
                      command.cyan());
         },
         
-        AliasAction::Remove { alias } => {
+        AliasAction::Remove { alias, .. } => {
             if project.custom_aliases.contains_key(&alias) {
                 project.remove_alias(&alias);
                 project.save_to_file(&config_path)?;
@@ -146,6 +535,25 @@ pub async fn execute_alias(cmd: AliasCommand, _core: &CyrusCore) -> Result<()> {
             }
         },
         
+        AliasAction::Expand { alias: name, args } => {
+            let aliases = alias::merge_layers(&project.alias_layers(&inherited));
+            let is_alias = project.custom_aliases.contains_key(&name)
+                || (!project.scripts.contains_key(&name) && aliases.contains_key(&name));
+            
+            if is_alias {
+                let expansion = alias::expand(&aliases, &name, &args)?;
+                if expansion.chain.len() > 1 {
+                    eprintln!("{} {}", "🔗".blue(), expansion.chain.join(" → ").yellow());
+                }
+                println!("{}", expansion.command_line);
+            } else {
+                // Not an alias: show what `cyrus run` would execute instead
+                for step in project.plan_command(&name, &args, &inherited)? {
+                    println!("{}", step.invocation.display());
+                }
+            }
+        },
+        
         AliasAction::Toggle => {
             project.toggle_aliases();
             project.save_to_file(&config_path)?;
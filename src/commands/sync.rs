@@ -0,0 +1,84 @@
+//! `cyrus sync`: keep cyrus.toml and the native manifest in step
+//! src/commands/sync.rs
+
+use crate::core::manifest::{self, NativeManifest};
+use crate::core::{CyrusCore, Project};
+use super::SyncCommand;
+use anyhow::{Context, Result};
+use colored::*;
+
+pub async fn execute(cmd: SyncCommand, _core: &CyrusCore) -> Result<()> {
+    let root = Project::find_project_root()
+        .context("No cyrus.toml found. Run 'cyrus init --from-existing' to create one from the native manifest.")?;
+    let mut project = Project::load_from_file(Project::config_path(&root)?)?;
+
+    let native = NativeManifest::for_language(&root, &project.language)?
+        .with_context(|| format!("No native manifest for {} found in {}", project.language, root.display()))?;
+    let file = native.kind.file_name();
+
+    let mut plan = manifest::plan(&project, &native, cmd.prefer);
+    if cmd.export {
+        plan.import_scripts.clear();
+        plan.dependencies = None;
+        plan.dev_dependencies = None;
+    }
+    if cmd.import {
+        plan.export_scripts.clear();
+    }
+
+    println!("{} Syncing {} with {}", "🔄".blue(), "cyrus.toml".yellow(), file.yellow());
+
+    for (name, command) in &plan.import_scripts {
+        println!("  {} script {} = {}", "⬅️ ".green(), name.green(), command.dimmed());
+    }
+    for (name, command) in &plan.export_scripts {
+        println!("  {} script {} = {} → {}", "➡️ ".blue(), name.green(), command.dimmed(), file);
+    }
+    report_dependencies("dependencies", &project.dependencies, plan.dependencies.as_deref());
+    report_dependencies("dev_dependencies", &project.dev_dependencies, plan.dev_dependencies.as_deref());
+
+    if !plan.cyrus_only.is_empty() && !cmd.import {
+        println!("  {} Kept in cyrus.toml only ({} can't hold them): {}",
+                 "💡".yellow(), file, plan.cyrus_only.join(", "));
+    }
+
+    if !plan.conflicts.is_empty() {
+        println!("\n{} {} conflict(s) left untouched:", "⚠️".yellow(), plan.conflicts.len());
+        for conflict in &plan.conflicts {
+            println!("  • {}", conflict);
+        }
+        println!("  Re-run with {} or {} to pick a side", "--prefer cyrus".cyan(), "--prefer native".cyan());
+    }
+
+    if plan.is_empty() {
+        println!("{} Already in sync", "✅".green());
+        return Ok(());
+    }
+    if cmd.dry_run {
+        println!("\n{} Dry run; nothing written", "💡".yellow());
+        return Ok(());
+    }
+
+    if !plan.import_scripts.is_empty() || plan.dependencies.is_some() || plan.dev_dependencies.is_some() {
+        plan.apply_to(&mut project);
+        project.save_to_file(Project::config_path(&root)?)?;
+    }
+    native.write_scripts(&plan.export_scripts)?;
+
+    println!("{} Synced", "✅".green());
+    Ok(())
+}
+
+/// Print the specs the manifest adds and drops compared to cyrus.toml
+fn report_dependencies(label: &str, current: &[String], synced: Option<&[String]>) {
+    let Some(synced) = synced else {
+        return;
+    };
+
+    for spec in synced.iter().filter(|spec| !current.contains(spec)) {
+        println!("  {} {} {}", "⬅️ ".green(), label, spec.green());
+    }
+    for spec in current.iter().filter(|spec| !synced.contains(spec)) {
+        println!("  {} {} {} {}", "➖".red(), label, spec.red(), "(not in the manifest)".dimmed());
+    }
+}
@@ -0,0 +1,107 @@
+//! `cyrus up`: start a project's (or workspace's) processes together
+//! src/commands/up.rs
+
+use crate::core::script::Invocation;
+use crate::core::{config_format, CyrusCore, Project};
+use crate::error::CyrusError;
+use crate::runtime::supervisor::{self, ManagedProcess};
+use crate::workspace::{self, WorkspaceManager};
+use super::run::{resolve_project_environment, toolchain_env_vars};
+use super::UpCommand;
+use anyhow::{Context, Result};
+use colored::*;
+use std::path::Path;
+
+pub async fn execute(cmd: UpCommand, core: &CyrusCore) -> Result<()> {
+    let project = Project::discover()?;
+    let workspace_root = workspace::find_workspace_root();
+
+    let mut processes = match (project, workspace_root) {
+        (Some((root, project)), _) if !cmd.workspace => project_processes(core, &root, &project, None)?,
+        (_, Some(root)) => workspace_processes(core, &root)?,
+        (Some(_), None) => anyhow::bail!("No cyrus-workspace.toml found for --workspace"),
+        (None, None) => anyhow::bail!("No cyrus.toml found. Run 'cyrus init' first."),
+    };
+
+    if !cmd.processes.is_empty() {
+        if let Some(unknown) = cmd.processes.iter().find(|name| !processes.iter().any(|p| &p.name == *name)) {
+            anyhow::bail!("Process '{}' is not defined in [processes]", unknown);
+        }
+        processes.retain(|p| cmd.processes.contains(&p.name));
+    }
+
+    if processes.is_empty() {
+        println!("{} No [processes] defined", "⚠️".yellow());
+        return Ok(());
+    }
+
+    println!("{} Starting {} processes {}",
+             "🚀".blue(),
+             processes.len(),
+             "(Ctrl-C to stop)".dimmed());
+
+    let code = supervisor::supervise(processes).await?;
+    if code != 0 {
+        return Err(CyrusError::CommandFailed {
+            command: "cyrus up".to_string(),
+            code: Some(code),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Processes of one project, optionally namespaced as `member/name`
+fn project_processes(
+    core: &CyrusCore,
+    root: &Path,
+    project: &Project,
+    namespace: Option<&str>,
+) -> Result<Vec<ManagedProcess>> {
+    let mut base_env = toolchain_env_vars(core, project, root)?;
+    base_env.extend(resolve_project_environment(core, root, &project.environment)?);
+
+    let mut names: Vec<&String> = project.processes.keys().collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let spec = &project.processes[name];
+
+            let mut env = base_env.clone();
+            env.extend(resolve_project_environment(core, root, &spec.env)?);
+
+            Ok(ManagedProcess {
+                name: match namespace {
+                    Some(member) => format!("{}/{}", member, name),
+                    None => name.clone(),
+                },
+                invocation: Invocation::parse(&spec.run)?,
+                cwd: spec.cwd.as_ref().map(|cwd| root.join(cwd)).unwrap_or_else(|| root.to_path_buf()),
+                env,
+                restart: spec.restart,
+            })
+        })
+        .collect()
+}
+
+/// Processes of every enabled workspace member that has a cyrus.toml
+fn workspace_processes(core: &CyrusCore, root: &Path) -> Result<Vec<ManagedProcess>> {
+    let mut manager = WorkspaceManager::new();
+    manager.load_workspace(root)?;
+
+    let mut processes = Vec::new();
+    for member in manager.list_members()? {
+        let member_root = root.join(&member.path);
+        if !member.enabled || !config_format::exists(&member_root) {
+            continue;
+        }
+        let project = Project::load_from_dir(&member_root)
+            .with_context(|| format!("Failed to load the project in {}", member_root.display()))?;
+        processes.extend(project_processes(core, &member_root, &project, Some(&member.name))?);
+    }
+
+    Ok(processes)
+}
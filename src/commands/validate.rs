@@ -0,0 +1,477 @@
+//! `cyrus dev validate`: check every config file cyrus reads, without running anything
+//! src/commands/validate.rs
+//!
+//! Covers the project file (with its `extends` chain), the workspace file, the
+//! global config, the active profile and installed plugin manifests. Exit codes:
+//! 0 when everything is valid, 1 on errors, 2 on warnings with `--strict`.
+
+use crate::config::GlobalConfig;
+use crate::core::extends::Merged;
+use crate::core::{config_format, migration, task, CyrusCore, Project};
+use crate::error::ValidationWarning;
+use crate::languages;
+use crate::plugins::{self, PluginManifest};
+use crate::workspace::{self, Workspace};
+use super::ValidateCommand;
+use anyhow::Result;
+use colored::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One problem, located by file and, when known, dotted key
+#[derive(Debug, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Validation {
+    pub findings: Vec<Finding>,
+    /// Files that were looked at
+    pub checked: Vec<String>,
+}
+
+impl Validation {
+    fn error(&mut self, file: &str, key: Option<&str>, message: impl Into<String>) {
+        self.push(Severity::Error, file, key, message.into());
+    }
+
+    fn warning(&mut self, file: &str, key: Option<&str>, message: impl Into<String>) {
+        self.push(Severity::Warning, file, key, message.into());
+    }
+
+    fn push(&mut self, severity: Severity, file: &str, key: Option<&str>, message: String) {
+        self.findings.push(Finding {
+            severity,
+            file: file.to_string(),
+            key: key.map(str::to_string),
+            message,
+        });
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|f| f.severity == severity).count()
+    }
+
+    /// 0 when valid, 1 on errors, 2 on warnings when `strict`
+    pub fn exit_code(&self, strict: bool) -> i32 {
+        if self.count(Severity::Error) > 0 {
+            1
+        } else if strict && self.count(Severity::Warning) > 0 {
+            2
+        } else {
+            0
+        }
+    }
+}
+
+pub async fn execute(cmd: ValidateCommand, core: &CyrusCore) -> Result<()> {
+    let mut validation = Validation::default();
+
+    let global = check_global(&mut validation);
+    let project = match Project::find_project_root() {
+        Some(root) => check_project(&root, &mut validation),
+        None => None,
+    };
+    if let Some(root) = workspace::find_workspace_root() {
+        check_workspace(&root, &mut validation);
+    }
+    if let Some((config, file)) = &global {
+        check_profile(config, file, project.as_ref(), &mut validation);
+    }
+    check_plugins(core, global.as_ref().map(|(config, _)| config), &mut validation);
+
+    let errors = validation.count(Severity::Error);
+    let warnings = validation.count(Severity::Warning);
+    let code = validation.exit_code(cmd.strict);
+
+    if cmd.json {
+        println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+            "valid": code == 0,
+            "strict": cmd.strict,
+            "errors": errors,
+            "warnings": warnings,
+            "checked": validation.checked,
+            "findings": validation.findings,
+        }))?);
+    } else {
+        print_report(&validation);
+        println!();
+        if errors == 0 && warnings == 0 {
+            println!("{} Configuration is valid ({} file(s) checked)", "✅".green(), validation.checked.len());
+        } else {
+            println!("{} {} error(s), {} warning(s)", "🔍".blue(), errors.to_string().red(), warnings.to_string().yellow());
+        }
+    }
+
+    // Exit directly so CI sees 1 and 2 apart, and --json output stays the only output
+    if code != 0 {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+fn print_report(validation: &Validation) {
+    println!("{}", "🔍 Validating configuration".cyan().bold());
+    for file in &validation.checked {
+        println!("  {} {}", "📄", file.dimmed());
+    }
+    if !validation.findings.is_empty() {
+        println!();
+    }
+
+    for finding in &validation.findings {
+        let icon = match finding.severity {
+            Severity::Error => "❌",
+            Severity::Warning => "⚠️ ",
+        };
+        let location = match &finding.key {
+            Some(key) => format!("{}:{}", finding.file, key),
+            None => finding.file.clone(),
+        };
+        println!("  {} {} {}", icon, location.yellow(), finding.message);
+    }
+}
+
+/// `path` relative to the current directory when it is below it
+fn location(path: &Path) -> String {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(|p| p.display().to_string()))
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// The global config (defaults when there is no file) and how to refer to its file
+fn check_global(validation: &mut Validation) -> Option<(GlobalConfig, String)> {
+    let path = match GlobalConfig::config_path() {
+        Ok(path) => path,
+        Err(e) => {
+            validation.error("global config", None, e.to_string());
+            return None;
+        }
+    };
+    let file = location(&path);
+    if !path.exists() {
+        return Some((GlobalConfig::new(), file));
+    }
+    validation.checked.push(file.clone());
+
+    let config: GlobalConfig = match std::fs::read_to_string(&path).map_err(|e| e.to_string())
+        .and_then(|content| toml::from_str(&content).map_err(|e| e.to_string()))
+    {
+        Ok(config) => config,
+        Err(e) => {
+            validation.error(&file, None, e);
+            return None;
+        }
+    };
+
+    if let Err(e) = config.validate() {
+        let key = if config.parallel_downloads == 0 || config.parallel_downloads > 10 {
+            "parallel_downloads"
+        } else {
+            "default_profile"
+        };
+        validation.error(&file, Some(key), e.to_string());
+    }
+    for warning in config.get_warnings() {
+        let key = match &warning {
+            ValidationWarning::TooManyDependencies { .. } => Some("parallel_downloads"),
+            ValidationWarning::SecurityVulnerability { .. } => Some("security_settings.verify_downloads"),
+            _ => None,
+        };
+        validation.warning(&file, key, warning.to_string());
+    }
+
+    Some((config, file))
+}
+
+/// The project in `root` and how to refer to its file, if it loads
+pub fn check_project(root: &Path, validation: &mut Validation) -> Option<(Project, String)> {
+    let path = match Project::config_path(root) {
+        Ok(path) => path,
+        Err(e) => {
+            validation.error(&location(root), None, e.to_string());
+            return None;
+        }
+    };
+    let file = location(&path);
+    validation.checked.push(file.clone());
+
+    // Migrated in memory only: validating must never rewrite the working tree
+    let loaded = config_format::read_table(&path).and_then(|mut table| {
+        let from = migration::schema_version(&table);
+        migration::migrate(&mut table)?;
+        Ok((from, Project::from_table(&path, table)?))
+    });
+    let (project, merged) = match loaded {
+        Ok((from, loaded)) => {
+            if from < migration::CURRENT_SCHEMA_VERSION {
+                validation.warning(&file, Some("schema_version"), format!(
+                    "Schema {} needs migration to {}; run 'cyrus migrate'",
+                    from, migration::CURRENT_SCHEMA_VERSION
+                ));
+            }
+            loaded
+        },
+        Err(e) => {
+            validation.error(&file, None, format!("{:#}", e));
+            return None;
+        }
+    };
+
+    // Inherited keys are reported against the base that sets them
+    let source = |key: &str| origin_file(root, &merged, key, &file);
+
+    let problems = project.problems();
+    for (key, message) in &problems {
+        validation.error(&source(key), Some(key.as_str()), message.clone());
+    }
+
+    if !project.language.is_empty() {
+        match languages::get_language_handler(&project.language) {
+            None => validation.error(&source("language"), Some("language"), format!("Unsupported language '{}'", project.language)),
+            Some(handler) => {
+                let config = handler.get_config();
+                if !project.package_manager.is_empty() && !config.package_managers.contains(&project.package_manager) {
+                    validation.warning(
+                        &source("package_manager"),
+                        Some("package_manager"),
+                        format!("'{}' is not a known package manager for {} ({})",
+                                project.package_manager, project.language, config.package_managers.join(", ")),
+                    );
+                }
+            }
+        }
+    }
+
+    let mut cycles = HashSet::new();
+    let mut names: Vec<&String> = project.tasks.keys().collect();
+    names.sort();
+    for name in names {
+        if let Err(e) = task::execution_order(&project.tasks, name) {
+            let message = e.to_string();
+            if cycles.insert(message.clone()) {
+                let key = format!("tasks.{}", name);
+                validation.error(&source(&key), Some(key.as_str()), message);
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        if let Ok(warnings) = project.validate_comprehensive() {
+            for warning in warnings {
+                let key = match &warning {
+                    ValidationWarning::SuggestTypescript | ValidationWarning::MissingDevDependencies { .. } => "dev_dependencies",
+                    ValidationWarning::SuboptimalPackageManager { .. } => "package_manager",
+                    ValidationWarning::OutdatedVersion { .. } => "version",
+                    ValidationWarning::SecurityVulnerability { .. } | ValidationWarning::TooManyDependencies { .. } => "dependencies",
+                };
+                validation.warning(&source(key), Some(key), warning.to_string());
+            }
+        }
+    }
+
+    Some((project, file))
+}
+
+fn origin_file(root: &Path, merged: &Merged, key: &str, own: &str) -> String {
+    match merged.origin(key) {
+        "" => own.to_string(),
+        origin if origin.starts_with("profile:") => origin.to_string(),
+        origin => location(&root.join(origin)),
+    }
+}
+
+fn check_workspace(root: &Path, validation: &mut Validation) {
+    let path = root.join("cyrus-workspace.toml");
+    let file = location(&path);
+    validation.checked.push(file.clone());
+
+    let workspace: Workspace = match std::fs::read_to_string(&path).map_err(|e| e.to_string())
+        .and_then(|content| toml::from_str(&content).map_err(|e| e.to_string()))
+    {
+        Ok(workspace) => workspace,
+        Err(e) => {
+            validation.error(&file, None, e);
+            return;
+        }
+    };
+
+    let names: HashSet<&str> = workspace.members.iter().map(|m| m.name.as_str()).collect();
+    let mut seen = HashSet::new();
+    for (index, member) in workspace.members.iter().enumerate() {
+        let key = format!("members[{}]", index);
+        if !seen.insert(member.name.as_str()) {
+            validation.error(&file, Some(format!("{}.name", key).as_str()), format!("Duplicate member '{}'", member.name));
+        }
+
+        let member_path = root.join(&member.path);
+        if !member_path.exists() {
+            validation.error(&file, Some(format!("{}.path", key).as_str()), format!("{} does not exist", member.path.display()));
+        } else if member.enabled && !config_format::exists(&member_path) {
+            validation.warning(&file, Some(format!("{}.path", key).as_str()), format!("Member '{}' has no cyrus.toml", member.name));
+        }
+
+        for dependency in member.dependencies.iter().filter(|d| !names.contains(d.as_str())) {
+            validation.error(&file, Some(format!("{}.dependencies", key).as_str()),
+                             format!("'{}' depends on unknown member '{}'", member.name, dependency));
+        }
+    }
+
+    if let Some(cycle) = member_cycle(&workspace) {
+        validation.error(&file, Some("members"), format!("Circular member dependency: {}", cycle.join(" → ")));
+    }
+    if workspace.shared_config.max_parallel_jobs == Some(0) {
+        validation.error(&file, Some("shared_config.max_parallel_jobs"), "Must be at least 1");
+    }
+}
+
+/// The first dependency cycle between workspace members, if any
+fn member_cycle(workspace: &Workspace) -> Option<Vec<String>> {
+    let dependencies: HashMap<&str, &Vec<String>> = workspace.members.iter()
+        .map(|m| (m.name.as_str(), &m.dependencies))
+        .collect();
+
+    fn visit<'a>(
+        name: &'a str,
+        dependencies: &HashMap<&'a str, &'a Vec<String>>,
+        stack: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+    ) -> Option<Vec<String>> {
+        if let Some(start) = stack.iter().position(|n| *n == name) {
+            let mut cycle: Vec<String> = stack[start..].iter().map(|n| n.to_string()).collect();
+            cycle.push(name.to_string());
+            return Some(cycle);
+        }
+        if !done.insert(name) {
+            return None;
+        }
+
+        stack.push(name);
+        for dependency in dependencies.get(name).into_iter().flat_map(|deps| deps.iter()) {
+            if let Some(cycle) = visit(dependency, dependencies, stack, done) {
+                return Some(cycle);
+            }
+        }
+        stack.pop();
+        None
+    }
+
+    let mut done = HashSet::new();
+    workspace.members.iter()
+        .find_map(|m| visit(&m.name, &dependencies, &mut Vec::new(), &mut done))
+}
+
+/// The active profile itself, and its quality gates against the project
+fn check_profile(config: &GlobalConfig, global_file: &str, project: Option<&(Project, String)>, validation: &mut Validation) {
+    let profile = match config.active_profile() {
+        Ok(profile) => profile,
+        Err(e) => {
+            validation.error(global_file, Some("default_profile"), e.to_string());
+            return;
+        }
+    };
+
+    let (file, key) = if config.profiles.contains_key(&profile.name) {
+        (global_file.to_string(), Some(format!("profiles.{}", profile.name)))
+    } else {
+        (format!("profile:{}", profile.name), None)
+    };
+    if let Err(e) = profile.validate() {
+        validation.error(&file, key.as_deref(), e.to_string());
+    }
+
+    let Some((project, project_file)) = project else {
+        return;
+    };
+    let gates = &profile.quality_gates;
+    for (list, specs) in [("dependencies", &project.dependencies), ("dev_dependencies", &project.dev_dependencies)] {
+        for spec in specs {
+            let name = languages::split_dependency(spec).0;
+            if gates.banned_dependencies.iter().any(|banned| banned == name) {
+                validation.error(project_file, Some(list), format!("'{}' is banned by the {} profile", name, profile.name));
+            }
+        }
+    }
+    if let Some(max) = gates.max_dependencies {
+        if project.dependencies.len() > max {
+            validation.warning(project_file, Some("dependencies"), format!(
+                "{} dependencies exceed the {} profile's limit of {}",
+                project.dependencies.len(), profile.name, max
+            ));
+        }
+    }
+}
+
+/// Manifests of installed plugins; libraries are not loaded
+fn check_plugins(core: &CyrusCore, config: Option<&GlobalConfig>, validation: &mut Validation) {
+    let mut directories = plugins::default_plugin_directories(&core.cyrus_dir);
+    if let Some(config) = config {
+        directories.extend(config.plugin_settings.plugin_directories.iter().cloned());
+    }
+    let mut seen = HashSet::new();
+    directories.retain(|directory| seen.insert(directory.clone()));
+
+    let current = semver::Version::parse(env!("CARGO_PKG_VERSION")).ok();
+    for directory in directories.iter().filter(|d| d.exists()) {
+        let Ok(entries) = std::fs::read_dir(directory) else {
+            continue;
+        };
+        let plugin_dirs: Vec<PathBuf> = entries.flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.join("cyrus-plugin.toml").is_file())
+            .collect();
+
+        for plugin_dir in plugin_dirs {
+            let path = plugin_dir.join("cyrus-plugin.toml");
+            let file = location(&path);
+            validation.checked.push(file.clone());
+
+            let manifest: PluginManifest = match std::fs::read_to_string(&path).map_err(|e| e.to_string())
+                .and_then(|content| toml::from_str(&content).map_err(|e| e.to_string()))
+            {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    validation.error(&file, None, e);
+                    continue;
+                }
+            };
+
+            match semver::Version::parse(&manifest.min_cyrus_version) {
+                Err(e) => validation.error(&file, Some("min_cyrus_version"), format!("Invalid version: {}", e)),
+                Ok(required) if current.as_ref().is_some_and(|current| *current < required) => validation.error(
+                    &file,
+                    Some("min_cyrus_version"),
+                    format!("Requires cyrus {} or newer; this is {}", required, env!("CARGO_PKG_VERSION")),
+                ),
+                Ok(_) => {},
+            }
+
+            if !plugin_dir.join(&manifest.entry_point).exists() {
+                validation.error(&file, Some("entry_point"), format!("{} not found", manifest.entry_point));
+            }
+
+            let permissions = &manifest.permissions;
+            let trusted = config.is_some_and(|c| c.plugin_settings.trusted_plugins.contains(&manifest.name));
+            if (permissions.system_commands || permissions.network_access) && !trusted {
+                validation.warning(&file, Some("permissions"), format!(
+                    "'{}' may run commands or use the network but is not in plugin_settings.trusted_plugins",
+                    manifest.name
+                ));
+            }
+        }
+    }
+}
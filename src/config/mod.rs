@@ -1,18 +1,22 @@
 // src/config/mod.rs
 //! Enhanced configuration management with profiles and validation
 
+use crate::core::alias::{AliasLayer, AliasSource};
+use crate::core::document;
 use crate::error::{CyrusError, Result, ValidationError, ValidationWarning};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use validator::{Validate, ValidationErrors};
 
 pub mod profiles;
+pub mod schema;
 pub mod validation;
 
 use profiles::CyrusProfile;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct GlobalConfig {
     #[validate(length(min = 1))]
     pub default_profile: String,
@@ -22,6 +26,10 @@ pub struct GlobalConfig {
     #[serde(default)]
     pub global_aliases: HashMap<String, String>,
 
+    /// Version used outside projects when none is given, by language
+    #[serde(default)]
+    pub default_versions: HashMap<String, String>,
+
     #[serde(default)]
     pub security_settings: SecuritySettings,
 
@@ -42,7 +50,7 @@ pub struct GlobalConfig {
     pub cache_settings: CacheSettings,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct SecuritySettings {
     #[serde(default = "default_true")]
     pub verify_downloads: bool,
@@ -60,7 +68,7 @@ pub struct SecuritySettings {
     pub security_level: SecurityLevel,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub enum SecurityLevel {
     Low,
     Medium,
@@ -68,7 +76,7 @@ pub enum SecurityLevel {
     Paranoid,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct NetworkSettings {
     #[serde(default = "default_timeout")]
     pub timeout_seconds: u64,
@@ -85,7 +93,7 @@ pub struct NetworkSettings {
     pub use_ipv6: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct UiSettings {
     #[serde(default = "default_true")]
     pub colored_output: bool,
@@ -103,7 +111,7 @@ pub struct UiSettings {
     pub theme: UiTheme,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub enum VerbosityLevel {
     Quiet,
     Normal,
@@ -111,7 +119,7 @@ pub enum VerbosityLevel {
     Debug,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub enum UiTheme {
     Default,
     Dark,
@@ -119,7 +127,7 @@ pub enum UiTheme {
     Colorblind,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PluginSettings {
     #[serde(default = "default_true")]
     pub enabled: bool,
@@ -134,7 +142,7 @@ pub struct PluginSettings {
     pub auto_update_plugins: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct CacheSettings {
     #[serde(default = "default_true")]
     pub enabled: bool,
@@ -250,6 +258,7 @@ impl GlobalConfig {
             default_profile: "default".to_string(),
             profiles,
             global_aliases: HashMap::new(),
+            default_versions: HashMap::new(),
             security_settings: SecuritySettings::default(),
             network_settings: NetworkSettings::default(),
             ui_settings: UiSettings::default(),
@@ -259,6 +268,30 @@ impl GlobalConfig {
         }
     }
 
+    /// Location of the global config file; `CYRUS_CONFIG` (set by `--config`) overrides it
+    pub fn config_path() -> Result<PathBuf> {
+        if let Some(path) = std::env::var_os("CYRUS_CONFIG") {
+            return Ok(PathBuf::from(path));
+        }
+
+        Ok(dirs::config_dir()
+            .ok_or_else(|| CyrusError::Config {
+                message: "Could not determine config directory".to_string(),
+            })?
+            .join("cyrus")
+            .join("config.toml"))
+    }
+
+    /// Load the global config file, falling back to defaults when it doesn't exist
+    pub fn load_or_default() -> Result<Self> {
+        let path = Self::config_path()?;
+        if path.exists() {
+            Self::load_from_file(path)
+        } else {
+            Ok(Self::new())
+        }
+    }
+
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = std::fs::read_to_string(path).map_err(|e| CyrusError::Config {
             message: format!("Failed to read config file: {}", e),
@@ -272,12 +305,24 @@ impl GlobalConfig {
         Ok(config)
     }
 
+    /// Write the config; an existing file keeps its comments and key order
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         self.validate()?;
 
-        let content = toml::to_string_pretty(self).map_err(|e| CyrusError::Config {
-            message: format!("Failed to serialize config: {}", e),
-        })?;
+        let content = match std::fs::read_to_string(path.as_ref()) {
+            Ok(existing) => toml::Value::try_from(self)
+                .map_err(anyhow::Error::from)
+                .and_then(|value| match value {
+                    toml::Value::Table(table) => document::update(&existing, &table),
+                    _ => unreachable!("the config serializes to a table"),
+                })
+                .map_err(|e| CyrusError::Config {
+                    message: format!("Failed to serialize config: {}", e),
+                })?,
+            Err(_) => toml::to_string_pretty(self).map_err(|e| CyrusError::Config {
+                message: format!("Failed to serialize config: {}", e),
+            })?,
+        };
 
         std::fs::write(path, content).map_err(|e| CyrusError::Config {
             message: format!("Failed to write config file: {}", e),
@@ -330,10 +375,53 @@ impl GlobalConfig {
         Ok(())
     }
 
+    /// Profile in effect: `CYRUS_PROFILE` (set by `--profile`) or the default profile.
+    ///
+    /// Names not defined in the config file fall back to the built-in profiles.
+    pub fn active_profile(&self) -> Result<CyrusProfile> {
+        let name = std::env::var("CYRUS_PROFILE")
+            .ok()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| self.default_profile.clone());
+
+        if let Some(profile) = self.profiles.get(&name) {
+            return Ok(profile.clone());
+        }
+
+        profiles::ProfileManager::new()
+            .list_profiles()
+            .into_iter()
+            .find(|profile| profile.name == name)
+            .cloned()
+            .ok_or_else(|| CyrusError::Config {
+                message: format!("Profile '{}' does not exist", name),
+            })
+    }
+
+    /// Profile and global alias layers, highest precedence first
+    pub fn alias_layers(&self) -> Result<Vec<AliasLayer>> {
+        let profile = self.active_profile()?;
+
+        Ok(vec![
+            AliasLayer {
+                source: AliasSource::Profile(profile.name.clone()),
+                aliases: profile.global_aliases,
+            },
+            AliasLayer {
+                source: AliasSource::Global,
+                aliases: self.global_aliases.clone(),
+            },
+        ])
+    }
+
     pub fn add_global_alias(&mut self, alias: String, command: String) {
         self.global_aliases.insert(alias, command);
     }
 
+    pub fn remove_global_alias(&mut self, alias: &str) -> bool {
+        self.global_aliases.remove(alias).is_some()
+    }
+
     pub fn get_warnings(&self) -> Vec<ValidationWarning> {
         let mut warnings = Vec::new();
 
@@ -374,169 +462,3 @@ fn validation_errors_to_vec(errors: validator::ValidationErrors) -> Vec<Validati
 
     result
 }
-
-/// Enhanced project configuration with validation
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct EnhancedProject {
-    #[validate(length(min = 1))]
-    pub name: String,
-
-    #[validate(length(min = 1))]
-    pub language: String,
-
-    #[validate(custom = "validate_version")]
-    pub version: String,
-
-    #[validate(length(min = 1))]
-    pub package_manager: String,
-
-    #[serde(default)]
-    pub dependencies: Vec<String>,
-
-    #[serde(default)]
-    pub dev_dependencies: Vec<String>,
-
-    #[serde(default)]
-    pub scripts: HashMap<String, String>,
-
-    #[serde(default)]
-    pub environment: HashMap<String, String>,
-
-    #[serde(default = "default_true")]
-    pub enable_aliases: bool,
-
-    #[serde(default)]
-    pub custom_aliases: HashMap<String, String>,
-
-    #[serde(default)]
-    pub workspace: Option<WorkspaceConfig>,
-
-    #[serde(default)]
-    pub template_source: Option<String>,
-
-    #[serde(default)]
-    pub metadata: ProjectMetadata,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct WorkspaceConfig {
-    pub root: PathBuf,
-    pub members: Vec<String>,
-    pub shared_dependencies: Vec<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct ProjectMetadata {
-    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
-    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
-    pub author: Option<String>,
-    pub description: Option<String>,
-    pub tags: Vec<String>,
-    pub cyrus_version: String,
-}
-
-impl Default for ProjectMetadata {
-    fn default() -> Self {
-        Self {
-            created_at: Some(chrono::Utc::now()),
-            updated_at: Some(chrono::Utc::now()),
-            author: None,
-            description: None,
-            tags: Vec::new(),
-            cyrus_version: env!("CARGO_PKG_VERSION").to_string(),
-        }
-    }
-}
-
-fn validate_version(version: &str) -> std::result::Result<(), validator::ValidationError> {
-    if semver::Version::parse(version).is_ok()
-        || version.chars().all(|c| c.is_numeric() || c == '.')
-    {
-        Ok(())
-    } else {
-        Err(validator::ValidationError::new("invalid_version"))
-    }
-}
-
-impl EnhancedProject {
-    pub fn validate_comprehensive(&self) -> Result<Vec<crate::error::ValidationWarning>> {
-        // Basic validation first
-        self.validate()?;
-
-        let mut warnings = Vec::new();
-
-        // Language-specific validations
-        match self.language.as_str() {
-            "javascript" => {
-                if !self.dependencies.iter().any(|d| d.contains("typescript"))
-                    && !self
-                        .dev_dependencies
-                        .iter()
-                        .any(|d| d.contains("typescript"))
-                {
-                    warnings.push(crate::error::ValidationWarning::SuggestTypescript);
-                }
-
-                if self.package_manager == "npm"
-                    && (self.dependencies.len() + self.dev_dependencies.len()) > 10
-                {
-                    warnings.push(crate::error::ValidationWarning::SuboptimalPackageManager {
-                        pm: "npm".to_string(),
-                        suggested: "pnpm".to_string(),
-                    });
-                }
-            }
-            "python" => {
-                let common_dev_deps = ["pytest", "black", "flake8", "mypy"];
-                let missing: Vec<String> = common_dev_deps
-                    .iter()
-                    .filter(|&dep| !self.dev_dependencies.iter().any(|d| d.contains(dep)))
-                    .map(|&s| s.to_string())
-                    .collect();
-
-                if !missing.is_empty() {
-                    warnings.push(crate::error::ValidationWarning::MissingDevDependencies {
-                        suggestions: missing,
-                    });
-                }
-            }
-            _ => {}
-        }
-
-        // Check for too many dependencies
-        let total_deps = self.dependencies.len() + self.dev_dependencies.len();
-        if total_deps > 50 {
-            warnings
-                .push(crate::error::ValidationWarning::TooManyDependencies { count: total_deps });
-        }
-
-        Ok(warnings)
-    }
-    pub fn validate(&self) -> Result<()> {
-        if self.name.is_empty() {
-            return Err(CyrusError::Config {
-                message: "Project name cannot be empty".to_string(),
-            });
-        }
-
-        if self.language.is_empty() {
-            return Err(CyrusError::Config {
-                message: "Language cannot be empty".to_string(),
-            });
-        }
-
-        if self.version.is_empty() {
-            return Err(CyrusError::Config {
-                message: "Version cannot be empty".to_string(),
-            });
-        }
-
-        if self.package_manager.is_empty() {
-            return Err(CyrusError::Config {
-                message: "Package manager cannot be empty".to_string(),
-            });
-        }
-
-        Ok(())
-    }
-}
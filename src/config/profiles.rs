@@ -2,10 +2,11 @@
 //! Configuration profiles for different development environments
 
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use std::collections::HashMap;
 use crate::error::{CyrusError, Result};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct CyrusProfile {
     pub name: String,
     pub description: String,
@@ -32,7 +33,7 @@ pub struct CyrusProfile {
     pub quality_gates: QualityGates,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct DevToolsConfig {
     /// Preferred editors/IDEs
     pub preferred_editor: Option<String>,
@@ -50,7 +51,7 @@ pub struct DevToolsConfig {
     pub build_tools: HashMap<String, String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct QualityGates {
     /// Require tests before allowing commits
     pub require_tests: bool,
@@ -0,0 +1,136 @@
+// src/config/schema.rs
+//! JSON Schema for every cyrus config file, generated from the Rust types
+
+use schemars::schema_for;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::core::Project;
+use crate::plugins::PluginManifest;
+use crate::templates::ProjectTemplate;
+use crate::workspace::Workspace;
+use super::GlobalConfig;
+
+/// Directory the association snippets expect the schemas in
+pub const SCHEMA_DIR: &str = ".cyrus/schemas";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaKind {
+    Project,
+    Workspace,
+    Plugin,
+    Template,
+    Global,
+}
+
+impl SchemaKind {
+    pub const ALL: &'static [SchemaKind] = &[
+        SchemaKind::Project,
+        SchemaKind::Workspace,
+        SchemaKind::Plugin,
+        SchemaKind::Template,
+        SchemaKind::Global,
+    ];
+
+    /// Files this schema describes
+    pub fn file_names(&self) -> &'static [&'static str] {
+        match self {
+            SchemaKind::Project => &["cyrus.toml", "cyrus.yaml", "cyrus.json"],
+            SchemaKind::Workspace => &["cyrus-workspace.toml"],
+            SchemaKind::Plugin => &["cyrus-plugin.toml"],
+            SchemaKind::Template => &["cyrus-template.toml"],
+            SchemaKind::Global => &["cyrus/config.toml"],
+        }
+    }
+
+    /// Where `cyrus dev schema --editor` expects this schema to be saved
+    pub fn schema_path(&self) -> String {
+        format!("{}/cyrus-{}.schema.json", SCHEMA_DIR, self)
+    }
+}
+
+impl FromStr for SchemaKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "project" => Ok(SchemaKind::Project),
+            "workspace" => Ok(SchemaKind::Workspace),
+            "plugin" => Ok(SchemaKind::Plugin),
+            "template" => Ok(SchemaKind::Template),
+            "global" => Ok(SchemaKind::Global),
+            other => Err(format!(
+                "Unknown schema kind '{}' (expected project, workspace, plugin, template or global)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SchemaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SchemaKind::Project => "project",
+            SchemaKind::Workspace => "workspace",
+            SchemaKind::Plugin => "plugin",
+            SchemaKind::Template => "template",
+            SchemaKind::Global => "global",
+        })
+    }
+}
+
+/// The JSON Schema for one kind of config file
+pub fn generate(kind: SchemaKind) -> serde_json::Value {
+    let schema = match kind {
+        SchemaKind::Project => schema_for!(Project),
+        SchemaKind::Workspace => schema_for!(Workspace),
+        SchemaKind::Plugin => schema_for!(PluginManifest),
+        SchemaKind::Template => schema_for!(ProjectTemplate),
+        SchemaKind::Global => schema_for!(GlobalConfig),
+    };
+
+    let mut value = serde_json::to_value(schema).expect("schemas serialize to JSON");
+    if let Some(object) = value.as_object_mut() {
+        object.insert(
+            "description".to_string(),
+            format!("cyrus {} configuration ({})", kind, kind.file_names().join(", ")).into(),
+        );
+    }
+    value
+}
+
+/// `.taplo.toml` rules and VS Code settings pointing editors at the saved schemas
+pub fn editor_associations() -> String {
+    let mut taplo = String::from("# .taplo.toml\n");
+    for kind in SchemaKind::ALL {
+        let toml_files: Vec<String> = kind.file_names().iter()
+            .filter(|name| name.ends_with(".toml"))
+            .map(|name| format!("\"**/{}\"", name))
+            .collect();
+        taplo.push_str(&format!(
+            "[[rule]]\ninclude = [{}]\n[rule.schema]\npath = \"{}\"\n\n",
+            toml_files.join(", "),
+            kind.schema_path()
+        ));
+    }
+
+    let mut toml_associations = serde_json::Map::new();
+    for kind in SchemaKind::ALL {
+        for name in kind.file_names().iter().filter(|name| name.ends_with(".toml")) {
+            let pattern = format!("{}$", regex::escape(name));
+            toml_associations.insert(pattern, format!("./{}", kind.schema_path()).into());
+        }
+    }
+    let project = format!("./{}", SchemaKind::Project.schema_path());
+    let vscode = serde_json::json!({
+        "evenBetterToml.schema.associations": toml_associations,
+        "yaml.schemas": { project.clone(): ["cyrus.yaml"] },
+        "json.schemas": [{ "fileMatch": ["cyrus.json"], "url": project }],
+    });
+
+    format!(
+        "{}// .vscode/settings.json\n{}\n",
+        taplo,
+        serde_json::to_string_pretty(&vscode).expect("settings serialize to JSON")
+    )
+}
@@ -0,0 +1,263 @@
+//! Alias expansion with positional placeholders
+//! src/core/alias.rs
+//!
+//! Alias bodies may reference the arguments given on the command line:
+//!
+//! | Placeholder      | Expands to                                   |
+//! |------------------|----------------------------------------------|
+//! | `$1` .. `$9`     | the n-th argument (empty if missing)         |
+//! | `${1:-default}`  | the n-th argument, or `default` if missing   |
+//! | `$@`             | all arguments                                |
+//! | `$$`             | a literal `$`                                |
+//!
+//! A body without placeholders gets the arguments appended, as before.
+//! If the expanded command starts with another alias, that alias is expanded
+//! in turn; an alias that starts with its own name refers to the real
+//! command (`mvn = "mvn -q"`). Any other loop is reported as an error.
+//!
+//! Aliases are layered: project `custom_aliases` win over project scripts,
+//! which win over the active profile's aliases, which win over the global
+//! aliases from the user's config file.
+
+use crate::error::{CyrusError, Result};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliasExpansion {
+    /// Aliases visited, outermost first
+    pub chain: Vec<String>,
+    /// Final command line after all expansions
+    pub command_line: String,
+}
+
+/// Expand `name` with the given user arguments
+pub fn expand(
+    aliases: &HashMap<String, String>,
+    name: &str,
+    args: &[String],
+) -> Result<AliasExpansion> {
+    let mut chain = Vec::new();
+    let mut current = name.to_string();
+    let mut rest = shell_words::join(args);
+
+    loop {
+        let body = aliases.get(&current).ok_or_else(|| CyrusError::Alias {
+            message: format!("Alias '{}' not found", current),
+        })?;
+
+        chain.push(current.clone());
+        let command_line = substitute(body, &rest)?;
+
+        let (head, tail) = split_first_word(&command_line)?;
+        let Some(head) = head else {
+            return Ok(AliasExpansion { chain, command_line });
+        };
+
+        // `mvn = "mvn -q"` names the real program, not itself
+        if head == current || !aliases.contains_key(&head) {
+            return Ok(AliasExpansion { chain, command_line });
+        }
+
+        if chain.contains(&head) {
+            return Err(CyrusError::Alias {
+                message: format!("Alias cycle detected: {} → {}", chain.join(" → "), head),
+            });
+        }
+
+        current = head;
+        rest = tail;
+    }
+}
+
+/// Substitute positional placeholders in an alias body.
+///
+/// `rest` is the argument text in shell syntax; single arguments are
+/// re-quoted on substitution while `$@` keeps the text as written.
+pub fn substitute(body: &str, rest: &str) -> Result<String> {
+    let args = shell_words::split(rest).map_err(|e| CyrusError::Alias {
+        message: format!("Failed to parse alias arguments '{}': {}", rest, e),
+    })?;
+
+    let positional = |index: usize| -> Option<String> {
+        index
+            .checked_sub(1)
+            .and_then(|i| args.get(i))
+            .map(|arg| shell_words::quote(arg).into_owned())
+    };
+
+    let mut output = String::with_capacity(body.len() + rest.len());
+    let mut used_placeholder = false;
+    let mut chars = body.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.peek().copied() {
+            Some('$') => {
+                chars.next();
+                output.push('$');
+            }
+            Some('@') => {
+                chars.next();
+                used_placeholder = true;
+                output.push_str(rest);
+            }
+            Some(d) if d.is_ascii_digit() && d != '0' => {
+                chars.next();
+                used_placeholder = true;
+                let index = d.to_digit(10).unwrap_or_default() as usize;
+                output.push_str(&positional(index).unwrap_or_default());
+            }
+            Some('{') => {
+                let mut braced = String::new();
+                let mut lookahead = chars.clone();
+                lookahead.next();
+                let mut closed = false;
+                for next in lookahead.by_ref() {
+                    if next == '}' {
+                        closed = true;
+                        break;
+                    }
+                    braced.push(next);
+                }
+
+                match (closed, parse_braced(&braced)) {
+                    (true, Some((Placeholder::All, _))) => {
+                        chars = lookahead;
+                        used_placeholder = true;
+                        output.push_str(rest);
+                    }
+                    (true, Some((Placeholder::Index(index), default))) => {
+                        chars = lookahead;
+                        used_placeholder = true;
+                        match positional(index) {
+                            Some(value) => output.push_str(&value),
+                            None => output.push_str(default.unwrap_or_default()),
+                        }
+                    }
+                    // `${HOME}` and friends belong to the shell
+                    _ => output.push('$'),
+                }
+            }
+            _ => output.push('$'),
+        }
+    }
+
+    if !used_placeholder && !rest.is_empty() {
+        output.push(' ');
+        output.push_str(rest);
+    }
+
+    Ok(output)
+}
+
+enum Placeholder {
+    Index(usize),
+    All,
+}
+
+fn parse_braced(inner: &str) -> Option<(Placeholder, Option<&str>)> {
+    let (name, default) = match inner.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (inner, None),
+    };
+
+    if name == "@" {
+        return Some((Placeholder::All, default));
+    }
+
+    match name.parse::<usize>() {
+        Ok(index) if index > 0 => Some((Placeholder::Index(index), default)),
+        _ => None,
+    }
+}
+
+/// Split off the first shell word, returning the untouched remainder
+fn split_first_word(command_line: &str) -> Result<(Option<String>, String)> {
+    let trimmed = command_line.trim_start();
+    let end = trimmed
+        .find(char::is_whitespace)
+        .unwrap_or(trimmed.len());
+    let (first, tail) = trimmed.split_at(end);
+
+    // Quoted or composite first words are never alias names
+    if first.is_empty() || first.contains(['\'', '"', '\\', '$']) {
+        return Ok((None, String::new()));
+    }
+
+    Ok((Some(first.to_string()), tail.trim_start().to_string()))
+}
+
+/// Where an alias definition comes from, highest precedence first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AliasSource {
+    ProjectAlias,
+    ProjectScript,
+    Profile(String),
+    Global,
+}
+
+impl std::fmt::Display for AliasSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AliasSource::ProjectAlias => write!(f, "project alias"),
+            AliasSource::ProjectScript => write!(f, "project script"),
+            AliasSource::Profile(name) => write!(f, "profile '{}'", name),
+            AliasSource::Global => write!(f, "global"),
+        }
+    }
+}
+
+/// One level of alias definitions
+#[derive(Debug, Clone)]
+pub struct AliasLayer {
+    pub source: AliasSource,
+    pub aliases: HashMap<String, String>,
+}
+
+/// An alias as seen after layering, with the definitions it hides
+#[derive(Debug, Clone)]
+pub struct ResolvedAlias {
+    pub name: String,
+    pub command: String,
+    pub source: AliasSource,
+    pub shadowed: Vec<(AliasSource, String)>,
+}
+
+/// Flatten layers (highest precedence first) into a single alias table
+pub fn merge_layers(layers: &[AliasLayer]) -> HashMap<String, String> {
+    let mut merged = HashMap::new();
+    for layer in layers.iter().rev() {
+        for (name, command) in &layer.aliases {
+            merged.insert(name.clone(), command.clone());
+        }
+    }
+    merged
+}
+
+/// Every alias name across all layers, with its winning definition
+pub fn resolve_layers(layers: &[AliasLayer]) -> Vec<ResolvedAlias> {
+    let mut names: Vec<&String> = layers.iter().flat_map(|l| l.aliases.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let mut definitions = layers
+                .iter()
+                .filter_map(|layer| layer.aliases.get(name).map(|cmd| (layer.source.clone(), cmd.clone())));
+
+            let (source, command) = definitions.next()?;
+            Some(ResolvedAlias {
+                name: name.clone(),
+                command,
+                source,
+                shadowed: definitions.collect(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,122 @@
+//! Project config file formats: cyrus.toml, cyrus.yaml and cyrus.json
+//! src/core/config_format.rs
+//!
+//! All three hold the same schema. Files are read into a TOML table first so
+//! migrations and `extends` work the same whatever the format; a directory
+//! may hold only one of them.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use toml::value::{Table, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+pub const FORMATS: &[ConfigFormat] = &[ConfigFormat::Toml, ConfigFormat::Yaml, ConfigFormat::Json];
+
+impl ConfigFormat {
+    /// The project file in this format
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ConfigFormat::Toml => "cyrus.toml",
+            ConfigFormat::Yaml => "cyrus.yaml",
+            ConfigFormat::Json => "cyrus.json",
+        }
+    }
+
+    /// Format of `path` by extension; anything unrecognised is TOML
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml") | Some("yml") => ConfigFormat::Yaml,
+            Some("json") => ConfigFormat::Json,
+            _ => ConfigFormat::Toml,
+        }
+    }
+
+    pub fn parse(&self, content: &str) -> Result<Table> {
+        Ok(match self {
+            ConfigFormat::Toml => toml::from_str(content)?,
+            ConfigFormat::Yaml => serde_yaml::from_str(content)?,
+            ConfigFormat::Json => serde_json::from_str(content)?,
+        })
+    }
+
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<String> {
+        Ok(match self {
+            ConfigFormat::Toml => toml::to_string_pretty(value)?,
+            ConfigFormat::Yaml => serde_yaml::to_string(&plain(toml::Value::try_from(value)?))?,
+            ConfigFormat::Json => serde_json::to_string_pretty(&plain(toml::Value::try_from(value)?))? + "\n",
+        })
+    }
+}
+
+impl FromStr for ConfigFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "toml" => Ok(ConfigFormat::Toml),
+            "yaml" | "yml" => Ok(ConfigFormat::Yaml),
+            "json" => Ok(ConfigFormat::Json),
+            other => Err(format!("Unknown config format '{}' (expected toml, yaml or json)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ConfigFormat::Toml => "toml",
+            ConfigFormat::Yaml => "yaml",
+            ConfigFormat::Json => "json",
+        })
+    }
+}
+
+/// TOML datetimes as strings, which is how YAML and JSON hold them
+fn plain(value: Value) -> Value {
+    match value {
+        Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        Value::Array(items) => Value::Array(items.into_iter().map(plain).collect()),
+        Value::Table(table) => Value::Table(table.into_iter().map(|(k, v)| (k, plain(v))).collect()),
+        other => other,
+    }
+}
+
+/// The project file in `dir`, if any; more than one format is an error
+pub fn find(dir: &Path) -> Result<Option<PathBuf>> {
+    let present: Vec<PathBuf> = FORMATS.iter()
+        .map(|format| dir.join(format.file_name()))
+        .filter(|path| path.is_file())
+        .collect();
+
+    if present.len() > 1 {
+        let names: Vec<String> = present.iter()
+            .filter_map(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+            .collect();
+        anyhow::bail!(
+            "{} has more than one project file ({}); keep one, e.g. with 'cyrus config convert'",
+            dir.display(), names.join(", ")
+        );
+    }
+    Ok(present.into_iter().next())
+}
+
+/// Whether `dir` holds a project file in any format
+pub fn exists(dir: &Path) -> bool {
+    FORMATS.iter().any(|format| dir.join(format.file_name()).is_file())
+}
+
+/// Read and parse the config file at `path` in the format its extension names
+pub fn read_table(path: &Path) -> Result<Table> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    ConfigFormat::from_path(path).parse(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
@@ -0,0 +1,243 @@
+//! Inferring a complete cyrus.toml from what is already in a repository
+//! src/core/detect.rs
+//!
+//! Used by `cyrus init --detect`. Every inferred value keeps the file that
+//! gave it away, so init can say why it chose what it chose.
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::manifest::{self, NativeManifest};
+use super::{version_files, Project};
+
+/// Files that give a project's language away, in precedence order
+const LANGUAGE_MARKERS: &[(&str, &str)] = &[
+    ("package.json", "javascript"),
+    ("pyproject.toml", "python"),
+    ("Pipfile", "python"),
+    ("requirements.txt", "python"),
+    ("setup.py", "python"),
+    ("setup.cfg", "python"),
+    ("Cargo.toml", "rust"),
+    ("go.mod", "golang"),
+    ("pom.xml", "java"),
+    ("build.gradle.kts", "java"),
+    ("build.gradle", "java"),
+    ("composer.json", "php"),
+    ("Gemfile", "ruby"),
+];
+
+/// Files that show which package manager a project uses
+const PACKAGE_MANAGER_MARKERS: &[(&str, &str)] = &[
+    ("pnpm-lock.yaml", "pnpm"),
+    ("yarn.lock", "yarn"),
+    ("bun.lockb", "bun"),
+    ("bun.lock", "bun"),
+    ("package-lock.json", "npm"),
+    ("poetry.lock", "poetry"),
+    ("Pipfile.lock", "pipenv"),
+    ("Pipfile", "pipenv"),
+    ("build.gradle.kts", "gradle"),
+    ("build.gradle", "gradle"),
+    ("pom.xml", "maven"),
+    ("Gemfile.lock", "bundler"),
+];
+
+/// A detected value and where it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inferred {
+    pub value: String,
+    pub reason: String,
+}
+
+impl Inferred {
+    fn new(value: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Everything `cyrus init --detect` found
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    pub name: Inferred,
+    pub language: Inferred,
+    pub version: Inferred,
+    pub package_manager: Inferred,
+    /// Script name to command, with the file it came from as the reason
+    pub scripts: BTreeMap<String, Inferred>,
+    pub dependencies: Vec<String>,
+    pub dev_dependencies: Vec<String>,
+}
+
+impl Detection {
+    pub fn to_project(&self) -> Project {
+        let mut project = Project::new(
+            self.name.value.clone(),
+            self.language.value.clone(),
+            self.version.value.clone(),
+            self.package_manager.value.clone(),
+        );
+
+        // Use what the repository has instead of the language's stock scripts
+        project.scripts = self.scripts.iter()
+            .map(|(name, command)| (name.clone(), command.value.as_str().into()))
+            .collect();
+        project.custom_aliases.clear();
+        project.dependencies = self.dependencies.clone();
+        project.dev_dependencies = self.dev_dependencies.clone();
+        project
+    }
+}
+
+/// The project's language, from the first marker file present
+pub fn detect_language(dir: &Path) -> Option<Inferred> {
+    LANGUAGE_MARKERS.iter()
+        .find(|(file, _)| dir.join(file).is_file())
+        .map(|(file, language)| Inferred::new(*language, *file))
+}
+
+/// Infer name, language, version, package manager, scripts and dependencies for `dir`
+pub fn detect(dir: &Path) -> Result<Option<Detection>> {
+    detect_as(dir, None)
+}
+
+/// Like [`detect`], but for a language chosen up front
+pub fn detect_as(dir: &Path, language: Option<&str>) -> Result<Option<Detection>> {
+    let language = match language {
+        Some(language) => Inferred::new(language, "--language"),
+        None => match detect_language(dir) {
+            Some(language) => language,
+            None => return Ok(None),
+        },
+    };
+    let Some(handler) = crate::languages::get_language_handler(&language.value) else {
+        return Ok(None);
+    };
+    let config = handler.get_config();
+    let native = NativeManifest::for_language(dir, &language.value)?;
+    let manifest_file = native.as_ref().map(|m| m.kind.file_name()).unwrap_or_default();
+
+    let name = native.as_ref()
+        .and_then(|m| m.name.as_deref())
+        .and_then(|name| name.rsplit('/').next())
+        .map(|name| Inferred::new(name, manifest_file))
+        .unwrap_or_else(|| {
+            let name = dir.file_name().and_then(|n| n.to_str()).unwrap_or("my-project");
+            Inferred::new(name, "directory name")
+        });
+
+    // An exact pin beats the floor a manifest constraint sets
+    let version = version_files::read_pins(dir)?
+        .into_iter()
+        .find(|pin| pin.language == language.value)
+        .map(|pin| {
+            let file = pin.source.file_name().map(|f| f.to_string_lossy().into_owned()).unwrap_or_default();
+            Inferred::new(pin.version, file)
+        })
+        .or_else(|| {
+            let version = native.as_ref()?.version.clone()?;
+            Some(Inferred::new(version, version_reason(&language.value, manifest_file)))
+        })
+        .or_else(|| python_requires(dir))
+        .unwrap_or_else(|| Inferred::new(config.default_version.clone(), "cyrus default"));
+
+    let package_manager = PACKAGE_MANAGER_MARKERS.iter()
+        .find(|(file, pm)| config.package_managers.iter().any(|p| p == pm) && dir.join(file).is_file())
+        .map(|(file, pm)| Inferred::new(*pm, *file))
+        .or_else(|| {
+            let pm = native.as_ref()?.package_manager.clone()?;
+            config.package_managers.contains(&pm).then(|| Inferred::new(pm, manifest_file))
+        })
+        .unwrap_or_else(|| Inferred::new(config.default_package_manager.clone(), "cyrus default"));
+
+    // Manifest scripts first, then make and just targets under names not already taken.
+    // Both run through their own tool, which sets up PATH (`node_modules/.bin`, ...) as usual.
+    let mut scripts: BTreeMap<String, Inferred> = native.as_ref()
+        .map(|m| {
+            m.scripts.keys()
+                .map(|name| (name.clone(), Inferred::new(m.script_command(name, &package_manager.value), manifest_file)))
+                .collect()
+        })
+        .unwrap_or_default();
+    for (file, runner) in [("Makefile", "make"), ("justfile", "just"), ("Justfile", "just")] {
+        let Ok(content) = std::fs::read_to_string(dir.join(file)) else {
+            continue;
+        };
+        let targets = if runner == "make" { make_targets(&content) } else { just_recipes(&content) };
+        for target in targets {
+            scripts.entry(target.clone())
+                .or_insert_with(|| Inferred::new(format!("{} {}", runner, target), file));
+        }
+    }
+
+    let (dependencies, dev_dependencies) = native
+        .map(|m| (m.dependencies, m.dev_dependencies))
+        .unwrap_or_default();
+
+    Ok(Some(Detection {
+        name,
+        language,
+        version,
+        package_manager,
+        scripts,
+        dependencies,
+        dev_dependencies,
+    }))
+}
+
+fn version_reason(language: &str, manifest_file: &str) -> String {
+    let field = match language {
+        "javascript" => "engines.node",
+        "python" => "python requirement",
+        "rust" => "rust-version",
+        "golang" => "go directive",
+        "php" => "require.php",
+        "ruby" => "ruby directive",
+        _ => return manifest_file.to_string(),
+    };
+    format!("{} in {}", field, manifest_file)
+}
+
+/// `python_requires` from setup.py or setup.cfg
+fn python_requires(dir: &Path) -> Option<Inferred> {
+    let pattern = regex::Regex::new(r#"python_requires\s*=\s*["']?([^"'\n,)]+)"#).unwrap();
+
+    ["setup.py", "setup.cfg"].iter().find_map(|file| {
+        let content = std::fs::read_to_string(dir.join(file)).ok()?;
+        let constraint = pattern.captures(&content)?.get(1)?.as_str().trim().to_string();
+        let version = manifest::version_from_constraint(&constraint)?;
+        Some(Inferred::new(version, format!("python_requires in {}", file)))
+    })
+}
+
+/// Explicit targets of a Makefile, skipping special (`.PHONY`), pattern and file targets
+pub fn make_targets(content: &str) -> Vec<String> {
+    let rule = regex::Regex::new(r"^([A-Za-z][A-Za-z0-9_-]*)\s*:([^=]|$)").unwrap();
+    let mut targets = Vec::new();
+
+    for line in content.lines() {
+        if let Some(captures) = rule.captures(line) {
+            let target = captures[1].to_string();
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+    targets
+}
+
+/// Recipe names of a justfile, skipping settings, aliases and private `_recipes`
+pub fn just_recipes(content: &str) -> Vec<String> {
+    let recipe = regex::Regex::new(r"^@?([A-Za-z][A-Za-z0-9_-]*)(\s+[^:=]*)?:([^=]|$)").unwrap();
+    const KEYWORDS: &[&str] = &["set", "alias", "export", "import", "mod"];
+
+    content.lines()
+        .filter_map(|line| recipe.captures(line))
+        .map(|captures| captures[1].to_string())
+        .filter(|name| !KEYWORDS.contains(&name.as_str()))
+        .collect()
+}
@@ -0,0 +1,186 @@
+//! Format-preserving edits to config files
+//! src/core/document.rs
+//!
+//! TOML files are edited through `toml_edit`, so comments, key order and
+//! formatting of everything that didn't change survive a save. YAML and JSON
+//! carry no comments and are simply re-serialized.
+
+use anyhow::{Context, Result};
+use toml::value::{Table, Value};
+use toml_edit::{DocumentMut, Item, TableLike};
+
+use super::config_format::ConfigFormat;
+
+/// Rewrite `content` so it holds exactly `table`, touching only what differs
+pub fn update(content: &str, table: &Table) -> Result<String> {
+    let mut document: DocumentMut = content.parse().context("Failed to parse TOML")?;
+    sync_table(document.as_table_mut(), table, true);
+    Ok(document.to_string())
+}
+
+fn sync_table(existing: &mut dyn TableLike, table: &Table, top_level: bool) {
+    let stale: Vec<String> = existing.iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !table.contains_key(key))
+        .collect();
+    for key in stale {
+        existing.remove(&key);
+    }
+
+    for (key, value) in table {
+        match (existing.get_mut(key), value) {
+            (Some(item), Value::Table(entries)) if item.is_table() => {
+                sync_table(item.as_table_like_mut().expect("checked is_table"), entries, false);
+            },
+            (Some(Item::Value(current)), value) => {
+                if !same(current, value) {
+                    replace(current, value);
+                }
+            },
+            (Some(item), value) => *item = new_item(value, top_level),
+            (None, value) => {
+                existing.insert(key, new_item(value, top_level));
+            },
+        }
+    }
+}
+
+/// A `[section]` for top-level tables, an inline value for everything else
+fn new_item(value: &Value, top_level: bool) -> Item {
+    match value {
+        Value::Table(entries) if top_level => {
+            let mut table = toml_edit::Table::new();
+            for (key, value) in entries {
+                table.insert(key, Item::Value(to_edit(value)));
+            }
+            Item::Table(table)
+        },
+        value => Item::Value(to_edit(value)),
+    }
+}
+
+fn to_edit(value: &Value) -> toml_edit::Value {
+    match value {
+        Value::String(s) => s.as_str().into(),
+        Value::Integer(i) => (*i).into(),
+        Value::Float(f) => (*f).into(),
+        Value::Boolean(b) => (*b).into(),
+        Value::Datetime(datetime) => datetime.to_string()
+            .parse::<toml_edit::Datetime>()
+            .map(toml_edit::Value::from)
+            .unwrap_or_else(|_| datetime.to_string().into()),
+        Value::Array(items) => toml_edit::Value::Array(items.iter().map(to_edit).collect()),
+        Value::Table(entries) => toml_edit::Value::InlineTable(
+            entries.iter().map(|(key, value)| (key.as_str(), to_edit(value))).collect()
+        ),
+    }
+}
+
+/// Whether an edited value already holds `value`, ignoring formatting
+fn same(current: &toml_edit::Value, value: &Value) -> bool {
+    let mut bare = current.clone();
+    bare.decor_mut().clear();
+    toml::from_str::<Table>(&format!("v = {}", bare))
+        .map(|table| table.get("v") == Some(value))
+        .unwrap_or(false)
+}
+
+/// Swap in a new value, keeping the old one's surrounding whitespace and comment
+fn replace(current: &mut toml_edit::Value, value: &Value) {
+    let decor = current.decor().clone();
+    *current = to_edit(value);
+    *current.decor_mut() = decor;
+}
+
+/// A command-line value as TOML (`3000`, `true`, `["a"]`, `{ version = "20" }`), if it parses as one
+pub fn parse_value(raw: &str) -> Option<Value> {
+    toml::from_str::<Table>(&format!("v = {}", raw)).ok()?.remove("v")
+}
+
+/// The value at `dotted.key`
+pub fn get<'a>(table: &'a Table, key: &str) -> Option<&'a Value> {
+    let mut segments = key.split('.');
+    let mut value = table.get(segments.next()?)?;
+    for segment in segments {
+        value = value.as_table()?.get(segment)?;
+    }
+    Some(value)
+}
+
+/// Set `dotted.key` in a config file's `content`, creating tables on the way
+pub fn set(format: ConfigFormat, content: &str, key: &str, value: Value) -> Result<String> {
+    let (parents, last) = split_key(key)?;
+
+    if format != ConfigFormat::Toml {
+        let mut table = format.parse(content)?;
+        let mut current = &mut table;
+        for segment in &parents {
+            current = current.entry(segment.to_string())
+                .or_insert_with(|| Value::Table(Table::new()))
+                .as_table_mut()
+                .with_context(|| format!("{} is not a table", segment))?;
+        }
+        current.insert(last.to_string(), value);
+        return format.serialize(&table);
+    }
+
+    let mut document: DocumentMut = content.parse().context("Failed to parse TOML")?;
+    let mut current: &mut dyn TableLike = document.as_table_mut();
+    for segment in &parents {
+        if current.get(segment).is_none() {
+            current.insert(segment, toml_edit::table());
+        }
+        current = current.get_mut(segment)
+            .and_then(Item::as_table_like_mut)
+            .with_context(|| format!("{} is not a table", segment))?;
+    }
+    match current.get_mut(last) {
+        Some(Item::Value(existing)) => replace(existing, &value),
+        _ => {
+            current.insert(last, Item::Value(to_edit(&value)));
+        },
+    }
+    Ok(document.to_string())
+}
+
+/// Remove `dotted.key` from a config file's `content`; `None` when it isn't there
+pub fn unset(format: ConfigFormat, content: &str, key: &str) -> Result<Option<String>> {
+    let (parents, last) = split_key(key)?;
+
+    if format != ConfigFormat::Toml {
+        let mut table = format.parse(content)?;
+        let mut current = &mut table;
+        for segment in &parents {
+            match current.get_mut(*segment).and_then(Value::as_table_mut) {
+                Some(next) => current = next,
+                None => return Ok(None),
+            }
+        }
+        if current.remove(last).is_none() {
+            return Ok(None);
+        }
+        return format.serialize(&table).map(Some);
+    }
+
+    let mut document: DocumentMut = content.parse().context("Failed to parse TOML")?;
+    let mut current: &mut dyn TableLike = document.as_table_mut();
+    for segment in &parents {
+        match current.get_mut(segment).and_then(Item::as_table_like_mut) {
+            Some(next) => current = next,
+            None => return Ok(None),
+        }
+    }
+    if current.remove(last).is_none() {
+        return Ok(None);
+    }
+    Ok(Some(document.to_string()))
+}
+
+fn split_key(key: &str) -> Result<(Vec<&str>, &str)> {
+    let mut segments: Vec<&str> = key.split('.').collect();
+    if segments.iter().any(|segment| segment.is_empty()) {
+        anyhow::bail!("Invalid key '{}'", key);
+    }
+    let last = segments.pop().unwrap_or_default();
+    Ok((segments, last))
+}
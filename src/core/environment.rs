@@ -11,6 +11,8 @@ pub struct Environment {
     pub package_manager: String,
     pub variables: HashMap<String, String>,
     pub paths: Vec<PathBuf>,
+    /// Shared library directories (LD_LIBRARY_PATH / DYLD_FALLBACK_LIBRARY_PATH)
+    pub library_paths: Vec<PathBuf>,
 }
 
 impl Environment {
@@ -21,6 +23,7 @@ impl Environment {
             package_manager,
             variables: HashMap::new(),
             paths: Vec::new(),
+            library_paths: Vec::new(),
         }
     }
 
@@ -32,12 +35,61 @@ impl Environment {
         self.paths.push(path);
     }
 
-    pub fn get_executable_path(&self, base_path: &PathBuf) -> PathBuf {
-        match self.language.as_str() {
-            "python" => base_path.join("bin").join("python"),
-            "javascript" => base_path.join("bin").join("node"),
-            "golang" => base_path.join("bin").join("go"),
-            _ => base_path.join("bin").join(&self.language),
+    pub fn add_library_path(&mut self, path: PathBuf) {
+        self.library_paths.push(path);
+    }
+
+    /// Combine with another toolchain's environment; `self` keeps PATH precedence
+    pub fn merge(&mut self, other: &Environment) {
+        self.paths.extend(other.paths.iter().cloned());
+        self.library_paths.extend(other.library_paths.iter().cloned());
+        for (key, value) in &other.variables {
+            self.variables.entry(key.clone()).or_insert_with(|| value.clone());
         }
     }
+
+    /// Variables for a child process, with `paths` prepended to the current PATH
+    pub fn to_env_vars(&self) -> Result<HashMap<String, String>> {
+        let mut vars = self.variables.clone();
+
+        if let Some(path) = prepend_paths("PATH", &self.paths)? {
+            vars.insert("PATH".to_string(), path);
+        }
+
+        let library_var = if cfg!(target_os = "macos") {
+            "DYLD_FALLBACK_LIBRARY_PATH"
+        } else {
+            "LD_LIBRARY_PATH"
+        };
+        // Only directories that exist; an empty entry would mean the current directory
+        let library_paths: Vec<PathBuf> = self.library_paths.iter().filter(|p| p.is_dir()).cloned().collect();
+        if !cfg!(windows) {
+            if let Some(value) = prepend_paths(library_var, &library_paths)? {
+                vars.insert(library_var.to_string(), value);
+            }
+        }
+
+        Ok(vars)
+    }
+
+    /// First executable called `name` in this environment's bin directories
+    pub fn find_executable(&self, name: &str) -> Option<PathBuf> {
+        let file_name = format!("{}{}", name, std::env::consts::EXE_SUFFIX);
+        self.paths
+            .iter()
+            .map(|dir| dir.join(&file_name))
+            .find(|candidate| candidate.is_file())
+    }
+}
+
+fn prepend_paths(variable: &str, paths: &[PathBuf]) -> Result<Option<String>> {
+    if paths.is_empty() {
+        return Ok(None);
+    }
+
+    let current = std::env::var_os(variable).unwrap_or_default();
+    let joined = std::env::join_paths(
+        paths.iter().cloned().chain(std::env::split_paths(&current)),
+    )?;
+    Ok(Some(joined.to_string_lossy().into_owned()))
 }
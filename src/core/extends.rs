@@ -0,0 +1,201 @@
+//! Config inheritance through `extends`
+//! src/core/extends.rs
+//!
+//! ```toml
+//! extends = ["../shared/cyrus.base.toml", "profile:enterprise"]
+//! ```
+//!
+//! Bases are applied in order, then the file itself on top: later layers win.
+//! `scripts`, `custom_aliases`, `environment`, `toolchains`, `tasks` and
+//! `processes` are merged entry by entry (an entry is replaced whole); every
+//! other key is replaced outright. `name` is never inherited. Paths are
+//! relative to the file that names them, and bases may be TOML, YAML or JSON;
+//! `profile:<name>` contributes a built-in profile's aliases and environment.
+
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use toml::value::{Table, Value};
+
+use super::{config_format, migration};
+use crate::config::profiles::ProfileManager;
+
+/// Tables inherited entry by entry instead of replaced
+pub const MERGED_TABLES: &[&str] = &["scripts", "custom_aliases", "environment", "toolchains", "tasks", "processes"];
+
+/// Keys a base never passes on
+const NOT_INHERITED: &[&str] = &["name", "extends", "schema_version"];
+
+/// A file with its `extends` chain applied
+#[derive(Debug, Clone, Default)]
+pub struct Merged {
+    pub table: Table,
+    /// Key (`language`, `scripts.build`, ...) to the file or profile that set it
+    pub origins: BTreeMap<String, String>,
+    /// What the bases alone contribute
+    pub inherited: Table,
+}
+
+impl Merged {
+    pub fn origin(&self, key: &str) -> &str {
+        self.origins.get(key).map(String::as_str).unwrap_or_default()
+    }
+}
+
+/// Apply the `extends` chain of `table`, which was read from `path`
+pub fn resolve(path: &Path, table: Table) -> Result<Merged> {
+    let label = path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    let mut stack = vec![path.canonicalize().unwrap_or_else(|_| path.to_path_buf())];
+    resolve_with(path, table, &label, &mut stack)
+}
+
+fn resolve_with(path: &Path, table: Table, label: &str, stack: &mut Vec<PathBuf>) -> Result<Merged> {
+    let mut merged = Merged::default();
+
+    for reference in references(&table).with_context(|| format!("Invalid extends in {}", label))? {
+        let mut base = match reference.strip_prefix("profile:") {
+            Some(name) => {
+                let table = profile_table(name)?;
+                let origins = origins_of(&table, &reference);
+                Merged { table, origins, inherited: Table::new() }
+            },
+            None => {
+                let base_label = Path::new(label).parent().unwrap_or(Path::new("")).join(&reference);
+                let base_path = path.parent().unwrap_or(Path::new("")).join(&reference);
+                load_base(&base_path, &base_label.to_string_lossy(), stack)?
+            },
+        };
+        for key in NOT_INHERITED {
+            base.table.remove(*key);
+        }
+        overlay(&mut merged, base.table, &base.origins);
+    }
+
+    merged.inherited = merged.table.clone();
+    let own = origins_of(&table, label);
+    overlay(&mut merged, table, &own);
+    Ok(merged)
+}
+
+fn load_base(path: &Path, label: &str, stack: &mut Vec<PathBuf>) -> Result<Merged> {
+    let canonical = path.canonicalize()
+        .with_context(|| format!("extends: {} not found", path.display()))?;
+
+    if let Some(start) = stack.iter().position(|seen| *seen == canonical) {
+        let cycle: Vec<String> = stack[start..].iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect();
+        anyhow::bail!("extends cycle: {}", cycle.join(" → "));
+    }
+
+    let mut table = config_format::read_table(path)?;
+    // Shared bases are upgraded in memory only; they may belong to other projects
+    migration::migrate(&mut table)?;
+
+    stack.push(canonical);
+    let merged = resolve_with(path, table, label, stack);
+    stack.pop();
+    merged
+}
+
+/// `extends` as a list; a single string is accepted too
+fn references(table: &Table) -> Result<Vec<String>> {
+    match table.get("extends") {
+        None => Ok(Vec::new()),
+        Some(Value::String(reference)) => Ok(vec![reference.clone()]),
+        Some(Value::Array(references)) => references.iter()
+            .map(|reference| reference.as_str()
+                .map(str::to_string)
+                .context("extends entries must be strings"))
+            .collect(),
+        Some(_) => anyhow::bail!("extends must be a list of paths or profile:<name> references"),
+    }
+}
+
+/// Lay `layer` over `merged`, taking each key's origin from `layer_origins`
+fn overlay(merged: &mut Merged, layer: Table, layer_origins: &BTreeMap<String, String>) {
+    for (key, value) in layer {
+        let prefix = format!("{}.", key);
+
+        match (merged.table.get_mut(&key), value) {
+            (Some(Value::Table(entries)), Value::Table(layer_entries)) if MERGED_TABLES.contains(&key.as_str()) => {
+                for (name, entry) in layer_entries {
+                    let dotted = format!("{}{}", prefix, name);
+                    if let Some(origin) = layer_origins.get(&dotted) {
+                        merged.origins.insert(dotted, origin.clone());
+                    }
+                    entries.insert(name, entry);
+                }
+            },
+            (_, value) => {
+                merged.origins.retain(|k, _| *k != key && !k.starts_with(&prefix));
+                for (k, origin) in layer_origins.iter().filter(|(k, _)| **k == key || k.starts_with(&prefix)) {
+                    merged.origins.insert(k.clone(), origin.clone());
+                }
+                merged.table.insert(key, value);
+            },
+        }
+    }
+}
+
+/// Every key of `table` attributed to `label`
+fn origins_of(table: &Table, label: &str) -> BTreeMap<String, String> {
+    let mut origins = BTreeMap::new();
+    for (key, value) in table {
+        match value {
+            Value::Table(entries) if MERGED_TABLES.contains(&key.as_str()) => {
+                for name in entries.keys() {
+                    origins.insert(format!("{}.{}", key, name), label.to_string());
+                }
+            },
+            _ => {
+                origins.insert(key.clone(), label.to_string());
+            },
+        }
+    }
+    origins
+}
+
+/// The aliases and environment of a built-in profile, as cyrus.toml keys
+fn profile_table(name: &str) -> Result<Table> {
+    let manager = ProfileManager::new();
+    let profiles = manager.list_profiles();
+    let Some(profile) = profiles.iter().find(|profile| profile.name == name) else {
+        let mut names: Vec<&str> = profiles.iter().map(|profile| profile.name.as_str()).collect();
+        names.sort();
+        anyhow::bail!("extends: unknown profile '{}' (available: {})", name, names.join(", "));
+    };
+
+    let mut table = Table::new();
+    for (key, values) in [("custom_aliases", &profile.global_aliases), ("environment", &profile.environment_vars)] {
+        if !values.is_empty() {
+            table.insert(key.to_string(), Value::Table(string_table(values)));
+        }
+    }
+    Ok(table)
+}
+
+fn string_table(values: &HashMap<String, String>) -> Table {
+    values.iter()
+        .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+        .collect()
+}
+
+/// Drop from a serialized project whatever it would inherit unchanged anyway
+pub fn strip_inherited(table: &mut Table, inherited: &Table) {
+    for (key, base) in inherited {
+        if table.get(key) == Some(base) {
+            table.remove(key);
+            continue;
+        }
+        if !MERGED_TABLES.contains(&key.as_str()) {
+            continue;
+        }
+        if let (Some(Value::Table(entries)), Value::Table(base_entries)) = (table.get_mut(key), base) {
+            entries.retain(|name, entry| base_entries.get(name) != Some(entry));
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Toolchains installed by other version managers, adopted by `cyrus migrate`
+//! src/core/external.rs
+//!
+//! Adopted installs are registered under `~/.cyrus/languages` as symlinks (or
+//! copies) and get a receipt in `~/.cyrus/receipts/<language>/<version>.json`
+//! so cyrus never deletes the original.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use super::version_files;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExternalTool {
+    Asdf,
+    Pyenv,
+    Nvm,
+    Rbenv,
+    Sdkman,
+}
+
+/// A toolchain found in another tool's install directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalInstall {
+    pub language: String,
+    pub version: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AdoptMode {
+    Symlink,
+    Copy,
+}
+
+/// Marks a cyrus install as belonging to another tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Receipt {
+    pub external: bool,
+    pub tool: ExternalTool,
+    pub source: PathBuf,
+    pub mode: AdoptMode,
+}
+
+impl FromStr for ExternalTool {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "asdf" => Ok(ExternalTool::Asdf),
+            "pyenv" => Ok(ExternalTool::Pyenv),
+            "nvm" => Ok(ExternalTool::Nvm),
+            "rbenv" => Ok(ExternalTool::Rbenv),
+            "sdkman" => Ok(ExternalTool::Sdkman),
+            other => Err(format!("unknown tool '{}' (expected asdf, pyenv, nvm, rbenv or sdkman)", other)),
+        }
+    }
+}
+
+impl fmt::Display for ExternalTool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExternalTool::Asdf => write!(f, "asdf"),
+            ExternalTool::Pyenv => write!(f, "pyenv"),
+            ExternalTool::Nvm => write!(f, "nvm"),
+            ExternalTool::Rbenv => write!(f, "rbenv"),
+            ExternalTool::Sdkman => write!(f, "sdkman"),
+        }
+    }
+}
+
+impl ExternalTool {
+    /// The tool's data directory, honouring its own environment variable
+    pub fn root(&self, home: &Path) -> PathBuf {
+        let (variable, default) = match self {
+            ExternalTool::Asdf => ("ASDF_DATA_DIR", ".asdf"),
+            ExternalTool::Pyenv => ("PYENV_ROOT", ".pyenv"),
+            ExternalTool::Nvm => ("NVM_DIR", ".nvm"),
+            ExternalTool::Rbenv => ("RBENV_ROOT", ".rbenv"),
+            ExternalTool::Sdkman => ("SDKMAN_DIR", ".sdkman"),
+        };
+
+        std::env::var_os(variable)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(default))
+    }
+
+    /// Every toolchain the tool has installed that cyrus can use
+    pub fn installs(&self, home: &Path) -> Vec<ExternalInstall> {
+        let root = self.root(home);
+
+        let mut installs = match self {
+            ExternalTool::Asdf => {
+                let Ok(plugins) = std::fs::read_dir(root.join("installs")) else {
+                    return Vec::new();
+                };
+                plugins.flatten()
+                    .filter_map(|plugin| {
+                        let name = plugin.file_name().to_string_lossy().into_owned();
+                        let language = crate::languages::canonical_language_name(&name)?;
+                        Some(versions_in(&plugin.path(), language))
+                    })
+                    .flatten()
+                    .collect()
+            },
+            ExternalTool::Pyenv => versions_in(&root.join("versions"), "python"),
+            ExternalTool::Nvm => versions_in(&root.join("versions").join("node"), "javascript"),
+            ExternalTool::Rbenv => versions_in(&root.join("versions"), "ruby"),
+            ExternalTool::Sdkman => versions_in(&root.join("candidates").join("java"), "java"),
+        };
+
+        installs.sort_by(|a, b| {
+            a.language.cmp(&b.language).then_with(|| version_key(&a.version).cmp(&version_key(&b.version)))
+        });
+        installs
+    }
+
+    /// The tool's global version settings as `(language, version)`
+    pub fn global_versions(&self, home: &Path) -> Vec<(String, String)> {
+        let root = self.root(home);
+        let read = |path: PathBuf| std::fs::read_to_string(path).ok();
+        let first_line = |content: String| content.lines().next().map(|line| line.trim().to_string());
+
+        let globals: Vec<(&str, Option<String>)> = match self {
+            ExternalTool::Asdf => {
+                return read(home.join(".tool-versions"))
+                    .map(|content| version_files::parse_file(".tool-versions", &content))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(language, version)| (language.to_string(), version))
+                    .collect();
+            },
+            ExternalTool::Pyenv => vec![("python", read(root.join("version")).and_then(first_line))],
+            ExternalTool::Nvm => vec![("javascript", read(root.join("alias").join("default")).and_then(first_line))],
+            ExternalTool::Rbenv => vec![("ruby", read(root.join("version")).and_then(first_line))],
+            ExternalTool::Sdkman => vec![(
+                "java",
+                std::fs::read_link(root.join("candidates").join("java").join("current"))
+                    .ok()
+                    .and_then(|target| target.file_name().map(|name| name.to_string_lossy().into_owned())),
+            )],
+        };
+
+        globals.into_iter()
+            .filter_map(|(language, raw)| {
+                let version = version_files::normalize_version(language, &raw?)?;
+                Some((language.to_string(), version))
+            })
+            .collect()
+    }
+}
+
+/// Version directories under `dir`, skipping aliases like `current` or `system`
+fn versions_in(dir: &Path, language: &str) -> Vec<ExternalInstall> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries.flatten()
+        .filter(|entry| entry.path().is_dir() && !entry.path().is_symlink())
+        .filter_map(|entry| {
+            let raw = entry.file_name().to_string_lossy().into_owned();
+            Some(ExternalInstall {
+                language: language.to_string(),
+                version: version_files::normalize_version(language, &raw)?,
+                path: entry.path(),
+            })
+        })
+        .collect()
+}
+
+/// Numeric components for ordering, so `3.11.10` sorts after `3.11.4`
+fn version_key(version: &str) -> Vec<u64> {
+    version.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+/// The cyrus-style short version projects usually pin (`3.11` for `3.11.4`, `20` for `20.11.0`)
+pub fn short_version(language: &str, version: &str) -> Option<String> {
+    let parts = match language {
+        "javascript" | "java" => 1,
+        _ => 2,
+    };
+    let short: Vec<&str> = version.split('.').take(parts).collect();
+    let short = short.join(".");
+    (short != version).then_some(short)
+}
+
+pub fn receipt_path(cyrus_dir: &Path, language: &str, version: &str) -> PathBuf {
+    cyrus_dir.join("receipts").join(language).join(format!("{}.json", version))
+}
+
+pub fn read_receipt(cyrus_dir: &Path, language: &str, version: &str) -> Option<Receipt> {
+    let content = std::fs::read_to_string(receipt_path(cyrus_dir, language, version)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Link (or copy) an external install into `target` and write its receipt
+pub fn adopt(
+    cyrus_dir: &Path,
+    tool: ExternalTool,
+    install: &ExternalInstall,
+    version: &str,
+    target: &Path,
+    mode: AdoptMode,
+) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    match mode {
+        AdoptMode::Symlink => symlink_dir(&install.path, target),
+        AdoptMode::Copy => copy_dir(&install.path, target),
+    }
+    .with_context(|| format!("Failed to register {} at {}", install.path.display(), target.display()))?;
+
+    let receipt = Receipt {
+        external: true,
+        tool,
+        source: install.path.clone(),
+        mode,
+    };
+    let path = receipt_path(cyrus_dir, &install.language, version);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(&receipt)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink_dir(source: &Path, target: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(source, target)
+}
+
+#[cfg(windows)]
+fn symlink_dir(source: &Path, target: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_dir(source, target)
+}
+
+fn copy_dir(source: &Path, target: &Path) -> Result<()> {
+    for entry in walkdir::WalkDir::new(source) {
+        let entry = entry?;
+        let destination = target.join(entry.path().strip_prefix(source)?);
+
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&destination)?;
+        } else if entry.file_type().is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(std::fs::read_link(entry.path())?, &destination)?;
+            #[cfg(not(unix))]
+            std::fs::copy(entry.path(), &destination)?;
+        } else {
+            std::fs::copy(entry.path(), &destination)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,183 @@
+//! cyrus.lock: exact toolchain resolutions for reproducible installs
+//! src/core/lockfile.rs
+//!
+//! cyrus.toml says what a project wants (`version = "20"`); cyrus.lock records
+//! the newest release that matched when it was written (`20.11.1`), where it was
+//! downloaded from and the archive digest, per platform, plus the package
+//! manager version in use.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::toolchain;
+use super::Project;
+
+pub const LOCK_FILE: &str = "cyrus.lock";
+
+/// Bumped when the lock format changes incompatibly
+const LOCK_FORMAT: u32 = 1;
+
+const HEADER: &str = "# This file is generated by `cyrus install`. Do not edit it by hand.\n\n";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+
+    #[serde(default, rename = "toolchain", skip_serializing_if = "Vec::is_empty")]
+    pub toolchains: Vec<LockedToolchain>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedToolchain {
+    pub language: String,
+
+    /// Version as written in cyrus.toml
+    pub requested: String,
+
+    /// Exact release that gets installed
+    pub resolved: String,
+
+    pub package_manager: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package_manager_version: Option<String>,
+
+    /// Download per `os-arch` target; empty when installs don't download an archive
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub artifacts: BTreeMap<String, LockedArtifact>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedArtifact {
+    pub url: String,
+
+    /// Filled in by the first install on this target
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// A toolchain cyrus.toml asks for
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolchainRequest {
+    pub language: String,
+    pub version: String,
+    pub package_manager: String,
+}
+
+/// Toolchains a project needs: the primary one first, then `[toolchains]` by name
+pub fn requested_toolchains(project: &Project) -> Vec<ToolchainRequest> {
+    let mut requests = vec![ToolchainRequest {
+        language: project.language.clone(),
+        version: project.version.clone(),
+        package_manager: project.package_manager.clone(),
+    }];
+
+    for (key, spec) in &project.toolchains {
+        let language = toolchain::language_key(key);
+        // Same language as the top-level fields: `Project::problems` rejects it unless it agrees
+        if requests.iter().any(|request| request.language == language) {
+            continue;
+        }
+        requests.push(ToolchainRequest {
+            package_manager: spec.package_manager_for(&language),
+            version: spec.version.clone(),
+            language,
+        });
+    }
+
+    requests
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: LOCK_FORMAT,
+            toolchains: Vec::new(),
+        }
+    }
+}
+
+impl Lockfile {
+    /// The lock next to cyrus.toml, if there is one
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(LOCK_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let lock: Lockfile = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        if lock.version > LOCK_FORMAT {
+            anyhow::bail!(
+                "{} uses lock format {}, this cyrus understands up to {}. Upgrade cyrus.",
+                path.display(), lock.version, LOCK_FORMAT
+            );
+        }
+        Ok(Some(lock))
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self)
+            .context("Failed to serialize cyrus.lock")?;
+        std::fs::write(project_root.join(LOCK_FILE), format!("{}{}", HEADER, content))
+            .context("Failed to write cyrus.lock")
+    }
+
+    pub fn toolchain(&self, language: &str) -> Option<&LockedToolchain> {
+        self.toolchains.iter().find(|t| t.language == language)
+    }
+
+    pub fn toolchain_mut(&mut self, language: &str) -> Option<&mut LockedToolchain> {
+        self.toolchains.iter_mut().find(|t| t.language == language)
+    }
+
+    /// Ways the lock disagrees with cyrus.toml; empty when it is up to date
+    pub fn mismatches(&self, requests: &[ToolchainRequest]) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for request in requests {
+            match self.toolchain(&request.language) {
+                None => problems.push(format!("{} {} is not locked", request.language, request.version)),
+                Some(locked) if locked.requested != request.version => problems.push(format!(
+                    "cyrus.toml asks for {} {} but cyrus.lock was resolved for {}",
+                    request.language, request.version, locked.requested
+                )),
+                Some(locked) if locked.package_manager != request.package_manager => problems.push(format!(
+                    "cyrus.toml uses {} for {} but cyrus.lock records {}",
+                    request.package_manager, request.language, locked.package_manager
+                )),
+                Some(_) => {},
+            }
+        }
+
+        for locked in &self.toolchains {
+            if !requests.iter().any(|r| r.language == locked.language) {
+                problems.push(format!("cyrus.lock still has {} which cyrus.toml no longer uses", locked.language));
+            }
+        }
+
+        problems
+    }
+
+    /// Bring the lock in line with `requests`, keeping entries that still match
+    pub fn relock(&self, requests: &[ToolchainRequest], resolve: impl Fn(&ToolchainRequest) -> LockedToolchain) -> Self {
+        let toolchains = requests
+            .iter()
+            .map(|request| match self.toolchain(&request.language) {
+                Some(locked) if locked.requested == request.version
+                    && locked.package_manager == request.package_manager => locked.clone(),
+                _ => resolve(request),
+            })
+            .collect();
+
+        Self {
+            version: LOCK_FORMAT,
+            toolchains,
+        }
+    }
+}
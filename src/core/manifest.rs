@@ -0,0 +1,625 @@
+//! Native package manifests (package.json, pyproject.toml, Cargo.toml, ...)
+//! src/core/manifest.rs
+//!
+//! `cyrus sync` imports scripts and dependencies from these into cyrus.toml
+//! and exports cyrus scripts back where the format has a place for them
+//! (package.json and composer.json). The package manager owns dependencies,
+//! so they only ever flow from the manifest into cyrus.toml.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use super::{Project, Script};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestKind {
+    PackageJson,
+    Pyproject,
+    CargoToml,
+    GoMod,
+    ComposerJson,
+    Gemfile,
+}
+
+/// Manifests cyrus reads, in detection order
+pub const MANIFESTS: &[ManifestKind] = &[
+    ManifestKind::PackageJson,
+    ManifestKind::Pyproject,
+    ManifestKind::CargoToml,
+    ManifestKind::GoMod,
+    ManifestKind::ComposerJson,
+    ManifestKind::Gemfile,
+];
+
+impl ManifestKind {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ManifestKind::PackageJson => "package.json",
+            ManifestKind::Pyproject => "pyproject.toml",
+            ManifestKind::CargoToml => "Cargo.toml",
+            ManifestKind::GoMod => "go.mod",
+            ManifestKind::ComposerJson => "composer.json",
+            ManifestKind::Gemfile => "Gemfile",
+        }
+    }
+
+    pub fn language(&self) -> &'static str {
+        match self {
+            ManifestKind::PackageJson => "javascript",
+            ManifestKind::Pyproject => "python",
+            ManifestKind::CargoToml => "rust",
+            ManifestKind::GoMod => "golang",
+            ManifestKind::ComposerJson => "php",
+            ManifestKind::Gemfile => "ruby",
+        }
+    }
+
+    /// Whether the format has a scripts section cyrus can export to
+    pub fn has_scripts(&self) -> bool {
+        matches!(self, ManifestKind::PackageJson | ManifestKind::ComposerJson)
+    }
+
+    /// Tools that run this manifest's scripts
+    fn script_runners(&self) -> &'static [&'static str] {
+        match self {
+            ManifestKind::PackageJson => &["npm", "pnpm", "yarn", "bun"],
+            ManifestKind::ComposerJson => &["composer"],
+            _ => &[],
+        }
+    }
+}
+
+/// What a native manifest declares
+#[derive(Debug, Clone, PartialEq)]
+pub struct NativeManifest {
+    pub kind: ManifestKind,
+    pub path: PathBuf,
+    pub name: Option<String>,
+    /// Language version it asks for (`engines.node`, `requires-python`, ...)
+    pub version: Option<String>,
+    pub package_manager: Option<String>,
+    pub scripts: BTreeMap<String, String>,
+    /// `name@version` specs, as in cyrus.toml
+    pub dependencies: Vec<String>,
+    pub dev_dependencies: Vec<String>,
+}
+
+impl NativeManifest {
+    /// The first manifest in `dir`, in [`MANIFESTS`] order
+    pub fn detect(dir: &Path) -> Result<Option<Self>> {
+        for kind in MANIFESTS {
+            if dir.join(kind.file_name()).is_file() {
+                return Self::load(dir, *kind).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// The manifest for `language` in `dir`, if there is one
+    pub fn for_language(dir: &Path, language: &str) -> Result<Option<Self>> {
+        match MANIFESTS.iter().find(|kind| kind.language() == language) {
+            Some(kind) if dir.join(kind.file_name()).is_file() => Self::load(dir, *kind).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn load(dir: &Path, kind: ManifestKind) -> Result<Self> {
+        let path = dir.join(kind.file_name());
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        let mut manifest = Self::parse(kind, &content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        manifest.path = path;
+
+        // Lockfiles tell which JavaScript package manager is in use
+        if kind == ManifestKind::PackageJson && manifest.package_manager.is_none() {
+            manifest.package_manager = [("pnpm-lock.yaml", "pnpm"), ("yarn.lock", "yarn"), ("bun.lockb", "bun"), ("bun.lock", "bun")]
+                .iter()
+                .find(|(lockfile, _)| dir.join(lockfile).exists())
+                .map(|(_, pm)| pm.to_string());
+        }
+        Ok(manifest)
+    }
+
+    pub fn parse(kind: ManifestKind, content: &str) -> Result<Self> {
+        let mut manifest = NativeManifest {
+            kind,
+            path: PathBuf::from(kind.file_name()),
+            name: None,
+            version: None,
+            package_manager: None,
+            scripts: BTreeMap::new(),
+            dependencies: Vec::new(),
+            dev_dependencies: Vec::new(),
+        };
+
+        match kind {
+            ManifestKind::PackageJson => parse_package_json(&mut manifest, content)?,
+            ManifestKind::Pyproject => parse_pyproject(&mut manifest, content)?,
+            ManifestKind::CargoToml => parse_cargo_toml(&mut manifest, content)?,
+            ManifestKind::GoMod => parse_go_mod(&mut manifest, content),
+            ManifestKind::ComposerJson => parse_composer_json(&mut manifest, content)?,
+            ManifestKind::Gemfile => parse_gemfile(&mut manifest, content),
+        }
+        Ok(manifest)
+    }
+
+    /// Whether `name` is a dev dependency here; `None` when the manifest doesn't list it
+    pub fn is_dev_dependency(&self, name: &str) -> Option<bool> {
+        let lists = |specs: &[String]| specs.iter().any(|spec| crate::languages::split_dependency(spec).0 == name);
+        if lists(&self.dev_dependencies) {
+            Some(true)
+        } else if lists(&self.dependencies) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// The cyrus command for the manifest's `name` script. It goes through the
+    /// package manager, which is what puts `node_modules/.bin` or `vendor/bin` on PATH.
+    pub fn script_command(&self, name: &str, package_manager: &str) -> String {
+        let runners = self.kind.script_runners();
+        let runner = std::iter::once(package_manager)
+            .chain(self.package_manager.as_deref())
+            .find(|pm| runners.contains(pm))
+            .or(runners.first().copied())
+            .unwrap_or("npm");
+        let run = if runner == "composer" { "run-script" } else { "run" };
+        shell_words::join([runner, run, name])
+    }
+
+    /// Whether `command` only runs the manifest's own `name` script (`npm run build`, `yarn build`)
+    pub fn runs_script(&self, name: &str, command: &str) -> bool {
+        let Ok(words) = shell_words::split(command) else {
+            return false;
+        };
+        let runners = self.kind.script_runners();
+        match words.as_slice() {
+            [runner, run, script] if run == "run" || run == "run-script" => runners.contains(&runner.as_str()) && script == name,
+            [runner, script] => runners.contains(&runner.as_str()) && script == name,
+            _ => false,
+        }
+    }
+
+    /// Add or replace `scripts` in the manifest file, keeping the rest of it untouched
+    pub fn write_scripts(&self, scripts: &[(String, String)]) -> Result<()> {
+        if scripts.is_empty() {
+            return Ok(());
+        }
+        if !self.kind.has_scripts() {
+            anyhow::bail!("{} has no scripts section", self.kind.file_name());
+        }
+
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        let updated = json_with_scripts(&content, scripts)?;
+        std::fs::write(&self.path, updated)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+fn parse_package_json(manifest: &mut NativeManifest, content: &str) -> Result<()> {
+    let json: serde_json::Value = serde_json::from_str(content)?;
+
+    manifest.name = json["name"].as_str().map(str::to_string);
+    manifest.version = json["engines"]["node"].as_str().and_then(version_from_constraint);
+    // `"packageManager": "pnpm@8.15.0"` (corepack)
+    manifest.package_manager = json["packageManager"].as_str()
+        .map(|spec| spec.split('@').next().unwrap_or(spec).to_string());
+    manifest.scripts = json_strings(&json["scripts"]);
+    manifest.dependencies = json_dependencies(&json["dependencies"], |_| true);
+    manifest.dev_dependencies = json_dependencies(&json["devDependencies"], |_| true);
+    Ok(())
+}
+
+fn parse_composer_json(manifest: &mut NativeManifest, content: &str) -> Result<()> {
+    let json: serde_json::Value = serde_json::from_str(content)?;
+
+    // `php` and `ext-*` entries are platform requirements, not packages
+    let is_package = |name: &str| name.contains('/');
+    manifest.name = json["name"].as_str().map(str::to_string);
+    manifest.version = json["require"]["php"].as_str().and_then(version_from_constraint);
+    manifest.package_manager = Some("composer".to_string());
+    manifest.scripts = json_strings(&json["scripts"]);
+    manifest.dependencies = json_dependencies(&json["require"], is_package);
+    manifest.dev_dependencies = json_dependencies(&json["require-dev"], is_package);
+    Ok(())
+}
+
+fn parse_pyproject(manifest: &mut NativeManifest, content: &str) -> Result<()> {
+    let toml: toml::Value = toml::from_str(content)?;
+    let project = toml.get("project");
+    let poetry = toml.get("tool").and_then(|tool| tool.get("poetry"));
+
+    manifest.name = project.or(poetry)
+        .and_then(|table| table.get("name")?.as_str())
+        .map(str::to_string);
+
+    if let Some(poetry) = poetry {
+        manifest.package_manager = Some("poetry".to_string());
+        manifest.version = poetry.get("dependencies")
+            .and_then(|deps| deps.get("python")?.as_str())
+            .and_then(version_from_constraint);
+        manifest.dependencies = toml_dependencies(poetry.get("dependencies"), |name| name != "python");
+
+        let dev = poetry.get("group")
+            .and_then(|groups| groups.get("dev")?.get("dependencies"))
+            .or_else(|| poetry.get("dev-dependencies"));
+        manifest.dev_dependencies = toml_dependencies(dev, |_| true);
+    }
+
+    // PEP 621 metadata, used by pip, pdm, hatch and recent poetry
+    if let Some(project) = project {
+        if manifest.version.is_none() {
+            manifest.version = project.get("requires-python")
+                .and_then(|value| value.as_str())
+                .and_then(version_from_constraint);
+        }
+        if manifest.dependencies.is_empty() {
+            manifest.dependencies = pep508_list(project.get("dependencies"));
+        }
+        if manifest.dev_dependencies.is_empty() {
+            manifest.dev_dependencies = pep508_list(
+                project.get("optional-dependencies").and_then(|extras| extras.get("dev")),
+            );
+        }
+    }
+    Ok(())
+}
+
+fn parse_cargo_toml(manifest: &mut NativeManifest, content: &str) -> Result<()> {
+    let toml: toml::Value = toml::from_str(content)?;
+    let package = toml.get("package");
+
+    manifest.name = package.and_then(|p| p.get("name")?.as_str()).map(str::to_string);
+    manifest.version = package.and_then(|p| p.get("rust-version")?.as_str()).map(str::to_string);
+    manifest.package_manager = Some("cargo".to_string());
+    manifest.dependencies = toml_dependencies(toml.get("dependencies"), |_| true);
+    manifest.dev_dependencies = toml_dependencies(toml.get("dev-dependencies"), |_| true);
+    Ok(())
+}
+
+fn parse_go_mod(manifest: &mut NativeManifest, content: &str) {
+    let mut in_require = false;
+    manifest.package_manager = Some("go mod".to_string());
+
+    for line in content.lines() {
+        let line = line.trim();
+        let indirect = line.ends_with("// indirect");
+        let line = line.split("//").next().unwrap_or("").trim();
+
+        if let Some(module) = line.strip_prefix("module ") {
+            manifest.name = module.trim().rsplit('/').next().map(str::to_string);
+        } else if let Some(version) = line.strip_prefix("go ") {
+            manifest.version = Some(version.trim().to_string());
+        } else if line == "require (" {
+            in_require = true;
+        } else if in_require && line == ")" {
+            in_require = false;
+        } else if in_require || line.starts_with("require ") {
+            let mut words = line.trim_start_matches("require ").split_whitespace();
+            if let (Some(module), Some(version), false) = (words.next(), words.next(), indirect) {
+                manifest.dependencies.push(format!("{}@{}", module, version));
+            }
+        }
+    }
+}
+
+fn parse_gemfile(manifest: &mut NativeManifest, content: &str) {
+    let gem_line = regex::Regex::new(r#"^gem\s+["']([^"']+)["'](?:\s*,\s*["']([^"']+)["'])?(.*)$"#).unwrap();
+    let quoted = regex::Regex::new(r#"["']([^"']+)["']"#).unwrap();
+    let mut dev_group = false;
+    manifest.package_manager = Some("bundler".to_string());
+
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+
+        if line.starts_with("group ") {
+            dev_group = line.contains(":development") || line.contains(":test");
+        } else if line == "end" {
+            dev_group = false;
+        } else if let Some(rest) = line.strip_prefix("ruby ") {
+            manifest.version = quoted.captures(rest).map(|c| c[1].to_string());
+        } else if let Some(captures) = gem_line.captures(line) {
+            let spec = match captures.get(2) {
+                Some(version) => format!("{}@{}", &captures[1], version.as_str()),
+                None => captures[1].to_string(),
+            };
+            let dev = dev_group || captures.get(3).is_some_and(|rest| rest.as_str().contains(":development"));
+            if dev {
+                manifest.dev_dependencies.push(spec);
+            } else {
+                manifest.dependencies.push(spec);
+            }
+        }
+    }
+}
+
+/// String values of a JSON object; composer's array scripts are skipped
+fn json_strings(value: &serde_json::Value) -> BTreeMap<String, String> {
+    value.as_object()
+        .map(|object| {
+            object.iter()
+                .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn json_dependencies(value: &serde_json::Value, include: impl Fn(&str) -> bool) -> Vec<String> {
+    json_strings(value)
+        .into_iter()
+        .filter(|(name, _)| include(name))
+        .map(|(name, version)| format!("{}@{}", name, version))
+        .collect()
+}
+
+/// `name = "1.0"` or `name = { version = "1.0", ... }`; path/git dependencies keep just the name
+fn toml_dependencies(table: Option<&toml::Value>, include: impl Fn(&str) -> bool) -> Vec<String> {
+    let Some(table) = table.and_then(|t| t.as_table()) else {
+        return Vec::new();
+    };
+
+    table.iter()
+        .filter(|(name, _)| include(name))
+        .map(|(name, value)| {
+            let version = value.as_str().or_else(|| value.get("version")?.as_str());
+            match version {
+                Some(version) if version != "*" => format!("{}@{}", name, version),
+                _ => name.clone(),
+            }
+        })
+        .collect()
+}
+
+/// PEP 508 strings like `requests>=2.31`, written `requests@>=2.31`
+fn pep508_list(value: Option<&toml::Value>) -> Vec<String> {
+    value.and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item.as_str())
+        .map(|requirement| {
+            let requirement = requirement.split(';').next().unwrap_or("").replace(' ', "");
+            match requirement.find(['=', '<', '>', '!', '~']) {
+                Some(index) => format!("{}@{}", &requirement[..index], &requirement[index..]),
+                None => requirement,
+            }
+        })
+        .collect()
+}
+
+/// The version a constraint starts from: `>=18.0.0` is `18.0.0`, `^3.11` is `3.11`
+pub fn version_from_constraint(constraint: &str) -> Option<String> {
+    let start = constraint.find(|c: char| c.is_ascii_digit())?;
+    let version: String = constraint[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    Some(version.trim_end_matches('.').to_string())
+}
+
+/// Insert or replace entries in a JSON file's top-level `"scripts"` object without reformatting it
+fn json_with_scripts(content: &str, scripts: &[(String, String)]) -> Result<String> {
+    let mut updated = content.to_string();
+
+    for (name, command) in scripts {
+        let key = serde_json::to_string(name)?;
+        let value = serde_json::to_string(command)?;
+
+        let Some((start, end)) = scripts_object(&updated) else {
+            // No scripts yet: open a new object right after the top-level brace
+            let brace = updated.find('{').context("Not a JSON object")?;
+            let separator = if updated[brace + 1..].trim_start().starts_with('}') { "" } else { "," };
+            updated.insert_str(brace + 1, &format!("\n  \"scripts\": {{\n    {}: {}\n  }}{}", key, value, separator));
+            continue;
+        };
+
+        let body = &updated[start + 1..end];
+        let existing = regex::Regex::new(&format!(r#"{}\s*:\s*"(?:[^"\\]|\\.)*""#, regex::escape(&key))).unwrap();
+        if let Some(found) = existing.find(body) {
+            let range = start + 1 + found.start()..start + 1 + found.end();
+            updated.replace_range(range, &format!("{}: {}", key, value));
+        } else if body.trim().is_empty() {
+            updated.replace_range(start + 1..end, &format!("\n    {}: {}\n  ", key, value));
+        } else {
+            // Append after the last entry, matching its indentation
+            let last = body.trim_end();
+            let indent = body.lines().rev().find(|l| l.contains(':'))
+                .map(|l| l[..l.len() - l.trim_start().len()].to_string())
+                .unwrap_or_else(|| "    ".to_string());
+            updated.insert_str(start + 1 + last.len(), &format!(",\n{}{}: {}", indent, key, value));
+        }
+    }
+    Ok(updated)
+}
+
+/// Byte range of the top-level `"scripts": { ... }` braces
+fn scripts_object(content: &str) -> Option<(usize, usize)> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut key_start = None;
+    let mut last_key: Option<&str> = None;
+    let mut object_start = None;
+
+    for (index, c) in content.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => {
+                    in_string = false;
+                    if depth == 1 {
+                        last_key = key_start.map(|start| &content[start..index]);
+                    }
+                },
+                _ => {},
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                key_start = Some(index + 1);
+            },
+            '{' => {
+                depth += 1;
+                if depth == 2 && last_key == Some("scripts") {
+                    object_start = Some(index);
+                }
+            },
+            '}' => {
+                if depth == 2 {
+                    if let Some(start) = object_start {
+                        return Some((start, index));
+                    }
+                }
+                depth -= 1;
+            },
+            ',' if depth == 1 => last_key = None,
+            _ => {},
+        }
+    }
+    None
+}
+
+/// Which side wins a script defined differently in both places
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prefer {
+    Cyrus,
+    Native,
+}
+
+impl FromStr for Prefer {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cyrus" => Ok(Prefer::Cyrus),
+            "native" | "manifest" => Ok(Prefer::Native),
+            other => Err(format!("unknown side '{}' (expected cyrus or native)", other)),
+        }
+    }
+}
+
+/// A difference `cyrus sync` will not resolve on its own
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub name: String,
+    pub cyrus: String,
+    pub native: String,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: cyrus.toml has `{}`, the manifest has `{}`", self.name, self.cyrus, self.native)
+    }
+}
+
+/// Everything `cyrus sync` would change
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncPlan {
+    /// Scripts to write into cyrus.toml, as calls through the package manager
+    pub import_scripts: Vec<(String, String)>,
+    /// Scripts to write into the manifest
+    pub export_scripts: Vec<(String, String)>,
+    /// Cyrus scripts the manifest can't hold: sequences, and calls to a script it doesn't have
+    pub cyrus_only: Vec<String>,
+    pub conflicts: Vec<Conflict>,
+    /// Dependency lists as the manifest has them, when they differ from cyrus.toml
+    pub dependencies: Option<Vec<String>>,
+    pub dev_dependencies: Option<Vec<String>>,
+}
+
+impl SyncPlan {
+    pub fn is_empty(&self) -> bool {
+        self.import_scripts.is_empty()
+            && self.export_scripts.is_empty()
+            && self.dependencies.is_none()
+            && self.dev_dependencies.is_none()
+    }
+
+    /// Apply the cyrus.toml side of the plan
+    pub fn apply_to(&self, project: &mut Project) {
+        for (name, command) in &self.import_scripts {
+            project.scripts.insert(name.clone(), Script::from(command.as_str()));
+        }
+        if let Some(dependencies) = &self.dependencies {
+            project.dependencies = dependencies.clone();
+        }
+        if let Some(dev_dependencies) = &self.dev_dependencies {
+            project.dev_dependencies = dev_dependencies.clone();
+        }
+    }
+}
+
+/// Work out what to copy in each direction between `project` and `manifest`
+pub fn plan(project: &Project, manifest: &NativeManifest, prefer: Option<Prefer>) -> SyncPlan {
+    let mut plan = SyncPlan::default();
+
+    for (name, native) in &manifest.scripts {
+        let imported = manifest.script_command(name, &project.package_manager);
+        match project.scripts.get(name).map(exportable) {
+            None => plan.import_scripts.push((name.clone(), imported)),
+            // `build = "npm run build"` already runs the manifest's script
+            Some(Some(cyrus)) if cyrus == *native || manifest.runs_script(name, &cyrus) => {},
+            Some(Some(cyrus)) => match prefer {
+                Some(Prefer::Cyrus) if manifest.kind.has_scripts() => plan.export_scripts.push((name.clone(), cyrus)),
+                Some(Prefer::Native) => plan.import_scripts.push((name.clone(), imported)),
+                _ => plan.conflicts.push(Conflict { name: name.clone(), cyrus, native: native.clone() }),
+            },
+            // A cyrus sequence with the same name as a native script
+            Some(None) => plan.conflicts.push(Conflict {
+                name: name.clone(),
+                cyrus: project.scripts[name].to_string(),
+                native: native.clone(),
+            }),
+        }
+    }
+
+    let mut names: Vec<&String> = project.scripts.keys().collect();
+    names.sort();
+    for name in names {
+        if manifest.scripts.contains_key(name) {
+            continue;
+        }
+        match exportable(&project.scripts[name]) {
+            // Exporting `"dev": "npm run dev"` would make npm call itself forever
+            Some(command) if manifest.runs_script(name, &command) => plan.cyrus_only.push(name.clone()),
+            Some(command) if manifest.kind.has_scripts() => plan.export_scripts.push((name.clone(), command)),
+            _ => plan.cyrus_only.push(name.clone()),
+        }
+    }
+
+    if !same_specs(&project.dependencies, &manifest.dependencies) {
+        plan.dependencies = Some(manifest.dependencies.clone());
+    }
+    if !same_specs(&project.dev_dependencies, &manifest.dev_dependencies) {
+        plan.dev_dependencies = Some(manifest.dev_dependencies.clone());
+    }
+    plan
+}
+
+/// The command line a manifest can hold for this script
+fn exportable(script: &Script) -> Option<String> {
+    match script {
+        Script::Command(command) => Some(command.clone()),
+        Script::Exec { exec } => Some(shell_words::join(exec)),
+        Script::Shell { shell } => Some(shell.clone()),
+        Script::Sequence(_) => None,
+    }
+}
+
+fn same_specs(a: &[String], b: &[String]) -> bool {
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    a.sort();
+    b.sort();
+    a == b
+}
@@ -0,0 +1,112 @@
+//! cyrus.toml schema versions and the migrations between them
+//! src/core/migration.rs
+//!
+//! Every cyrus.toml carries a `schema_version`; files without one are version 1.
+//! Older files are upgraded one step at a time, in memory whenever they load;
+//! only `cyrus config migrate` rewrites the file, keeping a backup of the
+//! original next to it as `cyrus.toml.v<N>.bak`.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use toml::value::Table;
+
+use super::config_format::{self, ConfigFormat};
+use super::document;
+
+/// Schema written by this version of cyrus
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// One step from `from` to `from + 1`
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    apply: fn(&mut Table),
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 1,
+        description: "keep smart aliases off where they were off by default (`enable_aliases` now defaults to true)",
+        apply: |table| {
+            table.entry("enable_aliases").or_insert(toml::Value::Boolean(false));
+        },
+    },
+];
+
+/// Outcome of migrating one file
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationReport {
+    pub from: u32,
+    pub to: u32,
+    pub applied: Vec<&'static str>,
+    /// Copy of the original, when the file was rewritten
+    pub backup: Option<PathBuf>,
+}
+
+impl MigrationReport {
+    pub fn is_needed(&self) -> bool {
+        self.from < self.to
+    }
+}
+
+pub fn schema_version(table: &Table) -> u32 {
+    table.get("schema_version")
+        .and_then(|value| value.as_integer())
+        .map(|version| version as u32)
+        .unwrap_or(1)
+}
+
+/// Upgrade a parsed cyrus.toml in place to the current schema
+pub fn migrate(table: &mut Table) -> Result<Vec<&'static str>> {
+    let version = schema_version(table);
+    if version > CURRENT_SCHEMA_VERSION {
+        anyhow::bail!(
+            "cyrus.toml uses schema_version {}, this cyrus understands up to {}. Upgrade cyrus.",
+            version, CURRENT_SCHEMA_VERSION
+        );
+    }
+
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= version) {
+        (migration.apply)(table);
+        applied.push(migration.description);
+    }
+
+    table.insert("schema_version".to_string(), toml::Value::Integer(CURRENT_SCHEMA_VERSION as i64));
+    Ok(applied)
+}
+
+/// Migrate the project file at `path` (any format), rewriting it (with a backup) unless `check_only`
+pub fn migrate_file(path: &Path, check_only: bool) -> Result<(Table, MigrationReport)> {
+    let mut table = config_format::read_table(path)?;
+
+    let from = schema_version(&table);
+    let applied = migrate(&mut table)?;
+    let mut report = MigrationReport {
+        from,
+        to: CURRENT_SCHEMA_VERSION,
+        applied,
+        backup: None,
+    };
+
+    if report.is_needed() && !check_only {
+        let backup = path.with_file_name(format!(
+            "{}.v{}.bak",
+            path.file_name().unwrap_or_default().to_string_lossy(),
+            from
+        ));
+        std::fs::copy(path, &backup)
+            .with_context(|| format!("Failed to back up {}", path.display()))?;
+
+        let migrated = match ConfigFormat::from_path(path) {
+            ConfigFormat::Toml => document::update(&std::fs::read_to_string(path)?, &table),
+            format => format.serialize(&table),
+        }
+        .with_context(|| format!("Failed to serialize migrated {}", path.display()))?;
+        std::fs::write(path, migrated)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        report.backup = Some(backup);
+    }
+
+    Ok((table, report))
+}
@@ -7,11 +7,27 @@ use anyhow::{Context, Result};
 use std::path::PathBuf;
 use dirs;
 
+pub mod alias;
+pub mod config_format;
+pub mod detect;
+pub mod document;
 pub mod environment;
+pub mod extends;
+pub mod external;
+pub mod lockfile;
+pub mod manifest;
+pub mod migration;
+pub mod procfile;
 pub mod project;
+pub mod script;
+pub mod task;
+pub mod toolchain;
+pub mod venv;
+pub mod version_files;
 
 pub use environment::Environment;
 pub use project::Project;
+pub use script::Script;
 
 /// Main Cyrus core structure
 #[derive(Debug)]
@@ -54,4 +70,28 @@ impl CyrusCore {
     pub fn is_language_installed(&self, language: &str, version: &str) -> bool {
         self.language_path(language, version).exists()
     }
+
+    /// Shared package manager caches, kept across toolchain versions
+    pub fn cache_dir(&self) -> PathBuf {
+        self.cyrus_dir.join("cache")
+    }
+
+    /// Runtime environment (PATH entries and variables) for an installed toolchain
+    pub fn toolchain_environment(&self, language: &str, version: &str, package_manager: &str) -> Environment {
+        let install_path = self.language_path(language, version);
+
+        let mut environment = match crate::languages::get_language_handler(language) {
+            Some(handler) => handler.runtime_environment(version, &install_path, &self.cache_dir()),
+            None => {
+                let mut environment = Environment::new(language.to_string(), version.to_string(), String::new());
+                environment.add_path(install_path.join("bin"));
+                environment
+            }
+        };
+
+        if !package_manager.is_empty() {
+            environment.package_manager = package_manager.to_string();
+        }
+        environment
+    }
 }
@@ -0,0 +1,130 @@
+//! Long-running project processes for `cyrus up`
+//! src/core/procfile.rs
+//!
+//! ```toml
+//! [processes]
+//! api = "uvicorn app.main:app --reload"
+//! web = { run = "npm run dev", cwd = "frontend", env = { PORT = "5173" } }
+//! worker = { run = "celery -A app worker", restart = "always" }
+//! ```
+//!
+//! A plain string is shorthand for `{ run = "...", restart = "on-failure" }`.
+
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+/// What to do when a process exits on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    Never,
+    #[default]
+    OnFailure,
+    Always,
+}
+
+impl RestartPolicy {
+    fn is_default(&self) -> bool {
+        *self == RestartPolicy::default()
+    }
+
+    pub fn should_restart(&self, exit_code: i32) -> bool {
+        match self {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure => exit_code != 0,
+            RestartPolicy::Always => true,
+        }
+    }
+}
+
+impl fmt::Display for RestartPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestartPolicy::Never => write!(f, "never"),
+            RestartPolicy::OnFailure => write!(f, "on-failure"),
+            RestartPolicy::Always => write!(f, "always"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "ProcessEntry", into = "ProcessEntry")]
+pub struct ProcessSpec {
+    /// Command line to run; shell syntax runs through `sh -c`
+    pub run: String,
+
+    #[serde(default)]
+    pub restart: RestartPolicy,
+
+    /// Working directory relative to the project root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+
+    /// Extra environment on top of the project environment
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum ProcessEntry {
+    /// Command line only, restarted on failure
+    Command(String),
+    Detailed {
+        run: String,
+        #[serde(default, skip_serializing_if = "RestartPolicy::is_default")]
+        restart: RestartPolicy,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cwd: Option<PathBuf>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        env: HashMap<String, String>,
+    },
+}
+
+// Described by the shorthand-or-table form it is read from
+impl JsonSchema for ProcessSpec {
+    fn schema_name() -> String {
+        "ProcessSpec".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        ProcessEntry::json_schema(gen)
+    }
+}
+
+impl From<ProcessEntry> for ProcessSpec {
+    fn from(entry: ProcessEntry) -> Self {
+        match entry {
+            ProcessEntry::Command(run) => ProcessSpec {
+                run,
+                restart: RestartPolicy::default(),
+                cwd: None,
+                env: HashMap::new(),
+            },
+            ProcessEntry::Detailed { run, restart, cwd, env } => ProcessSpec {
+                run,
+                restart,
+                cwd,
+                env,
+            },
+        }
+    }
+}
+
+// Written back as the shorthand whenever the command line is all there is
+impl From<ProcessSpec> for ProcessEntry {
+    fn from(spec: ProcessSpec) -> Self {
+        if spec.restart.is_default() && spec.cwd.is_none() && spec.env.is_empty() {
+            return ProcessEntry::Command(spec.run);
+        }
+        ProcessEntry::Detailed {
+            run: spec.run,
+            restart: spec.restart,
+            cwd: spec.cwd,
+            env: spec.env,
+        }
+    }
+}
@@ -3,26 +3,128 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug, Serialize, Deserialize)]
+use super::alias::{self, AliasLayer, AliasSource};
+use super::config_format::{self, ConfigFormat};
+use super::document;
+use super::extends::{self, Merged};
+use super::migration;
+use crate::error::ValidationWarning;
+use super::script::{Invocation, Script, ScriptStep};
+use super::procfile::ProcessSpec;
+use super::task::Task;
+use super::toolchain::{self, ToolchainSpec};
+use super::version_files::{self, VersionPin};
+
+/// A project's cyrus.toml
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Project {
+    /// Schema the file follows; older files are migrated on load (see [`migration`])
+    #[serde(default = "current_schema_version")]
+    pub schema_version: u32,
+
+    /// Base files and `profile:<name>` references this file is layered on (see [`extends`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extends: Vec<String>,
+
     pub name: String,
+
+    /// Primary toolchain; may be left out when `[toolchains]` is given
+    #[serde(default)]
     pub language: String,
+    #[serde(default)]
     pub version: String,
+    #[serde(default)]
     pub package_manager: String,
+
+    /// Further toolchains provisioned and put on PATH alongside the primary one
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub toolchains: BTreeMap<String, ToolchainSpec>,
+
+    #[serde(default)]
     pub dependencies: Vec<String>,
+
+    #[serde(default)]
     pub dev_dependencies: Vec<String>,
-    pub scripts: std::collections::HashMap<String, String>,
+
+    #[serde(default)]
+    pub scripts: std::collections::HashMap<String, Script>,
+
+    #[serde(default)]
     pub environment: std::collections::HashMap<String, String>,
     
-    // New fields for enhanced functionality
-    #[serde(default)]
+    #[serde(default = "default_true")]
     pub enable_aliases: bool,
     
     #[serde(default)]
     pub custom_aliases: std::collections::HashMap<String, String>,
+
+    /// Incremental tasks with dependencies, inputs and outputs
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub tasks: std::collections::HashMap<String, Task>,
+
+    /// Long-running processes started together by `cyrus up`
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub processes: std::collections::HashMap<String, ProcessSpec>,
+
+    /// Membership in a multi-project workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<ProjectWorkspace>,
+
+    /// Template the project was created from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_source: Option<String>,
+
+    #[serde(default, skip_serializing_if = "ProjectMetadata::is_empty")]
+    pub metadata: ProjectMetadata,
+
+    /// What `extends` contributed, left out again on save
+    #[serde(skip)]
+    inherited: Option<toml::value::Table>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectWorkspace {
+    pub root: PathBuf,
+    #[serde(default)]
+    pub members: Vec<String>,
+    #[serde(default)]
+    pub shared_dependencies: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Cyrus version that created the project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cyrus_version: Option<String>,
+}
+
+impl ProjectMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn current_schema_version() -> u32 {
+    migration::CURRENT_SCHEMA_VERSION
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl Project {
@@ -129,40 +231,161 @@ impl Project {
         }
 
         Self {
+            schema_version: migration::CURRENT_SCHEMA_VERSION,
+            extends: Vec::new(),
             name,
             language,
             version,
             package_manager,
+            toolchains: BTreeMap::new(),
             dependencies: Vec::new(),
             dev_dependencies: Vec::new(),
-            scripts,
+            scripts: scripts.into_iter().map(|(name, command)| (name, Script::from(command))).collect(),
             environment: std::collections::HashMap::new(),
             enable_aliases: true, // Enable by default
             custom_aliases,
+            tasks: std::collections::HashMap::new(),
+            processes: std::collections::HashMap::new(),
+            workspace: None,
+            template_source: None,
+            metadata: ProjectMetadata {
+                created_at: Some(chrono::Utc::now()),
+                cyrus_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                ..ProjectMetadata::default()
+            },
+            inherited: None,
         }
     }
 
+    /// Load cyrus.toml, migrating older schemas (with a backup) first
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let content = fs::read_to_string(path)
-            .context("Failed to read cyrus.toml file")?;
-        
-        toml::from_str(&content)
-            .context("Failed to parse cyrus.toml file")
+        Self::load_merged(path).map(|(project, _)| project)
     }
 
+    /// Load cyrus.toml with its `extends` chain applied, keeping where each key came from
+    pub fn load_merged<P: AsRef<Path>>(path: P) -> Result<(Self, Merged)> {
+        let path = path.as_ref();
+        let mut table = config_format::read_table(path)?;
+
+        // Older files are upgraded in memory only; `cyrus config migrate` rewrites them
+        let from = migration::schema_version(&table);
+        migration::migrate(&mut table)?;
+        if from < migration::CURRENT_SCHEMA_VERSION {
+            eprintln!("💡 {} uses schema {}; run `cyrus config migrate` to upgrade it to {}",
+                      path.display(), from, migration::CURRENT_SCHEMA_VERSION);
+        }
+        Self::from_table(path, table)
+    }
+
+    /// A project from the already migrated `table` read from `path`, with its `extends` chain applied
+    pub fn from_table(path: &Path, table: toml::value::Table) -> Result<(Self, Merged)> {
+        let merged = extends::resolve(path, table)?;
+        let mut project: Self = toml::Value::Table(merged.table.clone())
+            .try_into()
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        if !project.extends.is_empty() {
+            project.inherited = Some(merged.inherited.clone());
+        }
+        project.promote_primary_toolchain();
+        Ok((project, merged))
+    }
+
+    /// Without a top-level `language`, the first `[toolchains]` entry becomes the primary one
+    fn promote_primary_toolchain(&mut self) {
+        if !self.language.is_empty() {
+            return;
+        }
+        let Some((key, spec)) = self.toolchains.pop_first() else {
+            return;
+        };
+
+        self.language = toolchain::language_key(&key);
+        self.package_manager = spec.package_manager_for(&self.language);
+        self.version = spec.version;
+    }
+
+    /// Write the project in the format `path`'s extension names; an existing
+    /// cyrus.toml keeps its comments and key order
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let content = toml::to_string_pretty(self)
-            .context("Failed to serialize project configuration")?;
-        
+        let path = path.as_ref();
+        let format = ConfigFormat::from_path(path);
+        let toml::Value::Table(mut table) = toml::Value::try_from(self)
+            .context("Failed to serialize project configuration")? else {
+            unreachable!("a project serializes to a table");
+        };
+        // Inherited values stay in their base file instead of being copied here
+        if let Some(inherited) = &self.inherited {
+            extends::strip_inherited(&mut table, inherited);
+        }
+
+        let content = match fs::read_to_string(path) {
+            Ok(existing) if format == ConfigFormat::Toml => document::update(&existing, &table),
+            _ if self.inherited.is_some() => format.serialize(&table),
+            _ => format.serialize(self),
+        }
+        .context("Failed to serialize project configuration")?;
+
         fs::write(path, content)
-            .context("Failed to write cyrus.toml file")
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// The project file in `root`: cyrus.toml, cyrus.yaml or cyrus.json, cyrus.toml when there is none yet
+    pub fn config_path(root: &Path) -> Result<PathBuf> {
+        Ok(config_format::find(root)?.unwrap_or_else(|| root.join(ConfigFormat::Toml.file_name())))
+    }
+
+    /// The project file in `root`, or a project built from its version files
+    pub fn load_from_dir(root: &Path) -> Result<Self> {
+        if let Some(config_path) = config_format::find(root)? {
+            return Self::load_from_file(config_path);
+        }
+
+        let pins = version_files::read_pins(root)?;
+        Self::from_version_pins(&default_name(root), &pins)
+            .with_context(|| format!("No cyrus.toml or version file found in {}", root.display()))
+    }
+
+    /// The nearest cyrus.toml, falling back to .nvmrc, .python-version and friends
+    pub fn discover() -> Result<Option<(PathBuf, Self)>> {
+        if let Some(root) = Self::find_project_root() {
+            let project = Self::load_from_file(Self::config_path(&root)?)?;
+            return Ok(Some((root, project)));
+        }
+
+        Ok(version_files::discover().and_then(|(root, pins)| {
+            let project = Self::from_version_pins(&default_name(&root), &pins)?;
+            Some((root, project))
+        }))
+    }
+
+    /// A project for the primary pinned language, with the other pins as `[toolchains]`
+    pub fn from_version_pins(name: &str, pins: &[VersionPin]) -> Option<Self> {
+        let primary = pins.first()?;
+        let package_manager = crate::languages::get_language_handler(&primary.language)?
+            .get_config()
+            .default_package_manager
+            .clone();
+
+        let mut project = Self::new(
+            name.to_string(),
+            primary.language.clone(),
+            primary.version.clone(),
+            package_manager,
+        );
+        for pin in &pins[1..] {
+            project.toolchains.insert(pin.language.clone(), ToolchainSpec {
+                version: pin.version.clone(),
+                package_manager: None,
+            });
+        }
+        Some(project)
     }
 
     pub fn find_project_root() -> Option<PathBuf> {
         let mut current = std::env::current_dir().ok()?;
         
         loop {
-            if current.join("cyrus.toml").exists() {
+            if config_format::exists(&current) {
                 return Some(current);
             }
             
@@ -187,7 +410,7 @@ impl Project {
 
         // Then check scripts
         if let Some(script) = self.scripts.get(command) {
-            return Some(script.clone());
+            return Some(script.to_string());
         }
 
         None
@@ -197,11 +420,8 @@ impl Project {
     pub fn resolve_command(&self, command: &str, args: &[String]) -> (String, Vec<String>) {
         // Check if it's an aliased command
         if let Some(aliased) = self.get_aliased_command(command) {
-            let parts: Vec<&str> = aliased.split_whitespace().collect();
-            if let Some(first) = parts.first() {
-                let mut new_args = parts[1..].iter().map(|s| s.to_string()).collect::<Vec<_>>();
-                new_args.extend_from_slice(args);
-                return (first.to_string(), new_args);
+            if let Ok(invocation) = Invocation::parse(&aliased) {
+                return invocation.with_args(args).to_program_args();
             }
         }
 
@@ -242,6 +462,193 @@ impl Project {
         (command.to_string(), args.to_vec())
     }
 
+    /// Expand a `cyrus run` command into the steps to execute.
+    ///
+    /// Lookup follows the alias layers: project custom aliases (which may
+    /// take `$1`/`$@` placeholders), then project scripts (run with their
+    /// npm-style `pre<name>`/`post<name>` hooks and able to reference other
+    /// scripts), then the `inherited` profile and global aliases. Anything
+    /// else goes through package manager prefixing or runs as-is.
+    pub fn plan_command(
+        &self,
+        command: &str,
+        args: &[String],
+        inherited: &[AliasLayer],
+    ) -> Result<Vec<ScriptStep>> {
+        if self.enable_aliases {
+            let layers = self.resolution_layers(inherited);
+            match layers.iter().find(|layer| layer.aliases.contains_key(command)).map(|layer| &layer.source) {
+                Some(AliasSource::ProjectScript) => {
+                    let mut steps = Vec::new();
+                    self.expand_script(command, args, true, &mut Vec::new(), &mut steps)?;
+                    return Ok(steps);
+                },
+                Some(_) => {
+                    let aliases = alias::merge_layers(&self.alias_layers(inherited));
+                    return self.alias_steps(&aliases, command, args);
+                },
+                None => {},
+            }
+        }
+
+        let (program, args) = self.resolve_command(command, args);
+        Ok(vec![ScriptStep {
+            script: None,
+            invocation: Invocation::Exec { program, args },
+        }])
+    }
+
+    /// Alias layers for this project on top of the inherited ones.
+    ///
+    /// Scripts are included for listing and shadowing; they are never
+    /// expanded as plain aliases.
+    pub fn alias_layers(&self, inherited: &[AliasLayer]) -> Vec<AliasLayer> {
+        let mut layers = vec![
+            AliasLayer {
+                source: AliasSource::ProjectAlias,
+                aliases: self.custom_aliases.clone(),
+            },
+        ];
+        layers.extend_from_slice(inherited);
+        layers
+    }
+
+    /// Every layer a `cyrus run` name is looked up in, highest precedence first:
+    /// project aliases, project scripts, then the `inherited` profile and global aliases.
+    /// `plan_command` and `cyrus alias list --resolved` both follow this order.
+    pub fn resolution_layers(&self, inherited: &[AliasLayer]) -> Vec<AliasLayer> {
+        let mut layers = self.alias_layers(&[]);
+        layers.push(self.script_layer());
+        layers.extend_from_slice(inherited);
+        layers
+    }
+
+    fn script_layer(&self) -> AliasLayer {
+        AliasLayer {
+            source: AliasSource::ProjectScript,
+            aliases: self.scripts.iter()
+                .map(|(name, script)| (name.clone(), script.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Expand an alias; a head that names a project script runs that script,
+    /// even when a profile or global alias of the same name exists
+    fn alias_steps(
+        &self,
+        aliases: &std::collections::HashMap<String, String>,
+        command: &str,
+        args: &[String],
+    ) -> Result<Vec<ScriptStep>> {
+        let mut aliases = aliases.clone();
+        for name in self.scripts.keys().filter(|name| !self.custom_aliases.contains_key(*name)) {
+            aliases.remove(name);
+        }
+
+        let expansion = alias::expand(&aliases, command, args)?;
+        let invocation = Invocation::parse(&expansion.command_line)?;
+        if let Invocation::Exec { program, args } = &invocation {
+            if self.scripts.contains_key(program) && !expansion.chain.contains(program) {
+                let mut steps = Vec::new();
+                self.expand_script(program, args, true, &mut Vec::new(), &mut steps)?;
+                return Ok(steps);
+            }
+        }
+
+        Ok(vec![ScriptStep {
+            script: None,
+            invocation,
+        }])
+    }
+
+    fn expand_script(
+        &self,
+        name: &str,
+        args: &[String],
+        with_hooks: bool,
+        stack: &mut Vec<String>,
+        steps: &mut Vec<ScriptStep>,
+    ) -> Result<()> {
+        if stack.iter().any(|s| s == name) {
+            anyhow::bail!("Script cycle detected: {} → {}", stack.join(" → "), name);
+        }
+
+        let script = self.scripts.get(name)
+            .with_context(|| format!("Script '{}' not found", name))?;
+
+        stack.push(name.to_string());
+
+        let pre_hook = format!("pre{}", name);
+        if with_hooks && self.scripts.contains_key(&pre_hook) {
+            self.expand_script(&pre_hook, &[], false, stack, steps)?;
+        }
+
+        self.push_script_steps(name, script, args, stack, steps)?;
+
+        let post_hook = format!("post{}", name);
+        if with_hooks && self.scripts.contains_key(&post_hook) {
+            self.expand_script(&post_hook, &[], false, stack, steps)?;
+        }
+
+        stack.pop();
+        Ok(())
+    }
+
+    /// Steps for a task's `run` command; sequences may reference scripts
+    pub fn plan_task(&self, name: &str, task: &Task, args: &[String]) -> Result<Vec<ScriptStep>> {
+        let mut steps = Vec::new();
+        if let Some(script) = &task.run {
+            self.push_script_steps(name, script, args, &mut Vec::new(), &mut steps)?;
+        } else if !args.is_empty() {
+            anyhow::bail!("Task '{}' has no command and does not accept arguments", name);
+        }
+        Ok(steps)
+    }
+
+    fn push_script_steps(
+        &self,
+        name: &str,
+        script: &Script,
+        args: &[String],
+        stack: &mut Vec<String>,
+        steps: &mut Vec<ScriptStep>,
+    ) -> Result<()> {
+        let step = |invocation: Invocation| ScriptStep {
+            script: Some(name.to_string()),
+            invocation,
+        };
+
+        match script {
+            Script::Command(command_line) => {
+                steps.push(step(Invocation::parse(command_line)?.with_args(args)));
+            },
+            Script::Exec { exec } => {
+                steps.push(step(Invocation::from_argv(exec)?.with_args(args)));
+            },
+            Script::Shell { shell } => {
+                steps.push(step(Invocation::Shell {
+                    command: shell.clone(),
+                    args: args.to_vec(),
+                }));
+            },
+            Script::Sequence(items) => {
+                if !args.is_empty() {
+                    anyhow::bail!("Script '{}' runs a sequence and does not accept arguments", name);
+                }
+
+                for item in items {
+                    if self.scripts.contains_key(item) {
+                        self.expand_script(item, &[], true, stack, steps)?;
+                    } else {
+                        steps.push(step(Invocation::parse(item)?));
+                    }
+                }
+            },
+        }
+
+        Ok(())
+    }
+
     /// Add a custom alias
     pub fn add_alias(&mut self, alias: String, command: String) {
         self.custom_aliases.insert(alias, command);
@@ -256,4 +663,124 @@ impl Project {
     pub fn toggle_aliases(&mut self) {
         self.enable_aliases = !self.enable_aliases;
     }
-}
\ No newline at end of file
+
+    /// Record `spec` (`name` or `name@version`), replacing any existing entry for the package
+    pub fn add_dependency(&mut self, spec: &str, dev: bool) {
+        let (name, _) = crate::languages::split_dependency(spec);
+        self.remove_dependency(name);
+
+        let list = if dev { &mut self.dev_dependencies } else { &mut self.dependencies };
+        list.push(spec.to_string());
+    }
+
+    /// Drop `name` from both lists; `Some(dev)` tells which one it was in
+    pub fn remove_dependency(&mut self, name: &str) -> Option<bool> {
+        let matches = |spec: &String| crate::languages::split_dependency(spec).0 == name;
+        let in_dev = self.dev_dependencies.iter().any(matches);
+        let in_main = self.dependencies.iter().any(matches);
+
+        self.dependencies.retain(|spec| !matches(spec));
+        self.dev_dependencies.retain(|spec| !matches(spec));
+        (in_main || in_dev).then_some(in_dev && !in_main)
+    }
+
+    /// Reject configurations cyrus cannot act on
+    pub fn validate(&self) -> Result<()> {
+        match self.problems().into_iter().next() {
+            Some((_, message)) => anyhow::bail!(message),
+            None => Ok(()),
+        }
+    }
+
+    /// Everything [`Project::validate`] rejects, each with the key it concerns
+    pub fn problems(&self) -> Vec<(String, String)> {
+        let mut problems = Vec::new();
+        let mut problem = |key: &str, message: String| problems.push((key.to_string(), message));
+
+        if self.name.is_empty() {
+            problem("name", "Project name cannot be empty".to_string());
+        }
+        if self.language.is_empty() {
+            problem("language", "Language cannot be empty".to_string());
+        }
+        if !is_valid_version(&self.version) {
+            problem("version", format!("Invalid version '{}'", self.version));
+        }
+        if self.package_manager.is_empty() {
+            problem("package_manager", "Package manager cannot be empty".to_string());
+        }
+        for (key, spec) in &self.toolchains {
+            let location = format!("toolchains.{}", key);
+            if crate::languages::canonical_language_name(key).is_none() {
+                problem(&location, format!("Unknown language '{}' in [toolchains]", key));
+            }
+            if !is_valid_version(&spec.version) {
+                problem(&location, format!("Invalid version '{}' for toolchains.{}", spec.version, key));
+            }
+            // The top-level fields are the primary toolchain; a second, different request would be ignored
+            let conflicting_pm = spec.package_manager.as_ref().is_some_and(|pm| *pm != self.package_manager);
+            if toolchain::language_key(key) == self.language && (spec.version != self.version || conflicting_pm) {
+                problem(&location, format!(
+                    "toolchains.{} conflicts with the primary {} {}; change the top-level version or remove the entry",
+                    key, self.language, self.version
+                ));
+            }
+        }
+        problems
+    }
+
+    /// [`Project::validate`] plus suggestions that don't block anything
+    pub fn validate_comprehensive(&self) -> Result<Vec<ValidationWarning>> {
+        self.validate()?;
+
+        let mut warnings = Vec::new();
+        let total_deps = self.dependencies.len() + self.dev_dependencies.len();
+
+        match self.language.as_str() {
+            "javascript" => {
+                if !self.dependencies.iter().chain(&self.dev_dependencies).any(|d| d.contains("typescript")) {
+                    warnings.push(ValidationWarning::SuggestTypescript);
+                }
+
+                if self.package_manager == "npm" && total_deps > 10 {
+                    warnings.push(ValidationWarning::SuboptimalPackageManager {
+                        pm: "npm".to_string(),
+                        suggested: "pnpm".to_string(),
+                    });
+                }
+            },
+            "python" => {
+                let missing: Vec<String> = ["pytest", "black", "flake8", "mypy"]
+                    .iter()
+                    .filter(|&dep| !self.dev_dependencies.iter().any(|d| d.contains(dep)))
+                    .map(|&dep| dep.to_string())
+                    .collect();
+
+                if !missing.is_empty() {
+                    warnings.push(ValidationWarning::MissingDevDependencies { suggestions: missing });
+                }
+            },
+            _ => {},
+        }
+
+        if total_deps > 50 {
+            warnings.push(ValidationWarning::TooManyDependencies { count: total_deps });
+        }
+
+        Ok(warnings)
+    }
+}
+
+/// `3.11`, `1.2.3-rc.1+build` or a channel name like `stable`
+fn is_valid_version(version: &str) -> bool {
+    !version.is_empty()
+        && version.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))
+}
+
+/// Directory name as a project name
+fn default_name(root: &Path) -> String {
+    root.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("my-project")
+        .to_string()
+}
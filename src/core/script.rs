@@ -0,0 +1,208 @@
+//! Project script parsing and composition
+//! src/core/script.rs
+//!
+//! A script in `cyrus.toml` is one of:
+//!
+//! ```toml
+//! [scripts]
+//! lint = "eslint 'src/**/*.ts'"          # parsed with POSIX shell-words rules
+//! clean = "rm -rf dist && mkdir dist"     # shell operators: run via `sh -c`
+//! fmt = { exec = ["prettier", "--write", "src"] }
+//! release = { shell = "npm version patch && git push --tags" }
+//! ci = ["lint", "test", "build"]          # runs other scripts in order
+//! ```
+//!
+//! Plain strings are exec-form unless they use shell syntax (operators,
+//! redirections, expansions, unquoted globs), in which case they are run
+//! through the shell so they behave exactly as they would in a terminal.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum Script {
+    /// A command line; exec-form or shell-form depending on its syntax
+    Command(String),
+    /// Names of other scripts (or command lines) to run in sequence
+    Sequence(Vec<String>),
+    /// Explicit exec-form argv
+    Exec { exec: Vec<String> },
+    /// Explicit shell-form command
+    Shell { shell: String },
+}
+
+impl From<String> for Script {
+    fn from(command: String) -> Self {
+        Script::Command(command)
+    }
+}
+
+impl From<&str> for Script {
+    fn from(command: &str) -> Self {
+        Script::Command(command.to_string())
+    }
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Script::Command(command) => write!(f, "{}", command),
+            Script::Sequence(steps) => write!(f, "[{}]", steps.join(", ")),
+            Script::Exec { exec } => write!(f, "{}", shell_words::join(exec)),
+            Script::Shell { shell } => write!(f, "sh -c {}", shell_words::quote(shell)),
+        }
+    }
+}
+
+/// How a single command is started
+#[derive(Debug, Clone, PartialEq)]
+pub enum Invocation {
+    /// Run a program directly with an argv
+    Exec { program: String, args: Vec<String> },
+    /// Run a command line through the platform shell; `args` become `"$@"`
+    Shell { command: String, args: Vec<String> },
+}
+
+impl Invocation {
+    /// Parse a command line, choosing exec-form or shell-form from its syntax
+    pub fn parse(command_line: &str) -> Result<Self> {
+        if needs_shell(command_line) {
+            return Ok(Invocation::Shell {
+                command: command_line.to_string(),
+                args: Vec::new(),
+            });
+        }
+
+        let mut words = shell_words::split(command_line)
+            .with_context(|| format!("Failed to parse command: {}", command_line))?;
+
+        if words.is_empty() {
+            anyhow::bail!("Empty command");
+        }
+
+        let program = words.remove(0);
+        Ok(Invocation::Exec { program, args: words })
+    }
+
+    pub fn from_argv(argv: &[String]) -> Result<Self> {
+        let (program, args) = argv
+            .split_first()
+            .context("Empty exec list in script")?;
+
+        Ok(Invocation::Exec {
+            program: program.clone(),
+            args: args.to_vec(),
+        })
+    }
+
+    /// Append user-supplied arguments
+    pub fn with_args(mut self, extra: &[String]) -> Self {
+        match &mut self {
+            Invocation::Exec { args, .. } | Invocation::Shell { args, .. } => {
+                args.extend_from_slice(extra)
+            }
+        }
+        self
+    }
+
+    /// Program and argv to hand to the OS
+    pub fn to_program_args(&self) -> (String, Vec<String>) {
+        match self {
+            Invocation::Exec { program, args } => (program.clone(), args.clone()),
+            Invocation::Shell { command, args } => shell_program_args(command, args),
+        }
+    }
+
+    /// Human-readable form for progress output
+    pub fn display(&self) -> String {
+        match self {
+            Invocation::Exec { program, args } => {
+                let mut argv = vec![program.clone()];
+                argv.extend_from_slice(args);
+                shell_words::join(argv)
+            }
+            Invocation::Shell { command, args } if args.is_empty() => command.clone(),
+            Invocation::Shell { command, args } => {
+                format!("{} {}", command, shell_words::join(args))
+            }
+        }
+    }
+}
+
+/// One command produced by expanding a script, hooks and references included
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptStep {
+    /// Script the step came from, if any
+    pub script: Option<String>,
+    pub invocation: Invocation,
+}
+
+#[cfg(unix)]
+fn shell_program_args(command: &str, args: &[String]) -> (String, Vec<String>) {
+    // `sh -c 'cmd "$@"' name args...` forwards arguments without re-quoting
+    let script = if args.is_empty() {
+        command.to_string()
+    } else {
+        format!("{} \"$@\"", command)
+    };
+
+    let mut argv = vec!["-c".to_string(), script, "cyrus".to_string()];
+    argv.extend_from_slice(args);
+    ("sh".to_string(), argv)
+}
+
+#[cfg(not(unix))]
+fn shell_program_args(command: &str, args: &[String]) -> (String, Vec<String>) {
+    let mut line = command.to_string();
+    for arg in args {
+        line.push(' ');
+        line.push_str(&shell_words::quote(arg));
+    }
+    ("cmd".to_string(), vec!["/C".to_string(), line])
+}
+
+/// Whether a command line relies on shell features that exec-form can't honour
+pub fn needs_shell(command_line: &str) -> bool {
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut escaped = false;
+    let mut at_word_start = true;
+    let mut first_word = true;
+
+    for c in command_line.chars() {
+        if escaped {
+            escaped = false;
+            at_word_start = false;
+            continue;
+        }
+
+        match c {
+            '\\' if !in_single => escaped = true,
+            '\'' if !in_double => in_single = !in_single,
+            '"' if !in_single => in_double = !in_double,
+            '$' | '`' if !in_single => return true,
+            '|' | '&' | ';' | '<' | '>' | '(' | ')' | '\n' if !in_single && !in_double => {
+                return true
+            }
+            '*' | '?' | '[' if !in_single && !in_double => return true,
+            '~' if !in_single && !in_double && at_word_start => return true,
+            // `VAR=value cmd` environment prefixes are shell syntax
+            '=' if !in_single && !in_double && first_word => return true,
+            c if c.is_whitespace() && !in_single && !in_double => {
+                if !at_word_start {
+                    first_word = false;
+                }
+                at_word_start = true;
+                continue;
+            }
+            _ => {}
+        }
+
+        at_word_start = false;
+    }
+
+    false
+}
@@ -0,0 +1,216 @@
+//! Incremental task runner
+//! src/core/task.rs
+//!
+//! Tasks are declared in the `[tasks]` table of `cyrus.toml`:
+//!
+//! ```toml
+//! [tasks.codegen]
+//! run = "protoc --python_out=gen proto/api.proto"
+//! inputs = ["proto/**/*.proto"]
+//! outputs = ["gen/**"]
+//!
+//! [tasks.build]
+//! run = "python -m build"
+//! depends_on = ["codegen"]
+//! inputs = ["src/**/*.py", "gen/**", "pyproject.toml"]
+//! outputs = ["dist/*.whl"]
+//! env = { PYTHONHASHSEED = "0" }
+//! ```
+//!
+//! `cyrus run build` runs `codegen` first, then `build`. A task that declares
+//! `inputs` is skipped when the hash of its command, environment and input
+//! files matches the one recorded at its last successful run and all of its
+//! `outputs` still exist. Tasks without inputs always run.
+
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::script::Script;
+
+/// File (relative to the project root) recording the last successful fingerprints
+pub const STATE_FILE: &str = ".cyrus/tasks.json";
+
+/// Directories never hashed as task inputs
+const IGNORED_DIRS: &[&str] = &[".git", ".cyrus", "node_modules", "target", ".venv", "__pycache__"];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Task {
+    /// Command to run; same forms as `[scripts]`. A task may only aggregate dependencies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run: Option<Script>,
+
+    /// Tasks that must complete first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+
+    /// Globs of files whose content determines whether the task is up to date
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<String>,
+
+    /// Globs of files the task produces; a missing output forces a re-run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<String>,
+
+    /// Extra environment for this task only
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+}
+
+/// Order in which to run `target` and its dependencies (dependencies first)
+pub fn execution_order(tasks: &HashMap<String, Task>, target: &str) -> Result<Vec<String>> {
+    let mut order = Vec::new();
+    visit(tasks, target, &mut Vec::new(), &mut order)?;
+    Ok(order)
+}
+
+fn visit(
+    tasks: &HashMap<String, Task>,
+    name: &str,
+    stack: &mut Vec<String>,
+    order: &mut Vec<String>,
+) -> Result<()> {
+    if order.iter().any(|done| done == name) {
+        return Ok(());
+    }
+
+    if stack.iter().any(|s| s == name) {
+        anyhow::bail!("Task cycle detected: {} → {}", stack.join(" → "), name);
+    }
+
+    let task = match tasks.get(name) {
+        Some(task) => task,
+        None if stack.is_empty() => anyhow::bail!("Task '{}' not found", name),
+        None => anyhow::bail!("Task '{}' depends on unknown task '{}'", stack.join(" → "), name),
+    };
+
+    stack.push(name.to_string());
+    for dependency in &task.depends_on {
+        visit(tasks, dependency, stack, order)?;
+    }
+    stack.pop();
+
+    order.push(name.to_string());
+    Ok(())
+}
+
+/// Hash of everything that decides whether a task needs to run again.
+///
+/// `command` is the planned command line(s) so changes to the task, the
+/// scripts it references or the extra arguments all invalidate the cache.
+pub fn fingerprint(root: &Path, task: &Task, command: &str) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(command.as_bytes());
+    hasher.update([0]);
+
+    let env: BTreeMap<_, _> = task.env.iter().collect();
+    for (key, value) in env {
+        hasher.update(format!("{}={}", key, value).as_bytes());
+        hasher.update([0]);
+    }
+
+    for path in matching_files(root, &task.inputs)? {
+        let content = std::fs::read(root.join(&path))
+            .with_context(|| format!("Failed to read task input {}", path.display()))?;
+        hasher.update(path.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(Sha256::digest(&content));
+    }
+
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Whether every declared output glob matches at least one file
+pub fn outputs_present(root: &Path, task: &Task) -> Result<bool> {
+    for pattern in &task.outputs {
+        if matching_files(root, std::slice::from_ref(pattern))?.is_empty() {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Files under `root` matching any of the globs, relative and sorted
+pub fn matching_files(root: &Path, patterns: &[String]) -> Result<Vec<PathBuf>> {
+    if patterns.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let globs = build_globset(patterns)?;
+    let mut files = Vec::new();
+
+    let walker = WalkDir::new(root).into_iter().filter_entry(|entry| {
+        entry.depth() == 0
+            || !entry.file_type().is_dir()
+            || !IGNORED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref())
+    });
+
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        if globs.is_match(relative) {
+            files.push(relative.to_path_buf());
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn build_globset(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).with_context(|| format!("Invalid glob '{}'", pattern))?);
+        // `gen/**` should also match when written as a bare directory name
+        if !pattern.contains(['*', '?', '[']) {
+            builder.add(Glob::new(&format!("{}/**", pattern.trim_end_matches('/')))?);
+        }
+    }
+    Ok(builder.build()?)
+}
+
+/// Fingerprints of each task's last successful run
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TaskState {
+    #[serde(default)]
+    pub fingerprints: BTreeMap<String, String>,
+}
+
+impl TaskState {
+    pub fn load(root: &Path) -> Self {
+        std::fs::read_to_string(root.join(STATE_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = root.join(STATE_FILE);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .context("Failed to write task state")
+    }
+
+    pub fn is_fresh(&self, name: &str, fingerprint: &str) -> bool {
+        self.fingerprints.get(name).map(|f| f == fingerprint).unwrap_or(false)
+    }
+
+    pub fn record(&mut self, name: &str, fingerprint: String) {
+        self.fingerprints.insert(name.to_string(), fingerprint);
+    }
+
+    pub fn invalidate(&mut self, name: &str) {
+        self.fingerprints.remove(name);
+    }
+}
@@ -0,0 +1,100 @@
+//! Extra toolchains for polyglot projects
+//! src/core/toolchain.rs
+//!
+//! ```toml
+//! language = "python"
+//! version = "3.12"
+//! package_manager = "poetry"
+//!
+//! [toolchains]
+//! node = { version = "20", package_manager = "pnpm" }
+//! golang = "1.22"
+//! ```
+//!
+//! A plain string is shorthand for `{ version = "..." }` with the language's
+//! default package manager. The top-level `language` stays the primary
+//! toolchain; without one, the first `[toolchains]` entry by name takes its place.
+
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "ToolchainEntry", into = "ToolchainEntry")]
+pub struct ToolchainSpec {
+    pub version: String,
+
+    /// Defaults to the language's default package manager
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package_manager: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum ToolchainEntry {
+    /// Version only, with the language's default package manager
+    Version(String),
+    Detailed {
+        version: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        package_manager: Option<String>,
+    },
+}
+
+// Described by the shorthand-or-table form it is read from
+impl JsonSchema for ToolchainSpec {
+    fn schema_name() -> String {
+        "ToolchainSpec".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        ToolchainEntry::json_schema(gen)
+    }
+}
+
+impl From<ToolchainEntry> for ToolchainSpec {
+    fn from(entry: ToolchainEntry) -> Self {
+        match entry {
+            ToolchainEntry::Version(version) => ToolchainSpec {
+                version,
+                package_manager: None,
+            },
+            ToolchainEntry::Detailed { version, package_manager } => ToolchainSpec {
+                version,
+                package_manager,
+            },
+        }
+    }
+}
+
+// Written back as the shorthand whenever the version is all there is
+impl From<ToolchainSpec> for ToolchainEntry {
+    fn from(spec: ToolchainSpec) -> Self {
+        match spec.package_manager {
+            None => ToolchainEntry::Version(spec.version),
+            package_manager => ToolchainEntry::Detailed {
+                version: spec.version,
+                package_manager,
+            },
+        }
+    }
+}
+
+impl ToolchainSpec {
+    /// The package manager to use for `language`
+    pub fn package_manager_for(&self, language: &str) -> String {
+        self.package_manager.clone().unwrap_or_else(|| default_package_manager(language))
+    }
+}
+
+/// Cyrus's language name for a `[toolchains]` key (`node` is `javascript`); unknown keys pass through
+pub fn language_key(key: &str) -> String {
+    crate::languages::canonical_language_name(key)
+        .map(str::to_string)
+        .unwrap_or_else(|| key.to_string())
+}
+
+pub fn default_package_manager(language: &str) -> String {
+    crate::languages::get_language_handler(language)
+        .map(|handler| handler.get_config().default_package_manager.clone())
+        .unwrap_or_default()
+}
@@ -0,0 +1,148 @@
+//! Python virtual environments bound to the project's cyrus toolchain
+//! src/core/venv.rs
+//!
+//! A project venv lives in `.venv` and is created with the cyrus-installed
+//! interpreter. Cyrus records the pinned version in `pyvenv.cfg` so a later
+//! change to cyrus.toml can be detected as a stale venv.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Directory of the project venv, relative to the project root
+pub const VENV_DIR: &str = ".venv";
+
+/// `pyvenv.cfg` key holding the version pinned in cyrus.toml at creation time
+const CYRUS_VERSION_KEY: &str = "cyrus-python-version";
+
+/// The interesting parts of a venv's `pyvenv.cfg`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VenvInfo {
+    /// Directory of the interpreter the venv was created from
+    pub home: Option<PathBuf>,
+    /// Interpreter version as written by `python -m venv`
+    pub version: Option<String>,
+    /// Version pinned in cyrus.toml when cyrus created the venv
+    pub cyrus_version: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VenvStatus {
+    Missing,
+    Current,
+    /// Built with another interpreter version, or its interpreter is gone
+    Stale { reason: String },
+}
+
+/// Where dependencies are reinstalled from after (re)creating a venv
+#[derive(Debug, Clone, PartialEq)]
+pub enum DependencySource {
+    Poetry,
+    Pipenv,
+    Requirements(PathBuf),
+    Declared(Vec<String>),
+}
+
+pub fn venv_path(project_root: &Path) -> PathBuf {
+    project_root.join(VENV_DIR)
+}
+
+/// Executable directory inside a venv
+pub fn bin_dir(venv: &Path) -> PathBuf {
+    venv.join(if cfg!(windows) { "Scripts" } else { "bin" })
+}
+
+impl VenvInfo {
+    pub fn load(venv: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(venv.join("pyvenv.cfg")).ok()?;
+        Some(Self::parse(&content))
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let value = |key: &str| {
+            content.lines()
+                .filter_map(|line| line.split_once('='))
+                .find(|(k, _)| k.trim() == key)
+                .map(|(_, v)| v.trim().to_string())
+        };
+
+        Self {
+            home: value("home").map(PathBuf::from),
+            version: value("version").or_else(|| value("version_info")),
+            cyrus_version: value(CYRUS_VERSION_KEY),
+        }
+    }
+}
+
+/// Record the pinned version in `pyvenv.cfg`; Python ignores unknown keys
+pub fn record_version(venv: &Path, pinned: &str) -> Result<()> {
+    let cfg_path = venv.join("pyvenv.cfg");
+    let content = std::fs::read_to_string(&cfg_path)
+        .with_context(|| format!("Failed to read {}", cfg_path.display()))?;
+
+    let mut lines: Vec<String> = content.lines()
+        .filter(|line| line.split_once('=').map(|(k, _)| k.trim() != CYRUS_VERSION_KEY).unwrap_or(true))
+        .map(str::to_string)
+        .collect();
+    lines.push(format!("{} = {}", CYRUS_VERSION_KEY, pinned));
+
+    std::fs::write(&cfg_path, lines.join("\n") + "\n")
+        .with_context(|| format!("Failed to write {}", cfg_path.display()))?;
+    Ok(())
+}
+
+/// Compare the project venv against the version pinned in cyrus.toml
+pub fn status(project_root: &Path, pinned: &str) -> VenvStatus {
+    let venv = venv_path(project_root);
+    let Some(info) = VenvInfo::load(&venv) else {
+        return VenvStatus::Missing;
+    };
+
+    if let Some(home) = info.home.as_ref().filter(|home| !home.exists()) {
+        return VenvStatus::Stale {
+            reason: format!("its interpreter {} no longer exists", home.display()),
+        };
+    }
+
+    // Prefer what cyrus recorded; fall back to the interpreter's own version
+    match info.cyrus_version.or(info.version) {
+        Some(version) if !version_matches(&version, pinned) && !version_matches(pinned, &version) => {
+            VenvStatus::Stale {
+                reason: format!("it was built with Python {} but cyrus.toml pins {}", version, pinned),
+            }
+        },
+        _ => VenvStatus::Current,
+    }
+}
+
+/// `3.11.4` matches a pin of `3.11` or `3.11.4`
+pub fn version_matches(actual: &str, pinned: &str) -> bool {
+    actual == pinned || actual.starts_with(&format!("{}.", pinned))
+}
+
+/// Lockfiles win over requirements files, which win over `dependencies` in cyrus.toml
+pub fn dependency_source(project_root: &Path, declared: &[String]) -> Option<DependencySource> {
+    if project_root.join("poetry.lock").exists() {
+        return Some(DependencySource::Poetry);
+    }
+    if project_root.join("Pipfile.lock").exists() {
+        return Some(DependencySource::Pipenv);
+    }
+
+    for name in ["requirements.lock", "requirements.txt"] {
+        let path = project_root.join(name);
+        if path.exists() {
+            return Some(DependencySource::Requirements(path));
+        }
+    }
+
+    if !declared.is_empty() {
+        return Some(DependencySource::Declared(declared.to_vec()));
+    }
+    None
+}
+
+/// Put the venv ahead of the bare toolchain, like `source .venv/bin/activate`
+pub fn activate(environment: &mut crate::core::Environment, venv: &Path) {
+    environment.paths.insert(0, bin_dir(venv));
+    environment.add_variable("VIRTUAL_ENV".to_string(), venv.to_string_lossy().into_owned());
+}
@@ -0,0 +1,179 @@
+//! Version pins from other tools' files, used when a project has no cyrus.toml
+//! src/core/version_files.rs
+//!
+//! Precedence:
+//!
+//! 1. A cyrus.toml anywhere up the directory tree always wins.
+//! 2. Otherwise the nearest directory holding any of the files below is the
+//!    project root, and only that directory's files are read. The search
+//!    stops at the repository root (`.git`, `.hg`, `.svn`) and never reaches
+//!    `$HOME`, where global asdf or pyenv pins live.
+//! 3. Within that directory a tool-specific file beats `.tool-versions` for the
+//!    same language.
+//! 4. When several languages are pinned, the first one in [`VERSION_FILES`]
+//!    order is the project's language and the others become `[toolchains]`.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Files understood, in precedence order
+pub const VERSION_FILES: &[&str] = &[
+    "rust-toolchain.toml",
+    "rust-toolchain",
+    "go.mod",
+    ".nvmrc",
+    ".node-version",
+    ".python-version",
+    ".ruby-version",
+    ".java-version",
+    ".tool-versions",
+];
+
+/// A language version pinned by a foreign file
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionPin {
+    /// Canonical cyrus language name
+    pub language: String,
+    pub version: String,
+    /// File the pin was read from
+    pub source: PathBuf,
+}
+
+/// Pins in `dir`, one per language, primary language first
+pub fn read_pins(dir: &Path) -> Result<Vec<VersionPin>> {
+    let mut pins: Vec<VersionPin> = Vec::new();
+
+    for name in VERSION_FILES {
+        let path = dir.join(name);
+        if !path.is_file() {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        for (language, version) in parse_file(name, &content) {
+            if !pins.iter().any(|pin| pin.language == language) {
+                pins.push(VersionPin {
+                    language: language.to_string(),
+                    version,
+                    source: path.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(pins)
+}
+
+/// Directories that mark a repository root, where the search for version files stops
+const VCS_MARKERS: &[&str] = &[".git", ".hg", ".svn"];
+
+/// Nearest directory from the current one that has version files, with its pins
+pub fn discover() -> Option<(PathBuf, Vec<VersionPin>)> {
+    discover_from(&std::env::current_dir().ok()?, dirs::home_dir().as_deref())
+}
+
+/// Like [`discover`], starting at `start` and never reading `home`
+pub fn discover_from(start: &Path, home: Option<&Path>) -> Option<(PathBuf, Vec<VersionPin>)> {
+    let mut current = start.to_path_buf();
+
+    loop {
+        if home == Some(current.as_path()) {
+            return None;
+        }
+
+        if let Ok(pins) = read_pins(&current) {
+            if !pins.is_empty() {
+                return Some((current, pins));
+            }
+        }
+
+        if VCS_MARKERS.iter().any(|marker| current.join(marker).exists()) || !current.pop() {
+            return None;
+        }
+    }
+}
+
+/// `(language, version)` pairs a single file pins
+pub fn parse_file(name: &str, content: &str) -> Vec<(&'static str, String)> {
+    let pin = match name {
+        ".nvmrc" | ".node-version" => first_line(content)
+            .and_then(|line| numeric(line.trim_start_matches('v')))
+            .map(|version| ("javascript", version)),
+        ".python-version" => content.lines()
+            .map(str::trim)
+            .find_map(numeric)
+            .map(|version| ("python", version)),
+        ".ruby-version" => first_line(content)
+            .and_then(|line| numeric(line.trim_start_matches("ruby-")))
+            .map(|version| ("ruby", version)),
+        ".java-version" => first_line(content)
+            .and_then(vendor_version)
+            .map(|version| ("java", version)),
+        "rust-toolchain.toml" => toml::from_str::<toml::Value>(content)
+            .ok()
+            .and_then(|value| value.get("toolchain")?.get("channel")?.as_str().map(str::to_string))
+            .map(|channel| ("rust", channel)),
+        "rust-toolchain" => first_line(content).map(|channel| ("rust", channel.to_string())),
+        "go.mod" => go_mod_version(content).map(|version| ("golang", version)),
+        ".tool-versions" => return tool_versions(content),
+        _ => None,
+    };
+
+    pin.into_iter().collect()
+}
+
+fn first_line(content: &str) -> Option<&str> {
+    content.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// The value if it looks like a version number; aliases like `lts/*` or `system` are skipped
+fn numeric(value: &str) -> Option<String> {
+    value.starts_with(|c: char| c.is_ascii_digit()).then(|| value.to_string())
+}
+
+/// `temurin-17.0.2+8`, `openjdk64-17` or `17.0` all pin Java 17-something
+fn vendor_version(value: &str) -> Option<String> {
+    value.split('-').find_map(numeric)
+}
+
+/// A version as other tools spell it (`v20.1.0`, `ruby-3.2.2`, `17.0.2-tem`) in cyrus form
+pub fn normalize_version(language: &str, raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    match language {
+        "java" => vendor_version(raw),
+        "ruby" => numeric(raw.trim_start_matches("ruby-")),
+        "golang" => numeric(raw.trim_start_matches("go")),
+        _ => numeric(raw.trim_start_matches('v')),
+    }
+}
+
+/// The `toolchain go1.21.5` line, falling back to the `go 1.21` directive
+fn go_mod_version(content: &str) -> Option<String> {
+    let directive = |keyword: &str| {
+        content.lines()
+            .filter_map(|line| line.trim().strip_prefix(keyword))
+            .find_map(|rest| rest.starts_with(char::is_whitespace).then(|| rest.trim().to_string()))
+    };
+
+    directive("toolchain")
+        .and_then(|toolchain| numeric(toolchain.trim_start_matches("go")))
+        .or_else(|| directive("go").and_then(|version| numeric(&version)))
+}
+
+/// asdf's `.tool-versions`: `nodejs 20.11.0`, first version listed wins
+fn tool_versions(content: &str) -> Vec<(&'static str, String)> {
+    content.lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let language = crate::languages::canonical_language_name(words.next()?)?;
+            let version = words.next()?;
+            let version = if language == "java" { vendor_version(version) } else { numeric(version) }?;
+            Some((language, version))
+        })
+        .collect()
+}
@@ -122,6 +122,14 @@ impl std::fmt::Display for ValidationWarning {
 
 pub type Result<T> = std::result::Result<T, CyrusError>;
 
+/// Exit code for a failed command; child process failures keep their own code
+pub fn exit_code_for(error: &anyhow::Error) -> i32 {
+    match error.downcast_ref::<CyrusError>() {
+        Some(CyrusError::CommandFailed { code: Some(code), .. }) => *code,
+        _ => 1,
+    }
+}
+
 /// Error recovery strategies
 #[derive(Debug, Clone)]
 pub enum RecoveryStrategy {
@@ -1,6 +1,8 @@
 //! Go language handler implementation
 
-use super::{LanguageConfig, LanguageHandler};
+use super::{DependencyChange, LanguageConfig, LanguageHandler};
+use crate::core::Environment;
+use crate::utils::path_string;
 use crate::utils::{downloader, archive, platform::Platform};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -47,7 +49,7 @@ impl GolangHandler {
 
 #[async_trait]
 impl LanguageHandler for GolangHandler {
-    async fn install(&self, version: &str, install_path: &Path) -> Result<()> {
+    async fn install(&self, version: &str, install_path: &Path, expected_sha256: Option<&str>) -> Result<()> {
         println!("🐹 Installing Go {} to {:?}", version, install_path);
         
         std::fs::create_dir_all(install_path)?;
@@ -58,6 +60,7 @@ impl LanguageHandler for GolangHandler {
         // Download Go
         downloader::download_file(&download_url, &temp_file).await
             .context("Failed to download Go")?;
+        downloader::record_download(install_path, &download_url, &temp_file, expected_sha256)?;
         
         // Extract based on platform
         let platform = Platform::current();
@@ -82,7 +85,7 @@ impl LanguageHandler for GolangHandler {
         Ok(())
     }
 
-    async fn setup_environment(&self, project_path: &Path) -> Result<()> {
+    async fn setup_environment(&self, project_path: &Path, toolchain: &Environment) -> Result<()> {
         println!("🔧 Setting up Go environment for project at {:?}", project_path);
         
         // Initialize go.mod if it doesn't exist
@@ -95,6 +98,7 @@ impl LanguageHandler for GolangHandler {
             
             let output = Command::new("go")
                 .args(["mod", "init", project_name])
+                .envs(toolchain.to_env_vars()?)
                 .current_dir(project_path)
                 .output()
                 .context("Failed to initialize Go module")?;
@@ -126,4 +130,36 @@ impl LanguageHandler for GolangHandler {
     fn get_config(&self) -> &LanguageConfig {
         &self.config
     }
+
+    fn runtime_environment(&self, version: &str, install_path: &Path, cache_dir: &Path) -> Environment {
+        let goroot = super::distribution_root(install_path);
+        let gopath = install_path.join("gopath");
+        let mut env = Environment::new("golang".to_string(), version.to_string(), self.config.default_package_manager.clone());
+
+        env.add_path(goroot.join("bin"));
+        env.add_path(gopath.join("bin"));
+        env.add_variable("GOROOT".to_string(), path_string(&goroot));
+        env.add_variable("GOPATH".to_string(), path_string(&gopath));
+        env.add_variable("GOMODCACHE".to_string(), path_string(&cache_dir.join("go-mod")));
+        env.add_variable("GOCACHE".to_string(), path_string(&cache_dir.join("go-build")));
+        env
+    }
+
+    fn download_url(&self, version: &str) -> Option<String> {
+        Some(self.get_download_url(version))
+    }
+
+    fn dependency_command(&self, package_manager: &str, change: &DependencyChange) -> Option<Vec<String>> {
+        if package_manager != "go mod" {
+            return None;
+        }
+
+        // Go has no dev dependencies; `@none` drops the requirement from go.mod
+        let module = match change {
+            DependencyChange::Add { name, version: Some(version), .. } => format!("{}@{}", name, version),
+            DependencyChange::Add { name, version: None, .. } => name.clone(),
+            DependencyChange::Remove { name, .. } => format!("{}@none", name),
+        };
+        Some(vec!["go".into(), "get".into(), module])
+    }
 }
@@ -1,7 +1,9 @@
 //! Java language handler implementation
 //! src/languages/java.rs
 
-use super::{LanguageConfig, LanguageHandler};
+use super::{DependencyChange, LanguageConfig, LanguageHandler};
+use crate::core::Environment;
+use crate::utils::path_string;
 use crate::utils::{downloader, archive, platform::Platform};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -75,7 +77,7 @@ impl JavaHandler {
 
 #[async_trait]
 impl LanguageHandler for JavaHandler {
-    async fn install(&self, version: &str, install_path: &Path) -> Result<()> {
+    async fn install(&self, version: &str, install_path: &Path, expected_sha256: Option<&str>) -> Result<()> {
         println!("☕ Installing Java {} to {:?}", version, install_path);
         
         std::fs::create_dir_all(install_path)?;
@@ -86,6 +88,7 @@ impl LanguageHandler for JavaHandler {
         // Download JDK
         downloader::download_file(&download_url, &temp_file).await
             .context("Failed to download JDK")?;
+        downloader::record_download(install_path, &download_url, &temp_file, expected_sha256)?;
         
         // Extract based on platform
         let platform = Platform::current();
@@ -110,7 +113,7 @@ impl LanguageHandler for JavaHandler {
         Ok(())
     }
 
-    async fn setup_environment(&self, project_path: &Path) -> Result<()> {
+    async fn setup_environment(&self, project_path: &Path, _toolchain: &Environment) -> Result<()> {
         println!("🔧 Setting up Java environment for project at {:?}", project_path);
         
         // Check for existing build files
@@ -203,4 +206,162 @@ public class Main {{
     fn get_config(&self) -> &LanguageConfig {
         &self.config
     }
-}
\ No newline at end of file
+
+    fn runtime_environment(&self, version: &str, install_path: &Path, cache_dir: &Path) -> Environment {
+        let java_home = super::distribution_root(install_path);
+        let mut env = Environment::new("java".to_string(), version.to_string(), self.config.default_package_manager.clone());
+
+        env.add_path(java_home.join("bin"));
+        env.add_variable("JAVA_HOME".to_string(), path_string(&java_home));
+        env.add_variable("GRADLE_USER_HOME".to_string(), path_string(&cache_dir.join("gradle")));
+        env.add_variable(
+            "MAVEN_OPTS".to_string(),
+            format!("-Dmaven.repo.local={}", path_string(&cache_dir.join("maven"))),
+        );
+        env
+    }
+
+    fn download_url(&self, version: &str) -> Option<String> {
+        Some(self.get_download_url(version))
+    }
+
+    fn apply_dependency_change(
+        &self,
+        project_path: &Path,
+        _toolchain: &Environment,
+        package_manager: &str,
+        change: &DependencyChange,
+    ) -> Result<()> {
+        // Neither build tool has an `add` command, so edit the build file directly
+        let path = match package_manager {
+            "maven" => project_path.join("pom.xml"),
+            "gradle" => ["build.gradle.kts", "build.gradle"]
+                .iter()
+                .map(|name| project_path.join(name))
+                .find(|path| path.exists())
+                .unwrap_or_else(|| project_path.join("build.gradle")),
+            other => anyhow::bail!("cyrus cannot change dependencies with {} yet", other),
+        };
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let updated = if package_manager == "maven" {
+            pom_with_change(&content, change)?
+        } else {
+            gradle_with_change(&content, change, path.extension().is_some_and(|ext| ext == "kts"))?
+        };
+        std::fs::write(&path, updated)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// `group:artifact` of a Java dependency name
+fn coordinates(name: &str) -> Result<(&str, &str)> {
+    name.split_once(':')
+        .filter(|(group, artifact)| !group.is_empty() && !artifact.is_empty())
+        .with_context(|| format!("Java dependencies are written group:artifact[@version], got '{}'", name))
+}
+
+/// Blank out `<tag>...</tag>` regions so only project-level `<dependencies>` are matched
+fn mask_sections(content: &str, tags: &[&str]) -> String {
+    let mut masked = content.to_string();
+    for tag in tags {
+        let pattern = regex::Regex::new(&format!(r"(?s)<{0}>.*?</{0}>", tag)).unwrap();
+        for found in pattern.find_iter(content) {
+            masked.replace_range(found.range(), &" ".repeat(found.len()));
+        }
+    }
+    masked
+}
+
+/// pom.xml with `change` applied to the project's `<dependencies>`
+pub fn pom_with_change(content: &str, change: &DependencyChange) -> Result<String> {
+    let (group, artifact) = coordinates(change.name())?;
+    let masked = mask_sections(content, &["dependencyManagement", "build", "profiles"]);
+
+    // Drop any existing entry first, so adding again updates the version
+    let block = regex::Regex::new(r"(?s)[ \t]*<dependency>.*?</dependency>[ \t]*\r?\n?").unwrap();
+    let group_tag = regex::Regex::new(&format!(r"<groupId>\s*{}\s*</groupId>", regex::escape(group))).unwrap();
+    let artifact_tag = regex::Regex::new(&format!(r"<artifactId>\s*{}\s*</artifactId>", regex::escape(artifact))).unwrap();
+    let mut updated = content.to_string();
+    let mut removed = 0;
+    for found in block.find_iter(&masked) {
+        let text = &content[found.range()];
+        if group_tag.is_match(text) && artifact_tag.is_match(text) {
+            let start = found.start() - removed;
+            updated.replace_range(start..start + found.len(), "");
+            removed += found.len();
+        }
+    }
+
+    let DependencyChange::Add { version, dev, .. } = change else {
+        return Ok(updated);
+    };
+    let version = version.as_deref()
+        .with_context(|| format!("Maven needs a version: {}@<version>", change.name()))?;
+
+    let mut entry = format!(
+        "        <dependency>\n            <groupId>{}</groupId>\n            <artifactId>{}</artifactId>\n            <version>{}</version>\n",
+        group, artifact, version
+    );
+    if *dev {
+        entry.push_str("            <scope>test</scope>\n");
+    }
+    entry.push_str("        </dependency>\n");
+
+    let masked = mask_sections(&updated, &["dependencyManagement", "build", "profiles"]);
+    if let Some(index) = masked.rfind("</dependencies>") {
+        let line_start = updated[..index].rfind('\n').map(|i| i + 1).unwrap_or(index);
+        updated.insert_str(line_start, &entry);
+    } else {
+        let index = updated.rfind("</project>").context("pom.xml has no </project>")?;
+        updated.insert_str(index, &format!("    <dependencies>\n{}    </dependencies>\n", entry));
+    }
+    Ok(updated)
+}
+
+/// build.gradle(.kts) with `change` applied to the top-level `dependencies { }` block
+pub fn gradle_with_change(content: &str, change: &DependencyChange, kotlin: bool) -> Result<String> {
+    let (group, artifact) = coordinates(change.name())?;
+    let existing = regex::Regex::new(&format!(
+        r#"^\s*\w+\s*\(?\s*["']{}:{}(:[^"']*)?["']\s*\)?\s*$"#,
+        regex::escape(group), regex::escape(artifact)
+    )).unwrap();
+
+    let mut lines: Vec<String> = content.lines()
+        .filter(|line| !existing.is_match(line))
+        .map(str::to_string)
+        .collect();
+
+    if let DependencyChange::Add { version, dev, .. } = change {
+        let configuration = if *dev { "testImplementation" } else { "implementation" };
+        let notation = match version {
+            Some(version) => format!("{}:{}:{}", group, artifact, version),
+            None => format!("{}:{}", group, artifact),
+        };
+        let entry = if kotlin {
+            format!("    {}(\"{}\")", configuration, notation)
+        } else {
+            format!("    {} '{}'", configuration, notation)
+        };
+
+        match lines.iter().position(|line| line.trim_end() == "dependencies {") {
+            Some(start) => {
+                // The block's closing brace is the first unindented `}` after it
+                let end = lines[start..].iter()
+                    .position(|line| line.trim_end() == "}")
+                    .map(|offset| start + offset)
+                    .context("Unterminated dependencies block")?;
+                lines.insert(end, entry);
+            },
+            None => {
+                lines.push(String::new());
+                lines.push("dependencies {".to_string());
+                lines.push(entry);
+                lines.push("}".to_string());
+            },
+        }
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
@@ -1,12 +1,17 @@
 //! JavaScript/Node.js language handler implementation
 
-use super::{LanguageConfig, LanguageHandler};
+use super::{DependencyChange, LanguageConfig, LanguageHandler};
+use crate::core::Environment;
+use crate::utils::path_string;
 use crate::utils::{downloader, archive, platform::Platform};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use std::path::Path;
 use std::process::Command;
 
+/// Every Node.js release, newest first
+const NODE_INDEX_URL: &str = "https://nodejs.org/dist/index.json";
+
 pub struct JavaScriptHandler {
     config: LanguageConfig,
 }
@@ -49,11 +54,12 @@ impl JavaScriptHandler {
     }
 
     fn get_download_url(&self, version: &str) -> String {
+        let version = self.resolve_version(version);
         let platform = Platform::current();
         match platform {
-            Platform::Windows => format!("https://nodejs.org/dist/v{}.0.0/node-v{}.0.0-win-x64.zip", version, version),
-            Platform::MacOS => format!("https://nodejs.org/dist/v{}.0.0/node-v{}.0.0-darwin-x64.tar.gz", version, version),
-            Platform::Linux => format!("https://nodejs.org/dist/v{}.0.0/node-v{}.0.0-linux-x64.tar.xz", version, version),
+            Platform::Windows => format!("https://nodejs.org/dist/v{}/node-v{}-win-x64.zip", version, version),
+            Platform::MacOS => format!("https://nodejs.org/dist/v{}/node-v{}-darwin-x64.tar.gz", version, version),
+            Platform::Linux => format!("https://nodejs.org/dist/v{}/node-v{}-linux-x64.tar.xz", version, version),
             _ => panic!("Unsupported platform"),
         }
     }
@@ -61,7 +67,7 @@ impl JavaScriptHandler {
 
 #[async_trait]
 impl LanguageHandler for JavaScriptHandler {
-    async fn install(&self, version: &str, install_path: &Path) -> Result<()> {
+    async fn install(&self, version: &str, install_path: &Path, expected_sha256: Option<&str>) -> Result<()> {
         println!("🟢 Installing Node.js {} to {:?}", version, install_path);
         
         std::fs::create_dir_all(install_path)?;
@@ -72,6 +78,7 @@ impl LanguageHandler for JavaScriptHandler {
         // Download Node.js
         downloader::download_file(&download_url, &temp_file).await
             .context("Failed to download Node.js")?;
+        downloader::record_download(install_path, &download_url, &temp_file, expected_sha256)?;
         
         // Extract based on platform
         let platform = Platform::current();
@@ -96,7 +103,7 @@ impl LanguageHandler for JavaScriptHandler {
         Ok(())
     }
 
-    async fn setup_environment(&self, project_path: &Path) -> Result<()> {
+    async fn setup_environment(&self, project_path: &Path, toolchain: &Environment) -> Result<()> {
         println!("🔧 Setting up Node.js environment for project at {:?}", project_path);
         
         // Initialize package.json if it doesn't exist
@@ -104,6 +111,7 @@ impl LanguageHandler for JavaScriptHandler {
         if !package_json.exists() {
             let output = Command::new("npm")
                 .args(["init", "-y"])
+                .envs(toolchain.to_env_vars()?)
                 .current_dir(project_path)
                 .output()
                 .context("Failed to initialize npm project")?;
@@ -135,4 +143,74 @@ impl LanguageHandler for JavaScriptHandler {
     fn get_config(&self) -> &LanguageConfig {
         &self.config
     }
+
+    fn runtime_environment(&self, version: &str, install_path: &Path, cache_dir: &Path) -> Environment {
+        let root = super::distribution_root(install_path);
+        let mut env = Environment::new("javascript".to_string(), version.to_string(), self.config.default_package_manager.clone());
+
+        // Windows zips keep node.exe at the top level
+        env.add_path(if cfg!(windows) { root.clone() } else { root.join("bin") });
+
+        // `npm install -g` lands next to this node instead of a system prefix
+        env.add_variable("NPM_CONFIG_PREFIX".to_string(), path_string(&root));
+        env.add_variable("npm_config_cache".to_string(), path_string(&cache_dir.join("npm")));
+        env.add_variable("YARN_CACHE_FOLDER".to_string(), path_string(&cache_dir.join("yarn")));
+        env.add_variable("PNPM_HOME".to_string(), path_string(&root.join("pnpm")));
+        env.add_variable("BUN_INSTALL_CACHE_DIR".to_string(), path_string(&cache_dir.join("bun")));
+        env.add_path(root.join("pnpm"));
+        env
+    }
+
+    fn resolve_version(&self, requested: &str) -> String {
+        // nodejs.org only publishes full `major.minor.patch` releases
+        match requested.split('.').count() {
+            1 => format!("{}.0.0", requested),
+            2 => format!("{}.0", requested),
+            _ => requested.to_string(),
+        }
+    }
+
+    async fn resolve_release(&self, requested: &str) -> Result<String> {
+        #[derive(serde::Deserialize)]
+        struct Release {
+            version: String,
+        }
+
+        let releases: Vec<Release> = reqwest::get(NODE_INDEX_URL).await
+            .and_then(|response| response.error_for_status())
+            .context("Failed to fetch the Node.js release index")?
+            .json().await
+            .context("Failed to parse the Node.js release index")?;
+
+        super::newest_matching(requested, releases.iter().map(|release| &release.version))
+            .with_context(|| format!("No Node.js release matches '{}'", requested))
+    }
+
+    fn download_url(&self, version: &str) -> Option<String> {
+        Some(self.get_download_url(version))
+    }
+
+    fn dependency_command(&self, package_manager: &str, change: &DependencyChange) -> Option<Vec<String>> {
+        let mut command: Vec<String> = match (package_manager, change) {
+            ("npm", DependencyChange::Add { .. }) => vec!["npm".into(), "install".into()],
+            ("npm", DependencyChange::Remove { .. }) => vec!["npm".into(), "uninstall".into()],
+            ("yarn" | "pnpm" | "bun", DependencyChange::Add { .. }) => vec![package_manager.into(), "add".into()],
+            ("yarn" | "pnpm" | "bun", DependencyChange::Remove { .. }) => vec![package_manager.into(), "remove".into()],
+            _ => return None,
+        };
+
+        match change {
+            DependencyChange::Add { name, version, dev } => {
+                if *dev {
+                    command.push("-D".into());
+                }
+                command.push(match version {
+                    Some(version) => format!("{}@{}", name, version),
+                    None => name.clone(),
+                });
+            },
+            DependencyChange::Remove { name, .. } => command.push(name.clone()),
+        }
+        Some(command)
+    }
 }
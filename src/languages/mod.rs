@@ -9,9 +9,11 @@ pub mod java;
 pub mod php;
 pub mod ruby;
 
-use anyhow::Result;
+use crate::core::Environment;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LanguageConfig {
@@ -24,12 +26,155 @@ pub struct LanguageConfig {
     pub run_commands: std::collections::HashMap<String, String>,
 }
 
+/// A dependency change made through the project's package manager
+#[derive(Debug, Clone, PartialEq)]
+pub enum DependencyChange {
+    Add {
+        name: String,
+        version: Option<String>,
+        dev: bool,
+    },
+    Remove {
+        name: String,
+        /// Whether cyrus.toml lists it under `dev_dependencies`
+        dev: bool,
+    },
+}
+
+impl DependencyChange {
+    /// `name@version` (or just `name`); npm scopes like `@types/node@20` are kept intact
+    pub fn add(spec: &str, dev: bool) -> Self {
+        let (name, version) = split_dependency(spec);
+        DependencyChange::Add {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+            dev,
+        }
+    }
+
+    pub fn is_dev(&self) -> bool {
+        match self {
+            DependencyChange::Add { dev, .. } | DependencyChange::Remove { dev, .. } => *dev,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            DependencyChange::Add { name, .. } | DependencyChange::Remove { name, .. } => name,
+        }
+    }
+}
+
+/// `(name, version)` of a `name@version` dependency spec
+pub fn split_dependency(spec: &str) -> (&str, Option<&str>) {
+    match spec.rfind('@') {
+        Some(index) if index > 0 => (&spec[..index], Some(&spec[index + 1..])),
+        _ => (spec, None),
+    }
+}
+
 #[async_trait]
 pub trait LanguageHandler {
-    async fn install(&self, version: &str, install_path: &std::path::Path) -> Result<()>;
-    async fn setup_environment(&self, project_path: &std::path::Path) -> Result<()>;
+    /// Install `version` into `install_path`; a downloaded archive must match `expected_sha256`
+    /// when one is given, and is checked before it gets unpacked
+    async fn install(&self, version: &str, install_path: &std::path::Path, expected_sha256: Option<&str>) -> Result<()>;
+    async fn setup_environment(&self, project_path: &std::path::Path, toolchain: &Environment) -> Result<()>;
     async fn run_command(&self, command: &str, args: &[String]) -> Result<()>;
     fn get_config(&self) -> &LanguageConfig;
+
+    /// Everything a process needs to use the toolchain installed at `install_path`:
+    /// bin dirs, home variables, library paths and package manager caches under `cache_dir`
+    fn runtime_environment(&self, version: &str, install_path: &Path, cache_dir: &Path) -> Environment;
+
+    /// Full release number for a requested version, without looking anything up (`20` is `20.0.0`)
+    fn resolve_version(&self, requested: &str) -> String {
+        requested.to_string()
+    }
+
+    /// The newest published release `requested` matches (`20` is `20.11.1`); what cyrus.lock records
+    async fn resolve_release(&self, requested: &str) -> Result<String> {
+        Ok(self.resolve_version(requested))
+    }
+
+    /// Archive `install` downloads for `version` on this platform, if it downloads one
+    fn download_url(&self, _version: &str) -> Option<String> {
+        None
+    }
+
+    /// Native command that makes `change`, such as `pnpm add -D vitest`
+    fn dependency_command(&self, _package_manager: &str, _change: &DependencyChange) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Make `change` in the project's native manifest
+    fn apply_dependency_change(
+        &self,
+        project_path: &Path,
+        toolchain: &Environment,
+        package_manager: &str,
+        change: &DependencyChange,
+    ) -> Result<()> {
+        let command = self.dependency_command(package_manager, change)
+            .with_context(|| format!("cyrus cannot change dependencies with {} yet", package_manager))?;
+        run_dependency_command(project_path, toolchain, &command)
+    }
+}
+
+/// Run a package manager command in the project, inheriting the terminal
+pub fn run_dependency_command(project_path: &Path, toolchain: &Environment, command: &[String]) -> Result<()> {
+    let (program, args) = command.split_first().context("Empty package manager command")?;
+    let status = std::process::Command::new(program)
+        .args(args)
+        .envs(toolchain.to_env_vars()?)
+        .current_dir(project_path)
+        .status()
+        .with_context(|| format!("Failed to run {}", program))?;
+
+    if !status.success() {
+        anyhow::bail!("`{}` failed with exit code {:?}", command.join(" "), status.code());
+    }
+    Ok(())
+}
+
+/// The newest of `releases` that `requested` is a prefix of, by version component:
+/// `20` matches `20.11.1`, `3.11` matches `3.11.9`, and `3.1` never matches `3.11.0`
+pub fn newest_matching<I, S>(requested: &str, releases: I) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let wanted: Vec<&str> = requested.trim_start_matches('v').split('.').collect();
+
+    releases.into_iter()
+        .filter_map(|release| semver::Version::parse(release.as_ref().trim_start_matches('v')).ok())
+        .filter(|version| version.pre.is_empty())
+        .filter(|version| {
+            let parts = [version.major.to_string(), version.minor.to_string(), version.patch.to_string()];
+            wanted.len() <= parts.len() && wanted.iter().zip(&parts).all(|(want, part)| want == part)
+        })
+        .max()
+        .map(|version| version.to_string())
+}
+
+/// Directory of an unpacked distribution that contains `bin`.
+///
+/// Release archives usually unpack into a single top-level directory
+/// (`go/`, `node-v20.11.0-linux-x64/`, `jdk-21/Contents/Home`), so look one
+/// level down when `install_path/bin` doesn't exist.
+pub fn distribution_root(install_path: &Path) -> PathBuf {
+    if install_path.join("bin").is_dir() {
+        return install_path.to_path_buf();
+    }
+
+    std::fs::read_dir(install_path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .flat_map(|path| [path.clone(), path.join("Contents").join("Home")])
+        .find(|candidate| candidate.join("bin").is_dir())
+        .unwrap_or_else(|| install_path.to_path_buf())
 }
 
 /// Get language handler for the specified language
@@ -59,6 +204,20 @@ pub fn get_supported_languages() -> Vec<&'static str> {
     ]
 }
 
+/// Canonical language name (as used for install directories) for an alias like `node` or `py`
+pub fn canonical_language_name(language: &str) -> Option<&'static str> {
+    match language.to_lowercase().as_str() {
+        "python" | "py" => Some("python"),
+        "javascript" | "js" | "node" | "nodejs" => Some("javascript"),
+        "golang" | "go" => Some("golang"),
+        "rust" | "rs" => Some("rust"),
+        "java" => Some("java"),
+        "php" => Some("php"),
+        "ruby" | "rb" => Some("ruby"),
+        _ => None,
+    }
+}
+
 /// Get language display names
 pub fn get_language_display_name(language: &str) -> &'static str {
     match language.to_lowercase().as_str() {
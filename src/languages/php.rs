@@ -1,7 +1,9 @@
 //! PHP language handler implementation
 //! src/languages/php.rs
 
-use super::{LanguageConfig, LanguageHandler};
+use super::{DependencyChange, LanguageConfig, LanguageHandler};
+use crate::core::Environment;
+use crate::utils::path_string;
 use crate::utils::{downloader, archive, platform::Platform};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -50,7 +52,7 @@ impl PhpHandler {
 
 #[async_trait]
 impl LanguageHandler for PhpHandler {
-    async fn install(&self, version: &str, install_path: &Path) -> Result<()> {
+    async fn install(&self, version: &str, install_path: &Path, expected_sha256: Option<&str>) -> Result<()> {
         println!("🐘 Installing PHP {} to {:?}", version, install_path);
         
         std::fs::create_dir_all(install_path)?;
@@ -93,6 +95,7 @@ impl LanguageHandler for PhpHandler {
                 // Download PHP
                 downloader::download_file(&download_url, &temp_file).await
                     .context("Failed to download PHP")?;
+                downloader::record_download(install_path, &download_url, &temp_file, expected_sha256)?;
                 
                 // Extract
                 archive::extract_zip(&temp_file, install_path)
@@ -113,7 +116,7 @@ impl LanguageHandler for PhpHandler {
         Ok(())
     }
 
-    async fn setup_environment(&self, project_path: &Path) -> Result<()> {
+    async fn setup_environment(&self, project_path: &Path, toolchain: &Environment) -> Result<()> {
         println!("🔧 Setting up PHP environment for project at {:?}", project_path);
         
         // Initialize composer.json if it doesn't exist
@@ -126,6 +129,7 @@ impl LanguageHandler for PhpHandler {
             
             let output = Command::new("composer")
                 .args(["init", "--name", &format!("example/{}", project_name), "--no-interaction"])
+                .envs(toolchain.to_env_vars()?)
                 .current_dir(project_path)
                 .output()
                 .context("Failed to initialize Composer project")?;
@@ -169,6 +173,48 @@ echo "PHP version: " . PHP_VERSION . "\n";
     fn get_config(&self) -> &LanguageConfig {
         &self.config
     }
+
+    fn runtime_environment(&self, version: &str, install_path: &Path, cache_dir: &Path) -> Environment {
+        let root = super::distribution_root(install_path);
+        let composer_home = install_path.join("composer");
+        let mut env = Environment::new("php".to_string(), version.to_string(), self.config.default_package_manager.clone());
+
+        // Windows zips keep php.exe at the top level; composer is installed next to it
+        env.add_path(if cfg!(windows) { root.clone() } else { root.join("bin") });
+        env.add_path(install_path.to_path_buf());
+        env.add_path(composer_home.join("vendor").join("bin"));
+        env.add_library_path(root.join("lib"));
+        env.add_variable("COMPOSER_HOME".to_string(), path_string(&composer_home));
+        env.add_variable("COMPOSER_CACHE_DIR".to_string(), path_string(&cache_dir.join("composer")));
+        if root.join("etc").join("php.ini").exists() {
+            env.add_variable("PHPRC".to_string(), path_string(&root.join("etc")));
+        }
+        env
+    }
+
+    fn download_url(&self, version: &str) -> Option<String> {
+        // Elsewhere the install goes through the system package manager
+        matches!(Platform::current(), Platform::Windows).then(|| self.get_download_url(version))
+    }
+
+    fn dependency_command(&self, package_manager: &str, change: &DependencyChange) -> Option<Vec<String>> {
+        if package_manager != "composer" {
+            return None;
+        }
+
+        let (action, package) = match change {
+            DependencyChange::Add { name, version: Some(version), .. } => ("require", format!("{}:{}", name, version)),
+            DependencyChange::Add { name, version: None, .. } => ("require", name.clone()),
+            DependencyChange::Remove { name, .. } => ("remove", name.clone()),
+        };
+
+        let mut command = vec!["composer".to_string(), action.to_string()];
+        if change.is_dev() {
+            command.push("--dev".into());
+        }
+        command.push(package);
+        Some(command)
+    }
 }
 
 impl PhpHandler {
@@ -1,12 +1,18 @@
 //! Python language handler implementation
 
-use super::{LanguageConfig, LanguageHandler};
+use super::{DependencyChange, LanguageConfig, LanguageHandler};
+use crate::core::venv::{self, DependencySource, VenvStatus};
+use crate::core::Environment;
+use crate::utils::path_string;
 use crate::utils::{downloader, archive, platform::Platform};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use std::path::Path;
 use std::process::Command;
 
+/// Published CPython releases, with pre-releases flagged
+const PYTHON_RELEASES_URL: &str = "https://www.python.org/api/v2/downloads/release/?is_published=true";
+
 pub struct PythonHandler {
     config: LanguageConfig,
 }
@@ -42,12 +48,86 @@ impl PythonHandler {
         Self { config }
     }
 
+    /// Create `.venv` with the cyrus interpreter and record its pinned version
+    pub fn create_venv(&self, project_path: &Path, toolchain: &Environment) -> Result<()> {
+        let python = toolchain.find_executable("python3")
+            .or_else(|| toolchain.find_executable("python"))
+            .with_context(|| format!("Python {} is not installed. Run 'cyrus install python{}'", toolchain.version, toolchain.version))?;
+        let venv_path = venv::venv_path(project_path);
+
+        let output = Command::new(&python)
+            .arg("-m")
+            .arg("venv")
+            .arg(&venv_path)
+            .envs(toolchain.to_env_vars()?)
+            .current_dir(project_path)
+            .output()
+            .context("Failed to create virtual environment")?;
+
+        if !output.status.success() {
+            anyhow::bail!("Failed to create virtual environment: {}",
+                String::from_utf8_lossy(&output.stderr));
+        }
+
+        venv::record_version(&venv_path, &toolchain.version)?;
+        println!("📦 Virtual environment created at .venv with Python {}", toolchain.version);
+        Ok(())
+    }
+
+    /// Install the project's dependencies into `.venv` from its lockfile or requirements
+    pub fn install_dependencies(&self, project_path: &Path, toolchain: &Environment, declared: &[String]) -> Result<()> {
+        let Some(source) = venv::dependency_source(project_path, declared) else {
+            return Ok(());
+        };
+
+        let venv_path = venv::venv_path(project_path);
+        let mut environment = toolchain.clone();
+        venv::activate(&mut environment, &venv_path);
+        let pip = venv::bin_dir(&venv_path).join("pip");
+
+        let mut cmd = match &source {
+            DependencySource::Poetry => {
+                let mut cmd = Command::new("poetry");
+                cmd.arg("install").env("POETRY_VIRTUALENVS_IN_PROJECT", "true");
+                cmd
+            },
+            DependencySource::Pipenv => {
+                let mut cmd = Command::new("pipenv");
+                cmd.arg("sync").env("PIPENV_VENV_IN_PROJECT", "1");
+                cmd
+            },
+            DependencySource::Requirements(path) => {
+                let mut cmd = Command::new(&pip);
+                cmd.arg("install").arg("-r").arg(path);
+                cmd
+            },
+            DependencySource::Declared(packages) => {
+                let mut cmd = Command::new(&pip);
+                cmd.arg("install").args(packages);
+                cmd
+            },
+        };
+
+        println!("📥 Installing dependencies into .venv ({:?})", cmd.get_program());
+        let status = cmd
+            .envs(environment.to_env_vars()?)
+            .current_dir(project_path)
+            .status()
+            .context("Failed to install dependencies")?;
+
+        if !status.success() {
+            anyhow::bail!("Dependency installation failed with exit code: {:?}", status.code());
+        }
+        Ok(())
+    }
+
     fn get_download_url(&self, version: &str) -> String {
+        let version = self.resolve_version(version);
         let platform = Platform::current();
         match platform {
-            Platform::Windows => format!("https://www.python.org/ftp/python/{version}.0/python-{version}.0-embed-amd64.zip", version = version),
-            Platform::MacOS => format!("https://www.python.org/ftp/python/{version}.0/python-{version}.0-macos11.pkg", version = version),
-            Platform::Linux => format!("https://www.python.org/ftp/python/{version}.0/Python-{version}.0.tgz", version = version),
+            Platform::Windows => format!("https://www.python.org/ftp/python/{version}/python-{version}-embed-amd64.zip", version = version),
+            Platform::MacOS => format!("https://www.python.org/ftp/python/{version}/python-{version}-macos11.pkg", version = version),
+            Platform::Linux => format!("https://www.python.org/ftp/python/{version}/Python-{version}.tgz", version = version),
             _ => panic!("Unsupported platform"),
         }
     }
@@ -55,7 +135,7 @@ impl PythonHandler {
 
 #[async_trait]
 impl LanguageHandler for PythonHandler {
-    async fn install(&self, version: &str, install_path: &Path) -> Result<()> {
+    async fn install(&self, version: &str, install_path: &Path, expected_sha256: Option<&str>) -> Result<()> {
         println!("🐍 Installing Python {} to {:?}", version, install_path);
         
         // Create installation directory
@@ -68,6 +148,7 @@ impl LanguageHandler for PythonHandler {
         // Download Python
         downloader::download_file(&download_url, &temp_file).await
             .context("Failed to download Python")?;
+        downloader::record_download(install_path, &download_url, &temp_file, expected_sha256)?;
         
         // Extract based on platform
         let platform = Platform::current();
@@ -92,25 +173,21 @@ impl LanguageHandler for PythonHandler {
         Ok(())
     }
 
-    async fn setup_environment(&self, project_path: &Path) -> Result<()> {
+    async fn setup_environment(&self, project_path: &Path, toolchain: &Environment) -> Result<()> {
         println!("🔧 Setting up Python environment for project at {:?}", project_path);
-        
-        // Create virtual environment
-        let venv_path = project_path.join(".venv");
-        
-        if !venv_path.exists() {
-            let output = Command::new("python")
-                .args(["-m", "venv", venv_path.to_str().unwrap()])
-                .current_dir(project_path)
-                .output()
-                .context("Failed to create virtual environment")?;
-
-            if !output.status.success() {
-                anyhow::bail!("Failed to create virtual environment: {}", 
-                    String::from_utf8_lossy(&output.stderr));
-            }
-            
-            println!("📦 Virtual environment created at .venv");
+
+        match venv::status(project_path, &toolchain.version) {
+            VenvStatus::Current => {},
+            VenvStatus::Stale { reason } => {
+                println!("⚠️  .venv is stale: {}. Run `cyrus run` to rebuild it.", reason);
+            },
+            VenvStatus::Missing if toolchain.find_executable("python3").is_none()
+                && toolchain.find_executable("python").is_none() => {
+                println!("💡 Python {} is not installed yet; .venv will be created on the first `cyrus run`", toolchain.version);
+            },
+            VenvStatus::Missing => {
+                self.create_venv(project_path, toolchain)?;
+            },
         }
 
         Ok(())
@@ -132,4 +209,165 @@ impl LanguageHandler for PythonHandler {
     fn get_config(&self) -> &LanguageConfig {
         &self.config
     }
+
+    fn runtime_environment(&self, version: &str, install_path: &Path, cache_dir: &Path) -> Environment {
+        let root = super::distribution_root(install_path);
+        let mut env = Environment::new("python".to_string(), version.to_string(), self.config.default_package_manager.clone());
+
+        if cfg!(windows) {
+            // The embeddable distribution keeps python.exe at the top level
+            env.add_path(root.clone());
+            env.add_path(root.join("Scripts"));
+        } else {
+            env.add_path(root.join("bin"));
+            env.add_library_path(root.join("lib"));
+        }
+
+        env.add_variable("PIP_CACHE_DIR".to_string(), path_string(&cache_dir.join("pip")));
+        env.add_variable("POETRY_CACHE_DIR".to_string(), path_string(&cache_dir.join("poetry")));
+        env.add_variable("PIPENV_CACHE_DIR".to_string(), path_string(&cache_dir.join("pipenv")));
+        env
+    }
+
+    fn resolve_version(&self, requested: &str) -> String {
+        // python.org only publishes full `major.minor.patch` releases
+        match requested.split('.').count() {
+            1 => format!("{}.0.0", requested),
+            2 => format!("{}.0", requested),
+            _ => requested.to_string(),
+        }
+    }
+
+    async fn resolve_release(&self, requested: &str) -> Result<String> {
+        #[derive(serde::Deserialize)]
+        struct Release {
+            /// `Python 3.12.1`
+            name: String,
+            pre_release: bool,
+        }
+
+        let releases: Vec<Release> = reqwest::get(PYTHON_RELEASES_URL).await
+            .and_then(|response| response.error_for_status())
+            .context("Failed to fetch the python.org release list")?
+            .json().await
+            .context("Failed to parse the python.org release list")?;
+
+        let versions = releases.iter()
+            .filter(|release| !release.pre_release)
+            .filter_map(|release| release.name.strip_prefix("Python "));
+        super::newest_matching(requested, versions)
+            .with_context(|| format!("No Python release matches '{}'", requested))
+    }
+
+    fn download_url(&self, version: &str) -> Option<String> {
+        Some(self.get_download_url(version))
+    }
+
+    fn dependency_command(&self, package_manager: &str, change: &DependencyChange) -> Option<Vec<String>> {
+        let command: Vec<String> = match (package_manager, change) {
+            ("poetry", DependencyChange::Add { name, version, dev }) => {
+                let mut command = vec!["poetry".into(), "add".into()];
+                if *dev {
+                    command.extend(["--group".into(), "dev".into()]);
+                }
+                command.push(match version {
+                    Some(version) => format!("{}@{}", name, version),
+                    None => name.clone(),
+                });
+                command
+            },
+            ("poetry", DependencyChange::Remove { name, dev }) => {
+                let mut command = vec!["poetry".into(), "remove".into()];
+                if *dev {
+                    command.extend(["--group".into(), "dev".into()]);
+                }
+                command.push(name.clone());
+                command
+            },
+            ("pipenv", DependencyChange::Add { name, version, dev }) => {
+                let mut command = vec!["pipenv".into(), "install".into()];
+                if *dev {
+                    command.push("--dev".into());
+                }
+                command.push(requirement(name, version.as_deref()));
+                command
+            },
+            ("pipenv", DependencyChange::Remove { name, .. }) => vec!["pipenv".into(), "uninstall".into(), name.clone()],
+            ("pip", DependencyChange::Add { name, version, .. }) => {
+                vec!["python".into(), "-m".into(), "pip".into(), "install".into(), requirement(name, version.as_deref())]
+            },
+            ("pip", DependencyChange::Remove { name, .. }) => {
+                vec!["python".into(), "-m".into(), "pip".into(), "uninstall".into(), "-y".into(), name.clone()]
+            },
+            _ => return None,
+        };
+        Some(command)
+    }
+
+    fn apply_dependency_change(
+        &self,
+        project_path: &Path,
+        toolchain: &Environment,
+        package_manager: &str,
+        change: &DependencyChange,
+    ) -> Result<()> {
+        let command = self.dependency_command(package_manager, change)
+            .with_context(|| format!("cyrus cannot change dependencies with {} yet", package_manager))?;
+        super::run_dependency_command(project_path, toolchain, &command)?;
+
+        // pip has no manifest of its own; requirements files play that role
+        if package_manager == "pip" {
+            update_requirements(project_path, change)?;
+        }
+        Ok(())
+    }
+}
+
+/// A pip requirement: `requests==2.31.0`, or `requests>=2` when the version has its own operator
+fn requirement(name: &str, version: Option<&str>) -> String {
+    match version {
+        Some(version) if version.starts_with(['=', '<', '>', '!', '~']) => format!("{}{}", name, version),
+        Some(version) => format!("{}=={}", name, version),
+        None => name.to_string(),
+    }
+}
+
+/// Package name of a requirements line, normalized (`Foo_Bar[extra]>=1` is `foo-bar`)
+fn requirement_name(line: &str) -> String {
+    line.split(|c: char| "=<>!~[;@ ".contains(c))
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_lowercase()
+        .replace('_', "-")
+}
+
+/// Record `change` in requirements.txt (requirements-dev.txt for dev dependencies)
+fn update_requirements(project_path: &Path, change: &DependencyChange) -> Result<()> {
+    let name = requirement_name(change.name());
+    let files: &[&str] = match change {
+        DependencyChange::Add { dev: true, .. } => &["requirements-dev.txt"],
+        DependencyChange::Add { dev: false, .. } => &["requirements.txt"],
+        DependencyChange::Remove { .. } => &["requirements.txt", "requirements-dev.txt"],
+    };
+
+    for file in files {
+        let path = project_path.join(file);
+        if !path.exists() && matches!(change, DependencyChange::Remove { .. }) {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap_or_default();
+        let mut lines: Vec<String> = content.lines()
+            .filter(|line| line.trim_start().starts_with('#') || requirement_name(line) != name)
+            .map(str::to_string)
+            .collect();
+        if let DependencyChange::Add { name, version, .. } = change {
+            lines.push(requirement(name, version.as_deref()));
+        }
+
+        std::fs::write(&path, lines.join("\n") + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(())
 }
@@ -1,7 +1,9 @@
 //! Ruby language handler implementation
 //! src/languages/ruby.rs
 
-use super::{LanguageConfig, LanguageHandler};
+use super::{DependencyChange, LanguageConfig, LanguageHandler};
+use crate::core::Environment;
+use crate::utils::path_string;
 use crate::utils::{downloader, archive, platform::Platform};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -58,7 +60,7 @@ impl RubyHandler {
 
 #[async_trait]
 impl LanguageHandler for RubyHandler {
-    async fn install(&self, version: &str, install_path: &Path) -> Result<()> {
+    async fn install(&self, version: &str, install_path: &Path, expected_sha256: Option<&str>) -> Result<()> {
         println!("💎 Installing Ruby {} to {:?}", version, install_path);
         
         std::fs::create_dir_all(install_path)?;
@@ -77,6 +79,7 @@ impl LanguageHandler for RubyHandler {
                 // Download Ruby installer
                 downloader::download_file(&download_url, &temp_file).await
                     .context("Failed to download Ruby installer")?;
+                downloader::record_download(install_path, &download_url, &temp_file, expected_sha256)?;
                 
                 // Run installer silently
                 let output = Command::new(&temp_file)
@@ -104,7 +107,7 @@ impl LanguageHandler for RubyHandler {
         Ok(())
     }
 
-    async fn setup_environment(&self, project_path: &Path) -> Result<()> {
+    async fn setup_environment(&self, project_path: &Path, toolchain: &Environment) -> Result<()> {
         println!("🔧 Setting up Ruby environment for project at {:?}", project_path);
         
         // Initialize Gemfile if it doesn't exist
@@ -155,6 +158,7 @@ puts "Platform: #{RUBY_PLATFORM}"
         if gemfile.exists() {
             let output = Command::new("bundle")
                 .args(["install"])
+                .envs(toolchain.to_env_vars()?)
                 .current_dir(project_path)
                 .output()
                 .context("Failed to run bundle install")?;
@@ -183,6 +187,52 @@ puts "Platform: #{RUBY_PLATFORM}"
     fn get_config(&self) -> &LanguageConfig {
         &self.config
     }
+
+    fn runtime_environment(&self, version: &str, install_path: &Path, cache_dir: &Path) -> Environment {
+        let root = super::distribution_root(install_path);
+        // Bundler is installed into this GEM_HOME at install time
+        let gem_home = install_path.join("gems");
+        let mut env = Environment::new("ruby".to_string(), version.to_string(), self.config.default_package_manager.clone());
+
+        env.add_path(root.join("bin"));
+        env.add_path(gem_home.join("bin"));
+        env.add_library_path(root.join("lib"));
+        env.add_variable("GEM_HOME".to_string(), path_string(&gem_home));
+        env.add_variable("GEM_PATH".to_string(), path_string(&gem_home));
+        env.add_variable("BUNDLE_USER_CACHE".to_string(), path_string(&cache_dir.join("bundler")));
+        env
+    }
+
+    fn download_url(&self, version: &str) -> Option<String> {
+        // Elsewhere the install goes through rbenv or a source build
+        matches!(Platform::current(), Platform::Windows).then(|| self.get_download_url(version))
+    }
+
+    fn dependency_command(&self, package_manager: &str, change: &DependencyChange) -> Option<Vec<String>> {
+        let command: Vec<String> = match (package_manager, change) {
+            ("bundler", DependencyChange::Add { name, version, dev }) => {
+                let mut command = vec!["bundle".into(), "add".into(), name.clone()];
+                if let Some(version) = version {
+                    command.extend(["--version".into(), version.clone()]);
+                }
+                if *dev {
+                    command.extend(["--group".into(), "development".into()]);
+                }
+                command
+            },
+            ("bundler", DependencyChange::Remove { name, .. }) => vec!["bundle".into(), "remove".into(), name.clone()],
+            ("gem", DependencyChange::Add { name, version, .. }) => {
+                let mut command = vec!["gem".into(), "install".into(), name.clone()];
+                if let Some(version) = version {
+                    command.extend(["--version".into(), version.clone()]);
+                }
+                command
+            },
+            ("gem", DependencyChange::Remove { name, .. }) => vec!["gem".into(), "uninstall".into(), "-x".into(), name.clone()],
+            _ => return None,
+        };
+        Some(command)
+    }
 }
 
 impl RubyHandler {
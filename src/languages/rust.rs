@@ -1,7 +1,9 @@
 //! Rust language handler implementation
 //! src/languages/rust.rs
 
-use super::{LanguageConfig, LanguageHandler};
+use super::{DependencyChange, LanguageConfig, LanguageHandler};
+use crate::core::Environment;
+use crate::utils::path_string;
 use crate::utils::{downloader, archive, platform::Platform};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -52,7 +54,7 @@ impl RustHandler {
 
 #[async_trait]
 impl LanguageHandler for RustHandler {
-    async fn install(&self, version: &str, install_path: &Path) -> Result<()> {
+    async fn install(&self, version: &str, install_path: &Path, _expected_sha256: Option<&str>) -> Result<()> {
         println!("🦀 Installing Rust {} to {:?}", version, install_path);
         
         std::fs::create_dir_all(install_path)?;
@@ -93,7 +95,7 @@ impl LanguageHandler for RustHandler {
         Ok(())
     }
 
-    async fn setup_environment(&self, project_path: &Path) -> Result<()> {
+    async fn setup_environment(&self, project_path: &Path, toolchain: &Environment) -> Result<()> {
         println!("🔧 Setting up Rust environment for project at {:?}", project_path);
         
         // Initialize Cargo.toml if it doesn't exist
@@ -106,6 +108,7 @@ impl LanguageHandler for RustHandler {
             
             let output = Command::new("cargo")
                 .args(["init", "--name", project_name, "."])
+                .envs(toolchain.to_env_vars()?)
                 .current_dir(project_path)
                 .output()
                 .context("Failed to initialize Cargo project")?;
@@ -137,4 +140,45 @@ impl LanguageHandler for RustHandler {
     fn get_config(&self) -> &LanguageConfig {
         &self.config
     }
+
+    fn runtime_environment(&self, version: &str, install_path: &Path, _cache_dir: &Path) -> Environment {
+        // rustup was run with these homes at install time
+        let cargo_home = install_path.join("cargo");
+        let rustup_home = install_path.join("rustup");
+        let mut env = Environment::new("rust".to_string(), version.to_string(), self.config.default_package_manager.clone());
+
+        env.add_path(cargo_home.join("bin"));
+        env.add_variable("CARGO_HOME".to_string(), path_string(&cargo_home));
+        env.add_variable("RUSTUP_HOME".to_string(), path_string(&rustup_home));
+        env.add_variable("RUSTUP_TOOLCHAIN".to_string(), version.to_string());
+        env
+    }
+
+    fn dependency_command(&self, package_manager: &str, change: &DependencyChange) -> Option<Vec<String>> {
+        if package_manager != "cargo" {
+            return None;
+        }
+
+        let mut command = vec!["cargo".to_string()];
+        match change {
+            DependencyChange::Add { name, version, dev } => {
+                command.push("add".into());
+                if *dev {
+                    command.push("--dev".into());
+                }
+                command.push(match version {
+                    Some(version) => format!("{}@{}", name, version),
+                    None => name.clone(),
+                });
+            },
+            DependencyChange::Remove { name, dev } => {
+                command.push("remove".into());
+                if *dev {
+                    command.push("--dev".into());
+                }
+                command.push(name.clone());
+            },
+        }
+        Some(command)
+    }
 }
\ No newline at end of file
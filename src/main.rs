@@ -23,6 +23,8 @@ mod utils;
 mod templates;
 mod plugins;
 mod workspace;
+#[cfg(feature = "security-audit")]
+mod secrets;
 
 use error::{CyrusError, Result};
 use commands::*;
@@ -79,6 +81,21 @@ enum Commands {
     New(NewCommand),
     /// Run commands in the project environment with smart aliasing
     Run(RunCommand),
+    /// Start all [processes] together with prefixed output
+    Up(UpCommand),
+    /// Run a command with a toolchain, no project required (cyrus exec python@3.9 -- python x.py)
+    Exec(ExecCommand),
+    /// Diagnose PATH, installs, build dependencies, configs, venvs and plugins
+    Doctor(DoctorCommand),
+    /// Adopt toolchains and global versions from asdf, pyenv, nvm, rbenv or sdkman
+    Migrate(MigrateCommand),
+    /// Add dependencies through the project's package manager and record them in cyrus.toml
+    Add(AddCommand),
+    /// Remove dependencies through the project's package manager and from cyrus.toml
+    #[command(name = "rm", visible_alias = "remove-dep")]
+    Rm(RmCommand),
+    /// Sync scripts and dependencies between cyrus.toml and package.json, pyproject.toml, ...
+    Sync(SyncCommand),
     /// List installed languages and versions
     List(ListCommand),
     /// Update Cyrus or installed languages
@@ -109,6 +126,14 @@ enum Commands {
     Security(SecurityCommand),
     /// Performance optimization commands
     Perf(PerfCommand),
+    /// Manage encrypted secrets referenced as secret://name
+    ///
+    /// Set CYRUS_SECRETS_PASSPHRASE to derive the vault key from a passphrase.
+    /// Without it the key is stored in ~/.cyrus/secrets/vault.key (owner-only),
+    /// next to the vaults: that protects against other users, not against
+    /// anyone who can read your home directory.
+    #[cfg(feature = "security-audit")]
+    Secret(SecretCommand),
 }
 
 #[derive(clap::Args)]
@@ -346,8 +371,8 @@ pub struct DevCommand {
 pub enum DevAction {
     /// Show debug information
     Debug,
-    /// Validate configuration
-    Validate,
+    /// Validate the project, workspace, global config, active profile and plugins
+    Validate(ValidateCommand),
     /// Generate shell completions
     Completions {
         /// Shell type
@@ -357,6 +382,15 @@ pub enum DevAction {
     Env,
     /// Clean caches and temporary files
     Clean,
+    /// Print the JSON Schema of a cyrus config file
+    Schema {
+        /// project, workspace, plugin, template or global
+        #[arg(long, default_value = "project")]
+        kind: config::schema::SchemaKind,
+        /// Print taplo and VS Code settings associating the files with their schemas instead
+        #[arg(long)]
+        editor: bool,
+    },
 }
 
 #[derive(clap::Args)]
@@ -377,6 +411,50 @@ pub enum SecurityAction {
     Update,
 }
 
+#[cfg(feature = "security-audit")]
+#[derive(clap::Args)]
+pub struct SecretCommand {
+    #[command(subcommand)]
+    pub action: SecretAction,
+}
+
+#[cfg(feature = "security-audit")]
+#[derive(clap::Subcommand)]
+pub enum SecretAction {
+    /// Store a secret (prompts for the value if omitted)
+    Set {
+        /// Secret name
+        name: String,
+        /// Secret value
+        value: Option<String>,
+        /// Store in the global vault instead of the project vault
+        #[arg(short, long)]
+        global: bool,
+    },
+    /// Print a secret value
+    Get {
+        /// Secret name
+        name: String,
+        /// Read from the global vault only
+        #[arg(short, long)]
+        global: bool,
+    },
+    /// List secret names (values are never shown)
+    List {
+        /// List the global vault only
+        #[arg(short, long)]
+        global: bool,
+    },
+    /// Remove a secret
+    Rm {
+        /// Secret name
+        name: String,
+        /// Remove from the global vault
+        #[arg(short, long)]
+        global: bool,
+    },
+}
+
 #[derive(clap::Args)]
 pub struct PerfCommand {
     #[command(subcommand)]
@@ -434,7 +512,12 @@ async fn main() {
     
     // Load configuration with profile override
     let config = match load_config(&cli) {
-        Ok(config) => config,
+        Ok(config) => Some(config),
+        // A broken config is one of the things doctor and validate report on
+        Err(_) if matches!(
+            cli.command,
+            Commands::Doctor(_) | Commands::Dev(DevCommand { action: DevAction::Validate(_) })
+        ) => None,
         Err(e) => {
             eprintln!("{} {}", "Configuration Error:".red().bold(), format_error(&e));
             process::exit(1);
@@ -447,6 +530,13 @@ async fn main() {
         Commands::Init(cmd) => init::execute(cmd, &core).await,
         Commands::New(cmd) => execute_new_command(cmd, &core).await,
         Commands::Run(cmd) => run::execute(cmd, &core).await,
+        Commands::Up(cmd) => up::execute(cmd, &core).await,
+        Commands::Exec(cmd) => exec::execute(cmd, &core).await,
+        Commands::Doctor(cmd) => doctor::execute(cmd, &core).await,
+        Commands::Migrate(cmd) => migrate::execute(cmd, &core).await,
+        Commands::Add(cmd) => add::execute(cmd, &core).await,
+        Commands::Rm(cmd) => add::execute_rm(cmd, &core).await,
+        Commands::Sync(cmd) => sync::execute(cmd, &core).await,
         Commands::List(cmd) => list::execute(cmd, &core).await,
         Commands::Update(cmd) => update::execute(cmd, &core).await,
         Commands::Remove(cmd) => remove::execute(cmd, &core).await,
@@ -461,29 +551,35 @@ async fn main() {
         Commands::Dev(cmd) => execute_dev_command(cmd, &core).await,
         Commands::Security(cmd) => execute_security_command(cmd, &core).await,
         Commands::Perf(cmd) => execute_perf_command(cmd, &core).await,
+        #[cfg(feature = "security-audit")]
+        Commands::Secret(cmd) => execute_secret_command(cmd, &core).await,
     };
 
     if let Err(e) = result {
-        eprintln!("{} {}", "Error:".red().bold(), format_error(&e));
-        
-        // Show suggestions for common errors
-        show_error_suggestions(&e);
+        match e.downcast_ref::<CyrusError>() {
+            Some(error) => {
+                eprintln!("{} {}", "Error:".red().bold(), format_error(error));
+                
+                // Show suggestions for common errors
+                show_error_suggestions(error);
+            },
+            None => eprintln!("{} {:#}", "Error:".red().bold(), e),
+        }
         
-        process::exit(1);
+        process::exit(error::exit_code_for(&e));
     }
 }
 
 fn load_config(cli: &Cli) -> AnyhowResult<config::GlobalConfig> {
-    let config_path = if let Some(path) = &cli.config {
-        path.clone()
-    } else {
-        dirs::config_dir()
-            .ok_or_else(|| CyrusError::Config {
-                message: "Could not determine config directory".to_string(),
-            })?
-            .join("cyrus")
-            .join("config.toml")
-    };
+    // Commands load the global config themselves; expose the overrides to them
+    if let Some(path) = &cli.config {
+        std::env::set_var("CYRUS_CONFIG", path);
+    }
+    if let Some(profile_name) = &cli.profile {
+        std::env::set_var("CYRUS_PROFILE", profile_name);
+    }
+
+    let config_path = config::GlobalConfig::config_path()?;
 
     let config = if config_path.exists() {
         config::GlobalConfig::load_from_file(&config_path)?
@@ -497,11 +593,8 @@ fn load_config(cli: &Cli) -> AnyhowResult<config::GlobalConfig> {
         config
     };
 
-    // Note: Profile switching would be implemented here in a real app
-    if let Some(_profile_name) = &cli.profile {
-        // Profile switching logic would go here
-        // For now, we just acknowledge the parameter exists
-    }
+    // Fail early on an unknown --profile
+    config.active_profile()?;
 
     Ok(config)
 }
@@ -654,10 +747,7 @@ fn print_template_info(template: &templates::TemplateInfo) {
 }
 
 async fn execute_plugin_command(cmd: PluginCommand, core: &CyrusCore) -> AnyhowResult<()> {
-    let plugin_dirs = vec![
-        core.cyrus_dir.join("plugins"),
-        dirs::data_dir().unwrap_or_default().join("cyrus").join("plugins"),
-    ];
+    let plugin_dirs = plugins::default_plugin_directories(&core.cyrus_dir);
     
     let mut plugin_manager = plugins::PluginManager::new(plugin_dirs);
     
@@ -710,7 +800,7 @@ async fn execute_plugin_command(cmd: PluginCommand, core: &CyrusCore) -> AnyhowR
 }
 
 async fn execute_workspace_command(cmd: WorkspaceCommand, _core: &CyrusCore) -> AnyhowResult<()> {
-    let workspace_path = find_workspace_root().unwrap_or_else(|| std::env::current_dir().unwrap());
+    let workspace_path = workspace::find_workspace_root().unwrap_or_else(|| std::env::current_dir().unwrap());
     
     match cmd.action {
         WorkspaceAction::Init { name, description, path } => {
@@ -838,15 +928,11 @@ async fn execute_dev_command(cmd: DevCommand, core: &CyrusCore) -> AnyhowResult<
             println!("\nEnvironment Variables:");
             for (key, value) in std::env::vars() {
                 if key.starts_with("CYRUS_") {
-                    println!("  {} = {}", key, value);
+                    println!("  {} = {}", key, mask_sensitive_value(&key, &value));
                 }
             }
         },
-        DevAction::Validate => {
-            println!("{} Validating configuration...", "✅".green());
-            // TODO: Implement configuration validation
-            println!("✅ Configuration is valid");
-        },
+        DevAction::Validate(cmd) => validate::execute(cmd, core).await?,
         DevAction::Completions { shell } => {
             use clap_complete::{generate, Generator};
             use std::io;
@@ -890,6 +976,19 @@ async fn execute_dev_command(cmd: DevCommand, core: &CyrusCore) -> AnyhowResult<
             
             println!("✅ Cleanup completed");
         },
+        DevAction::Schema { kind, editor } => {
+            use config::schema::{self, SchemaKind};
+
+            if editor {
+                println!("# Save the schemas first:");
+                for kind in SchemaKind::ALL {
+                    println!("#   cyrus dev schema --kind {} > {}", kind, kind.schema_path());
+                }
+                print!("{}", schema::editor_associations());
+            } else {
+                println!("{}", serde_json::to_string_pretty(&schema::generate(kind))?);
+            }
+        },
     }
     
     Ok(())
@@ -961,6 +1060,106 @@ async fn execute_perf_command(cmd: PerfCommand, _core: &CyrusCore) -> AnyhowResu
     Ok(())
 }
 
+#[cfg(feature = "security-audit")]
+async fn execute_secret_command(cmd: SecretCommand, core: &CyrusCore) -> AnyhowResult<()> {
+    use crate::secrets::{SecretScope, SecretStore};
+
+    let scope_for = |global: bool| -> CyrusResult<SecretScope> {
+        if global {
+            return Ok(SecretScope::Global);
+        }
+        crate::core::Project::find_project_root()
+            .map(SecretScope::Project)
+            .ok_or_else(|| CyrusError::ProjectNotFound {
+                path: std::env::current_dir().unwrap_or_default(),
+            })
+    };
+
+    match cmd.action {
+        SecretAction::Set { name, value, global } => {
+            let scope = scope_for(global)?;
+            let value = match value {
+                Some(value) => value,
+                None => dialoguer::Password::new()
+                    .with_prompt(format!("Value for '{}'", name))
+                    .interact()?,
+            };
+
+            let mut store = SecretStore::open(&core.cyrus_dir, &scope)?;
+            store.set(name.clone(), value)?;
+            store.save()?;
+            println!("{} Stored secret '{}' in {} vault", "🔒".green(), name.cyan(), scope.label());
+        },
+        SecretAction::Get { name, global } => {
+            let mut scopes = vec![SecretScope::Global];
+            if !global {
+                if let Ok(project) = scope_for(false) {
+                    scopes.insert(0, project);
+                }
+            }
+
+            for scope in scopes {
+                let store = SecretStore::open(&core.cyrus_dir, &scope)?;
+                if let Some(value) = store.get(&name) {
+                    println!("{}", value);
+                    return Ok(());
+                }
+            }
+
+            return Err(CyrusError::Security {
+                message: format!("Secret '{}' not found", name),
+            }
+            .into());
+        },
+        SecretAction::List { global } => {
+            let mut scopes = vec![SecretScope::Global];
+            if !global {
+                if let Ok(project) = scope_for(false) {
+                    scopes.insert(0, project);
+                }
+            }
+
+            for scope in scopes {
+                let store = SecretStore::open(&core.cyrus_dir, &scope)?;
+                println!("{} {} secrets:", "🔒".blue(), scope.label().yellow());
+                let names = store.names();
+                if names.is_empty() {
+                    println!("  {}", "(none)".dimmed());
+                }
+                for name in names {
+                    println!("  {} secret://{}", "•".cyan(), name);
+                }
+            }
+        },
+        SecretAction::Rm { name, global } => {
+            let scope = scope_for(global)?;
+            let mut store = SecretStore::open(&core.cyrus_dir, &scope)?;
+            if store.remove(&name) {
+                store.save()?;
+                println!("{} Removed secret '{}' from {} vault", "✅".green(), name.cyan(), scope.label());
+            } else {
+                println!("{} Secret '{}' not found in {} vault", "❌".red(), name.cyan(), scope.label());
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Hide values of variables that look like credentials in diagnostic output
+fn mask_sensitive_value(key: &str, value: &str) -> String {
+    let key = key.to_uppercase();
+    let sensitive = ["SECRET", "TOKEN", "PASSWORD", "PASSWD", "KEY", "CREDENTIAL"]
+        .iter()
+        .any(|marker| key.contains(marker));
+
+    if sensitive {
+        "********".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
 async fn languages_command(_core: &CyrusCore) -> AnyhowResult<()> {
     println!("{}", "🌐 Supported Languages:".cyan().bold());
     println!();
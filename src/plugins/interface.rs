@@ -3,6 +3,7 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use std::collections::HashMap;
 use async_trait::async_trait;
 
@@ -81,7 +82,7 @@ pub struct PluginInfo {
     pub capabilities: PluginCapabilities,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PluginCapabilities {
     pub provides_languages: Vec<String>,
     pub provides_templates: Vec<String>,
@@ -510,7 +511,7 @@ impl CyrusPlugin for DockerPlugin {
                 // Find project root and language
                 let current_dir = std::env::current_dir()?;
                 if let Some(project_root) = crate::core::Project::find_project_root() {
-                    let project = crate::core::Project::load_from_file(project_root.join("cyrus.toml"))?;
+                    let project = crate::core::Project::load_from_file(crate::core::Project::config_path(&project_root)?)?;
                     
                     // Generate Dockerfile
                     let dockerfile_content = self.generate_dockerfile(&project.language);
@@ -3,6 +3,7 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use libloading::{Library, Symbol};
@@ -18,7 +19,7 @@ use loader::PluginLoader;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PluginManifest {
     pub name: String,
     pub version: String,
@@ -33,7 +34,7 @@ pub struct PluginManifest {
     pub permissions: PluginPermissions,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PluginPermissions {
     pub filesystem_read: bool,
     pub filesystem_write: bool,
@@ -54,6 +55,14 @@ impl Default for PluginPermissions {
     }
 }
 
+/// Directories searched for installed plugins
+pub fn default_plugin_directories(cyrus_dir: &Path) -> Vec<PathBuf> {
+    vec![
+        cyrus_dir.join("plugins"),
+        dirs::data_dir().unwrap_or_default().join("cyrus").join("plugins"),
+    ]
+}
+
 pub struct PluginManager {
     registry: PluginRegistry,
     loader: PluginLoader,
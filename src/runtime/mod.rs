@@ -4,6 +4,10 @@ use anyhow::Result;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+pub mod process;
+pub mod supervisor;
+pub mod watch;
+
 pub struct RuntimeEnvironment {
     pub language: String,
     pub version: String,
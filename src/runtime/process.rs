@@ -0,0 +1,287 @@
+// src/runtime/process.rs
+//! Foreground child process execution with faithful exit semantics
+//!
+//! Commands started by `cyrus run` behave as if they were started by the
+//! shell: stdin/stdout/stderr are inherited, on Unix the child gets its own
+//! process group (which becomes the terminal's foreground group when stdin is
+//! a TTY), SIGINT/SIGTERM received by cyrus are forwarded to that group, and
+//! the child's exact exit code is reported back to the caller.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::future::Future;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+use tokio::process::{Child, Command};
+
+/// Exit code reported when a command exceeds its timeout (same as coreutils `timeout`)
+pub const TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// Exit code reported for a process stopped on request (128 + SIGTERM)
+pub const STOPPED_EXIT_CODE: i32 = 143;
+
+/// Time a timed-out process group gets between SIGTERM and SIGKILL
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessOutcome {
+    /// The process exited; signals are reported shell-style as 128 + signal number
+    Exited(i32),
+    /// The process was killed because it exceeded its timeout
+    TimedOut,
+    /// The process was stopped on request (e.g. a watch-mode restart)
+    Stopped,
+}
+
+impl ProcessOutcome {
+    pub fn code(&self) -> i32 {
+        match self {
+            ProcessOutcome::Exited(code) => *code,
+            ProcessOutcome::TimedOut => TIMEOUT_EXIT_CODE,
+            ProcessOutcome::Stopped => STOPPED_EXIT_CODE,
+        }
+    }
+
+    pub fn success(&self) -> bool {
+        matches!(self, ProcessOutcome::Exited(0))
+    }
+}
+
+/// Run a command in the foreground and wait for it to finish.
+///
+/// `envs` are added on top of the inherited environment.
+pub async fn run_foreground(
+    command: &str,
+    args: &[String],
+    envs: &HashMap<String, String>,
+    timeout: Option<Duration>,
+) -> Result<ProcessOutcome> {
+    run_until(command, args, envs, timeout, std::future::pending()).await
+}
+
+/// Like [`run_foreground`], but gracefully stops the process when `stop` completes
+pub async fn run_until(
+    command: &str,
+    args: &[String],
+    envs: &HashMap<String, String>,
+    timeout: Option<Duration>,
+    stop: impl Future<Output = ()>,
+) -> Result<ProcessOutcome> {
+    let mut cmd = Command::new(command);
+    cmd.args(args)
+        .envs(envs)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+
+    #[cfg(unix)]
+    let terminal = unix::TerminalGuard::new();
+    #[cfg(unix)]
+    unix::isolate_process_group(&mut cmd, terminal.hands_off());
+
+    let mut child = cmd
+        .spawn()
+        .with_context(|| format!("Failed to start '{}'", command))?;
+
+    let outcome = wait(&mut child, timeout, stop).await;
+
+    #[cfg(unix)]
+    drop(terminal);
+
+    outcome
+}
+
+/// Start a command in its own process group so terminal signals reach only cyrus
+pub fn isolate(cmd: &mut Command) {
+    #[cfg(unix)]
+    unix::isolate_process_group(cmd, false);
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// Gracefully stop a running child (and its process group on Unix)
+pub async fn stop(child: &mut Child) -> Result<()> {
+    #[cfg(unix)]
+    {
+        let pgid = child.id().map(|id| id as libc::pid_t);
+        terminate(child, pgid).await
+    }
+
+    #[cfg(not(unix))]
+    {
+        child.kill().await?;
+        Ok(())
+    }
+}
+
+/// Convert an exit status into the code a shell would report
+pub fn exit_code(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+
+    1
+}
+
+#[cfg(unix)]
+async fn wait(
+    child: &mut Child,
+    timeout: Option<Duration>,
+    stop: impl Future<Output = ()>,
+) -> Result<ProcessOutcome> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    // The child leads its own process group, so its pid is the group id
+    let pgid = child.id().map(|id| id as libc::pid_t);
+
+    let mut sigint = signal(SignalKind::interrupt())?;
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let deadline = sleep_or_forever(timeout);
+    tokio::pin!(deadline);
+    tokio::pin!(stop);
+
+    loop {
+        tokio::select! {
+            status = child.wait() => {
+                return Ok(ProcessOutcome::Exited(exit_code(status?)));
+            }
+            _ = sigint.recv() => unix::signal_group(pgid, libc::SIGINT),
+            _ = sigterm.recv() => unix::signal_group(pgid, libc::SIGTERM),
+            _ = &mut deadline => {
+                terminate(child, pgid).await?;
+                return Ok(ProcessOutcome::TimedOut);
+            }
+            _ = &mut stop => {
+                terminate(child, pgid).await?;
+                return Ok(ProcessOutcome::Stopped);
+            }
+        }
+    }
+}
+
+/// SIGTERM the process group, escalating to SIGKILL after the grace period
+#[cfg(unix)]
+async fn terminate(child: &mut Child, pgid: Option<libc::pid_t>) -> Result<()> {
+    unix::signal_group(pgid, libc::SIGTERM);
+    if tokio::time::timeout(KILL_GRACE_PERIOD, child.wait()).await.is_err() {
+        unix::signal_group(pgid, libc::SIGKILL);
+        child.wait().await?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn wait(
+    child: &mut Child,
+    timeout: Option<Duration>,
+    stop: impl Future<Output = ()>,
+) -> Result<ProcessOutcome> {
+    // Console Ctrl-C events already reach every attached process on Windows,
+    // so cyrus only needs to survive them and keep waiting.
+    let deadline = sleep_or_forever(timeout);
+    tokio::pin!(deadline);
+    tokio::pin!(stop);
+
+    loop {
+        tokio::select! {
+            status = child.wait() => {
+                return Ok(ProcessOutcome::Exited(exit_code(status?)));
+            }
+            _ = tokio::signal::ctrl_c() => {}
+            _ = &mut deadline => {
+                child.kill().await?;
+                return Ok(ProcessOutcome::TimedOut);
+            }
+            _ = &mut stop => {
+                child.kill().await?;
+                return Ok(ProcessOutcome::Stopped);
+            }
+        }
+    }
+}
+
+async fn sleep_or_forever(timeout: Option<Duration>) {
+    match timeout {
+        Some(duration) => tokio::time::sleep(duration).await,
+        None => std::future::pending::<()>().await,
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use tokio::process::Command;
+
+    /// Remembers the terminal's foreground process group and restores it on drop
+    pub struct TerminalGuard {
+        previous_foreground: Option<libc::pid_t>,
+    }
+
+    impl TerminalGuard {
+        pub fn new() -> Self {
+            // Only take over the terminal when cyrus itself owns it; a cyrus
+            // started in the background must not steal the foreground.
+            let previous_foreground = unsafe {
+                if libc::isatty(libc::STDIN_FILENO) == 1
+                    && libc::tcgetpgrp(libc::STDIN_FILENO) == libc::getpgrp()
+                {
+                    Some(libc::getpgrp())
+                } else {
+                    None
+                }
+            };
+
+            Self { previous_foreground }
+        }
+
+        pub fn hands_off(&self) -> bool {
+            self.previous_foreground.is_some()
+        }
+    }
+
+    impl Drop for TerminalGuard {
+        fn drop(&mut self) {
+            if let Some(pgrp) = self.previous_foreground {
+                unsafe {
+                    // cyrus is a background group at this point; ignore SIGTTOU
+                    // while reclaiming the terminal.
+                    let previous = libc::signal(libc::SIGTTOU, libc::SIG_IGN);
+                    libc::tcsetpgrp(libc::STDIN_FILENO, pgrp);
+                    libc::signal(libc::SIGTTOU, previous);
+                }
+            }
+        }
+    }
+
+    /// Start the child in its own process group, optionally as the terminal's foreground group
+    pub fn isolate_process_group(cmd: &mut Command, take_terminal: bool) {
+        unsafe {
+            cmd.pre_exec(move || {
+                if libc::setpgid(0, 0) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                if take_terminal {
+                    libc::signal(libc::SIGTTOU, libc::SIG_IGN);
+                    libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpgrp());
+                    libc::signal(libc::SIGTTOU, libc::SIG_DFL);
+                }
+                Ok(())
+            });
+        }
+    }
+
+    pub fn signal_group(pgid: Option<libc::pid_t>, signal: libc::c_int) {
+        if let Some(pgid) = pgid {
+            unsafe {
+                libc::kill(-pgid, signal);
+            }
+        }
+    }
+}
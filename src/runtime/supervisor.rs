@@ -0,0 +1,165 @@
+// src/runtime/supervisor.rs
+//! Multi-process supervisor behind `cyrus up`
+//!
+//! Every process runs in its own process group with its output prefixed by
+//! a colored name. Processes are restarted according to their policy; Ctrl-C
+//! stops all of them gracefully (SIGTERM, then SIGKILL after a grace period).
+
+use crate::core::procfile::RestartPolicy;
+use crate::core::script::Invocation;
+use crate::runtime::process;
+use anyhow::{Context, Result};
+use colored::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::sync::watch;
+
+/// Delay before restarting a process, doubled after each quick failure
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(30);
+
+/// A process that ran at least this long resets the restart backoff
+const STABLE_AFTER: Duration = Duration::from_secs(10);
+
+const COLORS: &[Color] = &[
+    Color::Cyan,
+    Color::Magenta,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::BrightRed,
+    Color::BrightCyan,
+    Color::BrightMagenta,
+];
+
+#[derive(Debug, Clone)]
+pub struct ManagedProcess {
+    pub name: String,
+    pub invocation: Invocation,
+    pub cwd: PathBuf,
+    pub env: HashMap<String, String>,
+    pub restart: RestartPolicy,
+}
+
+/// Run all processes until they exit for good or Ctrl-C is pressed.
+///
+/// Returns the highest exit code of processes that stopped on their own.
+pub async fn supervise(processes: Vec<ManagedProcess>) -> Result<i32> {
+    let width = processes.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    let mut handles = Vec::new();
+    for (index, managed) in processes.into_iter().enumerate() {
+        let prefix = format!("{:width$} |", managed.name, width = width)
+            .color(COLORS[index % COLORS.len()])
+            .to_string();
+        handles.push(tokio::spawn(keep_running(managed, prefix, shutdown_rx.clone())));
+    }
+
+    let all_done = futures::future::join_all(handles);
+    tokio::pin!(all_done);
+
+    let results = tokio::select! {
+        results = &mut all_done => results,
+        _ = tokio::signal::ctrl_c() => {
+            eprintln!("\n{} Shutting down...", "🛑".red());
+            let _ = shutdown_tx.send(true);
+            all_done.await
+        }
+    };
+
+    let mut worst = 0;
+    for result in results {
+        let code = result.context("Process supervisor task panicked")??;
+        worst = worst.max(code);
+    }
+
+    Ok(if *shutdown_tx.borrow() { 0 } else { worst })
+}
+
+async fn keep_running(
+    managed: ManagedProcess,
+    prefix: String,
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<i32> {
+    let mut delay = RESTART_DELAY;
+
+    loop {
+        let (program, args) = managed.invocation.to_program_args();
+        let mut cmd = Command::new(&program);
+        cmd.args(&args)
+            .current_dir(&managed.cwd)
+            .envs(&managed.env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        process::isolate(&mut cmd);
+
+        eprintln!("{} {} {}", prefix, "started".dimmed(), managed.invocation.display().dimmed());
+        let started = std::time::Instant::now();
+
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to start process '{}'", managed.name))?;
+
+        let stdout = child.stdout.take().map(|out| tokio::spawn(pump(out, prefix.clone())));
+        let stderr = child.stderr.take().map(|err| tokio::spawn(pump(err, prefix.clone())));
+
+        let code = tokio::select! {
+            status = child.wait() => process::exit_code(status?),
+            _ = shutdown_requested(&mut shutdown) => {
+                process::stop(&mut child).await?;
+                finish_output(stdout, stderr).await;
+                eprintln!("{} {}", prefix, "stopped".dimmed());
+                return Ok(0);
+            }
+        };
+        finish_output(stdout, stderr).await;
+
+        let exited = format!("exited with code {}", code);
+        eprintln!("{} {}", prefix, if code == 0 { exited.dimmed() } else { exited.red() });
+
+        if !managed.restart.should_restart(code) || *shutdown.borrow() {
+            return Ok(code);
+        }
+
+        if started.elapsed() >= STABLE_AFTER {
+            delay = RESTART_DELAY;
+        }
+
+        eprintln!("{} {}", prefix, format!("restarting in {}s ({})", delay.as_secs(), managed.restart).yellow());
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown_requested(&mut shutdown) => return Ok(code),
+        }
+        delay = (delay * 2).min(MAX_RESTART_DELAY);
+    }
+}
+
+async fn pump(stream: impl AsyncRead + Unpin, prefix: String) {
+    let mut lines = BufReader::new(stream).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        println!("{} {}", prefix, line);
+    }
+}
+
+async fn finish_output(
+    stdout: Option<tokio::task::JoinHandle<()>>,
+    stderr: Option<tokio::task::JoinHandle<()>>,
+) {
+    for handle in [stdout, stderr].into_iter().flatten() {
+        let _ = handle.await;
+    }
+}
+
+async fn shutdown_requested(shutdown: &mut watch::Receiver<bool>) {
+    while !*shutdown.borrow() {
+        if shutdown.changed().await.is_err() {
+            return std::future::pending().await;
+        }
+    }
+}
@@ -0,0 +1,235 @@
+// src/runtime/watch.rs
+//! File watching for `cyrus run --watch`
+//!
+//! Filesystem notifications are debounced so that a burst of writes (an
+//! editor saving several files, a `git checkout`) triggers a single re-run.
+//! Paths ignored by the project's `.gitignore` files never trigger a run,
+//! and neither do `.git` or cyrus' own `.cyrus` state directory. Only the
+//! directories that aren't ignored get a (non-recursive) watch, so large
+//! ignored trees cost no watches; directories created later are added as
+//! they appear, and `.gitignore` edits take effect without a restart.
+
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder};
+use notify::{RecursiveMode, Watcher};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Quiet period after the last change before a run is triggered
+pub const DEBOUNCE: Duration = Duration::from_millis(200);
+
+const ALWAYS_IGNORED: &[&str] = &[".git", ".cyrus"];
+
+pub struct FileWatcher {
+    filter: WatchFilter,
+    events: mpsc::UnboundedReceiver<Vec<PathBuf>>,
+    debouncer: Debouncer<notify::RecommendedWatcher>,
+    /// Directories with a non-recursive watch; ignored trees never get one
+    watched: HashSet<PathBuf>,
+}
+
+impl FileWatcher {
+    /// Watch every directory under `root` that isn't ignored; when `globs` is non-empty only matching paths count
+    pub fn new(root: &Path, globs: &[String]) -> Result<Self> {
+        let filter = WatchFilter::new(root, globs)?;
+        let (tx, events) = mpsc::unbounded_channel();
+
+        let debouncer = new_debouncer(DEBOUNCE, move |result: DebounceEventResult| {
+            if let Ok(batch) = result {
+                let _ = tx.send(batch.into_iter().map(|event| event.path).collect());
+            }
+        })
+        .context("Failed to start file watcher")?;
+
+        let mut watcher = Self {
+            filter,
+            events,
+            debouncer,
+            watched: HashSet::new(),
+        };
+        let root = watcher.filter.root.clone();
+        watcher.sync_watches(&root)?;
+        Ok(watcher)
+    }
+
+    /// Wait for the next batch of relevant changes
+    pub async fn changed(&mut self) -> Vec<PathBuf> {
+        loop {
+            let Some(batch) = self.events.recv().await else {
+                // The watcher thread is gone; nothing will ever change again
+                return std::future::pending().await;
+            };
+
+            self.follow(&batch);
+            let relevant: Vec<PathBuf> = batch
+                .into_iter()
+                .filter_map(|path| self.filter.relevant(&path))
+                .collect();
+
+            if !relevant.is_empty() {
+                return relevant;
+            }
+        }
+    }
+
+    /// Keep the watches in step with the tree: new directories get one, deleted
+    /// ones drop theirs, and an edited `.gitignore` re-decides its whole subtree
+    fn follow(&mut self, batch: &[PathBuf]) {
+        let gitignores: Vec<&Path> = batch.iter()
+            .filter(|path| path.file_name().is_some_and(|name| name == ".gitignore"))
+            .filter_map(|path| path.parent())
+            .collect();
+        if !gitignores.is_empty() {
+            self.filter.reload_gitignores();
+        }
+
+        for path in batch {
+            if !path.exists() {
+                self.watched.retain(|dir| !dir.starts_with(path));
+            }
+        }
+
+        let new_dirs = batch.iter()
+            .filter(|path| path.is_dir() && !self.watched.contains(*path) && !self.filter.is_excluded(path, true));
+        let changed: Vec<PathBuf> = gitignores.into_iter().map(Path::to_path_buf).chain(new_dirs.cloned()).collect();
+        for dir in changed {
+            // A directory can vanish again before it is watched; the next event catches up
+            let _ = self.sync_watches(&dir);
+        }
+    }
+
+    /// Watch each directory under `dir` the walk visits, and stop watching the ones it no longer does
+    fn sync_watches(&mut self, dir: &Path) -> Result<()> {
+        let wanted: HashSet<PathBuf> = watched_dirs(dir).into_iter().collect();
+
+        let stale: Vec<PathBuf> = self.watched.iter()
+            .filter(|watched| watched.starts_with(dir) && !wanted.contains(*watched))
+            .cloned()
+            .collect();
+        for watched in stale {
+            let _ = self.debouncer.watcher().unwatch(&watched);
+            self.watched.remove(&watched);
+        }
+
+        for wanted in wanted {
+            if self.watched.contains(&wanted) {
+                continue;
+            }
+            self.debouncer
+                .watcher()
+                .watch(&wanted, RecursiveMode::NonRecursive)
+                .with_context(|| format!("Failed to watch {}", wanted.display()))?;
+            self.watched.insert(wanted);
+        }
+        Ok(())
+    }
+}
+
+/// Which changed paths count: not ignored, and matching `--glob` when given
+pub struct WatchFilter {
+    root: PathBuf,
+    globs: Option<GlobSet>,
+    /// One per directory with a `.gitignore`, parents before children
+    gitignores: Vec<Gitignore>,
+}
+
+impl WatchFilter {
+    pub fn new(root: &Path, globs: &[String]) -> Result<Self> {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+
+        let globs = if globs.is_empty() {
+            None
+        } else {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in globs {
+                builder.add(Glob::new(pattern).with_context(|| format!("Invalid glob '{}'", pattern))?);
+            }
+            Some(builder.build()?)
+        };
+
+        Ok(Self {
+            gitignores: load_gitignores(&root),
+            root,
+            globs,
+        })
+    }
+
+    /// `path` relative to the root, if a change to it should trigger a run
+    pub fn relevant(&self, path: &Path) -> Option<PathBuf> {
+        let relative = path.strip_prefix(&self.root).ok()?.to_path_buf();
+
+        if self.is_excluded(path, path.is_dir()) {
+            return None;
+        }
+
+        match &self.globs {
+            Some(globs) if !globs.is_match(&relative) => None,
+            _ => Some(relative),
+        }
+    }
+
+    /// Under `.git`/`.cyrus`, or ignored by a `.gitignore`
+    fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return true;
+        };
+        relative.components().any(|c| ALWAYS_IGNORED.contains(&c.as_os_str().to_string_lossy().as_ref()))
+            || self.is_ignored(path, is_dir)
+    }
+
+    /// Pick up `.gitignore` files added, changed or removed since the watch started
+    fn reload_gitignores(&mut self) {
+        self.gitignores = load_gitignores(&self.root);
+    }
+
+    /// The nearest `.gitignore` with an opinion decides, as in git
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        for gitignore in self.gitignores.iter().rev().filter(|g| path.starts_with(g.path())) {
+            match gitignore.matched_path_or_any_parents(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {},
+            }
+        }
+        false
+    }
+}
+
+/// Directories under `dir` that aren't ignored. The walk honours `.gitignore`
+/// files (including those above `dir`), so ignored trees (`target/`, `node_modules/`) are never entered.
+pub fn watched_dirs(dir: &Path) -> Vec<PathBuf> {
+    WalkBuilder::new(dir)
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(|entry| {
+            entry.file_type().is_some_and(|t| t.is_dir())
+                && !ALWAYS_IGNORED.contains(&entry.file_name().to_string_lossy().as_ref())
+        })
+        .build()
+        .flatten()
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// Each watched directory's `.gitignore`, anchored at that directory
+fn load_gitignores(root: &Path) -> Vec<Gitignore> {
+    let mut gitignores = Vec::new();
+    for dir in watched_dirs(root) {
+        let file = dir.join(".gitignore");
+        if !file.is_file() {
+            continue;
+        }
+        let mut builder = GitignoreBuilder::new(&dir);
+        // A broken pattern only loses that pattern, not the whole watch
+        let _ = builder.add(&file);
+        if let Ok(gitignore) = builder.build() {
+            gitignores.push(gitignore);
+        }
+    }
+    gitignores
+}
@@ -0,0 +1,316 @@
+// src/secrets/mod.rs
+//! Encrypted local secrets vault
+//!
+//! Secrets live under `~/.cyrus/secrets`, one vault per scope: a global vault
+//! plus one vault per project (keyed by a hash of the project root). Vaults
+//! are sealed with ChaCha20-Poly1305. Project environment values of the form
+//! `secret://name` are resolved against the project vault first, then the
+//! global one, only when a command is run.
+//!
+//! Where the key comes from decides what the encryption protects against:
+//!
+//! - With `CYRUS_SECRETS_PASSPHRASE` set, the key is derived from it with
+//!   PBKDF2-HMAC-SHA256 and a random salt kept in `vault.salt`. Nothing on
+//!   disk is enough to open a vault, so copies of `~/.cyrus` (backups, synced
+//!   dotfiles, a stolen disk) stay sealed.
+//! - Otherwise the key is a random `vault.key` next to the vaults, readable
+//!   by its owner only. That keeps secrets out of `cyrus.toml` and away from
+//!   other local users, but anyone who can read your files can read the key
+//!   too: it is not encryption at rest.
+
+use crate::error::{CyrusError, Result};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+
+/// Prefix marking an environment value as a reference into the vault
+pub const SECRET_SCHEME: &str = "secret://";
+
+/// Passphrase the vault key is derived from; without it the key is kept in `vault.key`
+pub const PASSPHRASE_ENV: &str = "CYRUS_SECRETS_PASSPHRASE";
+
+const KEY_FILE: &str = "vault.key";
+const SALT_FILE: &str = "vault.salt";
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const VAULT_AAD: &[u8] = b"cyrus-secrets-v1";
+
+/// Which vault a secret belongs to
+#[derive(Debug, Clone)]
+pub enum SecretScope {
+    Global,
+    Project(PathBuf),
+}
+
+impl SecretScope {
+    pub fn label(&self) -> String {
+        match self {
+            SecretScope::Global => "global".to_string(),
+            SecretScope::Project(root) => format!("project ({})", root.display()),
+        }
+    }
+}
+
+pub struct SecretStore {
+    vault_path: PathBuf,
+    key: LessSafeKey,
+    entries: BTreeMap<String, String>,
+}
+
+impl SecretStore {
+    /// Open (or create) the vault for the given scope
+    pub fn open(cyrus_dir: &Path, scope: &SecretScope) -> Result<Self> {
+        let secrets_dir = cyrus_dir.join("secrets");
+        std::fs::create_dir_all(&secrets_dir)?;
+        restrict_permissions(&secrets_dir, 0o700)?;
+
+        let key = load_key(&secrets_dir)?;
+        let vault_path = match scope {
+            SecretScope::Global => secrets_dir.join("global.vault"),
+            SecretScope::Project(root) => secrets_dir
+                .join("projects")
+                .join(format!("{}.vault", project_id(root))),
+        };
+
+        let entries = if vault_path.exists() {
+            let sealed = std::fs::read(&vault_path)?;
+            open_vault(&key, sealed)?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            vault_path,
+            key,
+            entries,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries.get(name).map(|v| v.as_str())
+    }
+
+    pub fn set(&mut self, name: String, value: String) -> Result<()> {
+        validate_name(&name)?;
+        self.entries.insert(name, value);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.entries.remove(name).is_some()
+    }
+
+    pub fn names(&self) -> Vec<&String> {
+        self.entries.keys().collect()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.vault_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let plaintext = serde_json::to_vec(&self.entries).map_err(|e| CyrusError::Security {
+            message: format!("Failed to serialize secrets vault: {}", e),
+        })?;
+
+        let sealed = seal_vault(&self.key, plaintext)?;
+        std::fs::write(&self.vault_path, sealed)?;
+        restrict_permissions(&self.vault_path, 0o600)?;
+        Ok(())
+    }
+}
+
+/// Check whether an environment value refers to the vault
+pub fn is_secret_reference(value: &str) -> bool {
+    value.starts_with(SECRET_SCHEME)
+}
+
+/// Resolve `secret://` references in a project environment.
+///
+/// Plain values are passed through untouched. A reference that exists in
+/// neither the project nor the global vault is an error, so a command never
+/// silently runs with the literal `secret://...` string.
+pub fn resolve_environment(
+    cyrus_dir: &Path,
+    project_root: Option<&Path>,
+    environment: &HashMap<String, String>,
+) -> Result<HashMap<String, String>> {
+    if !environment.values().any(|v| is_secret_reference(v)) {
+        return Ok(environment.clone());
+    }
+
+    let project_store = match project_root {
+        Some(root) => Some(SecretStore::open(cyrus_dir, &SecretScope::Project(root.to_path_buf()))?),
+        None => None,
+    };
+    let global_store = SecretStore::open(cyrus_dir, &SecretScope::Global)?;
+
+    let mut resolved = HashMap::new();
+    for (key, value) in environment {
+        let value = match value.strip_prefix(SECRET_SCHEME) {
+            Some(name) => project_store
+                .as_ref()
+                .and_then(|store| store.get(name))
+                .or_else(|| global_store.get(name))
+                .map(|v| v.to_string())
+                .ok_or_else(|| CyrusError::Security {
+                    message: format!(
+                        "Secret '{}' referenced by ${} is not set. Run 'cyrus secret set {}'",
+                        name, key, name
+                    ),
+                })?,
+            None => value.clone(),
+        };
+        resolved.insert(key.clone(), value);
+    }
+
+    Ok(resolved)
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.');
+
+    if !valid {
+        return Err(CyrusError::Security {
+            message: format!(
+                "Invalid secret name '{}'. Use letters, digits, '_', '-' or '.'",
+                name
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn project_id(root: &Path) -> String {
+    let canonical = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let digest = Sha256::digest(canonical.to_string_lossy().as_bytes());
+    digest.iter().take(16).map(|b| format!("{:02x}", b)).collect()
+}
+
+fn load_key(secrets_dir: &Path) -> Result<LessSafeKey> {
+    let key_bytes = match std::env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) if !passphrase.is_empty() => {
+            let salt = read_or_create_random(&secrets_dir.join(SALT_FILE), 16)?;
+            let mut key = vec![0u8; CHACHA20_POLY1305.key_len()];
+            ring::pbkdf2::derive(
+                ring::pbkdf2::PBKDF2_HMAC_SHA256,
+                NonZeroU32::new(PBKDF2_ITERATIONS).expect("iterations are non-zero"),
+                &salt,
+                passphrase.as_bytes(),
+                &mut key,
+            );
+            key
+        },
+        _ => read_or_create_random(&secrets_dir.join(KEY_FILE), CHACHA20_POLY1305.key_len())?,
+    };
+
+    let unbound = UnboundKey::new(&CHACHA20_POLY1305, &key_bytes).map_err(|_| CyrusError::Security {
+        message: format!("Secrets key at {:?} is corrupt", secrets_dir.join(KEY_FILE)),
+    })?;
+
+    Ok(LessSafeKey::new(unbound))
+}
+
+/// Contents of `path`, filling it with `len` random bytes first if it doesn't exist
+fn read_or_create_random(path: &Path, len: usize) -> Result<Vec<u8>> {
+    if path.exists() {
+        return Ok(std::fs::read(path)?);
+    }
+
+    let mut bytes = vec![0u8; len];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| CyrusError::Security {
+            message: "Failed to generate secrets key".to_string(),
+        })?;
+    write_private(path, &bytes)?;
+    Ok(bytes)
+}
+
+fn seal_vault(key: &LessSafeKey, mut plaintext: Vec<u8>) -> Result<Vec<u8>> {
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce_bytes)
+        .map_err(|_| CyrusError::Security {
+            message: "Failed to generate nonce".to_string(),
+        })?;
+
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce_bytes),
+        Aad::from(VAULT_AAD),
+        &mut plaintext,
+    )
+    .map_err(|_| CyrusError::Security {
+        message: "Failed to encrypt secrets vault".to_string(),
+    })?;
+
+    let mut sealed = nonce_bytes.to_vec();
+    sealed.extend_from_slice(&plaintext);
+    Ok(sealed)
+}
+
+fn open_vault(key: &LessSafeKey, sealed: Vec<u8>) -> Result<BTreeMap<String, String>> {
+    if sealed.len() < NONCE_LEN {
+        return Err(CyrusError::Security {
+            message: "Secrets vault is truncated".to_string(),
+        });
+    }
+
+    let (nonce_bytes, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce_bytes).map_err(|_| CyrusError::Security {
+        message: "Secrets vault has an invalid nonce".to_string(),
+    })?;
+
+    let mut buffer = ciphertext.to_vec();
+    let plaintext = key
+        .open_in_place(nonce, Aad::from(VAULT_AAD), &mut buffer)
+        .map_err(|_| CyrusError::Security {
+            message: format!(
+                "Failed to decrypt secrets vault (wrong key or tampered file). Was it sealed with a different {}?",
+                PASSPHRASE_ENV
+            ),
+        })?;
+
+    serde_json::from_slice(plaintext).map_err(|e| CyrusError::Security {
+        message: format!("Failed to parse secrets vault: {}", e),
+    })
+}
+
+/// Create `path` readable by its owner only; the mode is set when the file is created,
+/// so the contents are never briefly readable by others
+#[cfg(unix)]
+fn write_private(path: &Path, bytes: &[u8]) -> Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(bytes)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, bytes: &[u8]) -> Result<()> {
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path, mode: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path, _mode: u32) -> Result<()> {
+    Ok(())
+}
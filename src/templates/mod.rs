@@ -3,6 +3,7 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use handlebars::Handlebars;
@@ -16,7 +17,7 @@ use builtin::*;
 use git::GitTemplateSource;
 use registry::TemplateRegistry;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct ProjectTemplate {
     pub name: String,
     pub description: String,
@@ -58,7 +59,7 @@ pub struct ProjectTemplate {
     pub hooks: TemplateHooks,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct TemplateMetadata {
     pub category: TemplateCategory,
     pub tags: Vec<String>,
@@ -72,7 +73,7 @@ pub struct TemplateMetadata {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub enum TemplateCategory {
     Web,
     Api,
@@ -88,7 +89,7 @@ pub enum TemplateCategory {
     Other(String),
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub enum DifficultyLevel {
     Beginner,
     Intermediate,
@@ -96,7 +97,7 @@ pub enum DifficultyLevel {
     Expert,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct TemplateVariable {
     pub description: String,
     pub default_value: Option<String>,
@@ -105,7 +106,7 @@ pub struct TemplateVariable {
     pub validation: Option<String>, // Regex pattern
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub enum VariableType {
     String,
     Number,
@@ -116,7 +117,7 @@ pub enum VariableType {
     Email,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct TemplateFeature {
     pub name: String,
     pub description: String,
@@ -127,7 +128,7 @@ pub struct TemplateFeature {
     pub post_install_commands: Vec<PostInstallCommand>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PostInstallCommand {
     pub command: String,
     pub args: Vec<String>,
@@ -136,7 +137,7 @@ pub struct PostInstallCommand {
     pub ignore_failure: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct TemplateHooks {
     pub pre_create: Vec<String>,
     pub post_create: Vec<String>,
@@ -367,7 +368,9 @@ impl TemplateManager {
         
         project_config.dependencies = template.dependencies.clone();
         project_config.dev_dependencies = template.dev_dependencies.clone();
-        project_config.scripts = template.scripts.clone();
+        project_config.scripts = template.scripts.iter()
+            .map(|(name, command)| (name.clone(), command.clone().into()))
+            .collect();
         project_config.custom_aliases = template.aliases.clone();
         project_config.environment = template.environment.clone();
         
@@ -376,7 +379,7 @@ impl TemplateManager {
             if let Some(feature) = template.features.iter().find(|f| f.name == *feature_name) {
                 project_config.dependencies.extend_from_slice(&feature.dependencies);
                 for (script_name, script_cmd) in &feature.scripts {
-                    project_config.scripts.insert(script_name.clone(), script_cmd.clone());
+                    project_config.scripts.insert(script_name.clone(), script_cmd.clone().into());
                 }
             }
         }
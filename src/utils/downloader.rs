@@ -64,3 +64,48 @@ pub async fn download_with_retries(url: &str, destination: &Path, max_retries: u
     
     Err(anyhow::anyhow!("Failed to download after {} retries", max_retries))
 }
+
+/// File in an install directory recording where its archive came from
+const DOWNLOAD_RECORD: &str = ".cyrus-download.json";
+
+/// Origin of an installed toolchain, written next to it after download
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DownloadRecord {
+    pub url: String,
+    pub sha256: String,
+}
+
+pub fn sha256_file(path: &Path) -> Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let mut file = File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Record the URL and digest of a downloaded archive in `install_path`.
+/// An archive that doesn't match `expected_sha256` is deleted instead, before anything unpacks it.
+pub fn record_download(install_path: &Path, url: &str, archive: &Path, expected_sha256: Option<&str>) -> Result<DownloadRecord> {
+    let sha256 = sha256_file(archive)?;
+    if let Some(expected) = expected_sha256 {
+        if expected != sha256 {
+            let _ = std::fs::remove_file(archive);
+            anyhow::bail!("Checksum mismatch for {}: expected sha256 {}, got {}", url, expected, sha256);
+        }
+    }
+
+    let record = DownloadRecord {
+        url: url.to_string(),
+        sha256,
+    };
+    std::fs::write(install_path.join(DOWNLOAD_RECORD), serde_json::to_string_pretty(&record)?)
+        .context("Failed to write download record")?;
+    Ok(record)
+}
+
+pub fn read_download_record(install_path: &Path) -> Option<DownloadRecord> {
+    let content = std::fs::read_to_string(install_path.join(DOWNLOAD_RECORD)).ok()?;
+    serde_json::from_str(&content).ok()
+}
@@ -14,6 +14,11 @@ pub fn ensure_directory_exists(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Path as a string for environment variables
+pub fn path_string(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
 pub fn is_executable(path: &Path) -> bool {
     path.exists() && path.is_file()
 }
@@ -20,6 +20,11 @@ impl Platform {
         }
     }
     
+    /// `os-arch` key for platform-specific artifacts, e.g. `linux-x86_64`
+    pub fn target() -> String {
+        format!("{}-{}", env::consts::OS, env::consts::ARCH)
+    }
+    
     pub fn executable_extension(&self) -> &str {
         match self {
             Platform::Windows => ".exe",
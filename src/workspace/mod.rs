@@ -3,13 +3,14 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use crate::error::{CyrusError, Result as CyrusResult};
-use crate::core::Project;
+use crate::core::{config_format, Project};
 use futures::future;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct Workspace {
     pub name: String,
     pub description: String,
@@ -22,7 +23,7 @@ pub struct Workspace {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct WorkspaceMember {
     pub name: String,
     pub path: PathBuf,
@@ -32,7 +33,7 @@ pub struct WorkspaceMember {
     pub dependencies: Vec<String>, // Other workspace members this depends on
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct WorkspaceConfig {
     pub default_language_versions: HashMap<String, String>,
     pub shared_environment: HashMap<String, String>,
@@ -41,13 +42,13 @@ pub struct WorkspaceConfig {
     pub max_parallel_jobs: Option<u32>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct WorkspaceDependencies {
     pub shared_dependencies: HashMap<String, Vec<String>>, // language -> dependencies
     pub shared_dev_dependencies: HashMap<String, Vec<String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct WorkspaceScript {
     pub command: String,
     pub description: String,
@@ -77,6 +78,23 @@ impl Default for WorkspaceDependencies {
     }
 }
 
+/// Nearest directory (from the current one upwards) containing `cyrus-workspace.toml`
+pub fn find_workspace_root() -> Option<PathBuf> {
+    let mut current = std::env::current_dir().ok()?;
+    
+    loop {
+        if current.join("cyrus-workspace.toml").exists() {
+            return Some(current);
+        }
+        
+        if !current.pop() {
+            break;
+        }
+    }
+    
+    None
+}
+
 pub struct WorkspaceManager {
     current_workspace: Option<Workspace>,
 }
@@ -381,7 +399,7 @@ impl WorkspaceManager {
                 language: member.language.clone(),
                 enabled: member.enabled,
                 exists: member_path.exists(),
-                has_cyrus_config: member_path.join("cyrus.toml").exists(),
+                has_cyrus_config: config_format::exists(&member_path),
                 last_modified: self.get_last_modified(&member_path)?,
             };
             member_statuses.push(status);
@@ -424,24 +442,7 @@ impl WorkspaceManager {
     }
     
     fn detect_project_language(&self, path: &Path) -> Option<String> {
-        // Check for common language files
-        if path.join("package.json").exists() {
-            Some("javascript".to_string())
-        } else if path.join("Cargo.toml").exists() {
-            Some("rust".to_string())
-        } else if path.join("requirements.txt").exists() || path.join("pyproject.toml").exists() {
-            Some("python".to_string())
-        } else if path.join("go.mod").exists() {
-            Some("golang".to_string())
-        } else if path.join("pom.xml").exists() || path.join("build.gradle").exists() {
-            Some("java".to_string())
-        } else if path.join("composer.json").exists() {
-            Some("php".to_string())
-        } else if path.join("Gemfile").exists() {
-            Some("ruby".to_string())
-        } else {
-            None
-        }
+        crate::core::detect::detect_language(path).map(|language| language.value)
     }
     
     async fn run_parallel(
@@ -552,7 +553,7 @@ impl WorkspaceManager {
         let member_path = workspace.root_path.join(&member.path);
         
         // Check if it's a Cyrus project
-        if member_path.join("cyrus.toml").exists() {
+        if config_format::exists(&member_path) {
             // Use cyrus run
             let mut cyrus_cmd = tokio::process::Command::new("cyrus");
             cyrus_cmd.arg("run")
@@ -0,0 +1,76 @@
+//! Unit tests for alias placeholder substitution and recursive expansion
+
+use cyrus::core::alias::{expand, merge_layers, resolve_layers, substitute, AliasLayer, AliasSource};
+use cyrus::error::CyrusError;
+use std::collections::HashMap;
+
+fn aliases(entries: &[(&str, &str)]) -> HashMap<String, String> {
+    entries
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn args(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+#[test]
+fn test_positional_placeholders() {
+    let aliases = aliases(&[("mig", "bundle exec rails db:migrate VERSION=$1")]);
+    let expansion = expand(&aliases, "mig", &args(&["20240101"])).unwrap();
+    assert_eq!(expansion.command_line, "bundle exec rails db:migrate VERSION=20240101");
+}
+
+#[test]
+fn test_defaults_and_all_arguments() {
+    assert_eq!(substitute("serve --port ${1:-8000}", "").unwrap(), "serve --port 8000");
+    assert_eq!(substitute("serve --port ${1:-8000}", "9000").unwrap(), "serve --port 9000");
+    assert_eq!(substitute("pytest -x $@", "-k 'slow test'").unwrap(), "pytest -x -k 'slow test'");
+    assert_eq!(substitute("echo ${HOME}", "").unwrap(), "echo ${HOME}");
+}
+
+#[test]
+fn test_arguments_appended_without_placeholders() {
+    let aliases = aliases(&[("t", "bun test")]);
+    let expansion = expand(&aliases, "t", &args(&["--watch"])).unwrap();
+    assert_eq!(expansion.command_line, "bun test --watch");
+}
+
+#[test]
+fn test_recursive_expansion_and_self_reference() {
+    let aliases = aliases(&[
+        ("mvn", "mvn -q"),
+        ("t", "mvn test -Dtest=$1"),
+        ("tt", "t UserTest"),
+    ]);
+
+    let expansion = expand(&aliases, "tt", &[]).unwrap();
+    assert_eq!(expansion.chain, ["tt", "t", "mvn"]);
+    assert_eq!(expansion.command_line, "mvn -q test -Dtest=UserTest");
+}
+
+#[test]
+fn test_alias_cycles_are_reported() {
+    let aliases = aliases(&[("a", "b --x"), ("b", "a --y")]);
+    assert!(matches!(expand(&aliases, "a", &[]), Err(CyrusError::Alias { .. })));
+}
+
+#[test]
+fn test_layers_resolve_by_precedence() {
+    let layers = vec![
+        AliasLayer { source: AliasSource::ProjectAlias, aliases: aliases(&[("t", "bun test")]) },
+        AliasLayer { source: AliasSource::Profile("enterprise".to_string()), aliases: aliases(&[("audit", "npm audit")]) },
+        AliasLayer { source: AliasSource::Global, aliases: aliases(&[("t", "npm test"), ("gs", "git status")]) },
+    ];
+
+    let merged = merge_layers(&layers);
+    assert_eq!(merged["t"], "bun test");
+    assert_eq!(merged["gs"], "git status");
+
+    let resolved = resolve_layers(&layers);
+    let t = resolved.iter().find(|r| r.name == "t").unwrap();
+    assert_eq!(t.source, AliasSource::ProjectAlias);
+    assert_eq!(t.shadowed, vec![(AliasSource::Global, "npm test".to_string())]);
+    assert!(resolved.iter().find(|r| r.name == "audit").unwrap().shadowed.is_empty());
+}
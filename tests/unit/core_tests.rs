@@ -16,3 +16,668 @@ fn test_language_path_generation() {
     let path = core.language_path("python", "3.11");
     assert!(path.ends_with(".cyrus/languages/python/3.11"));
 }
+
+#[test]
+fn test_processes_accept_shorthand_and_tables() {
+    use cyrus::core::procfile::RestartPolicy;
+    use cyrus::core::Project;
+
+    let project: Project = toml::from_str(r#"
+        name = "shop"
+        language = "python"
+        version = "3.12"
+        package_manager = "pip"
+        dependencies = []
+        dev_dependencies = []
+        scripts = {}
+        environment = {}
+
+        [processes]
+        api = "uvicorn app:main --reload"
+        worker = { run = "celery -A app worker", restart = "always", env = { C_FORCE_ROOT = "1" } }
+    "#).unwrap();
+
+    assert_eq!(project.processes["api"].restart, RestartPolicy::OnFailure);
+    assert_eq!(project.processes["worker"].restart, RestartPolicy::Always);
+    assert_eq!(project.processes["worker"].env["C_FORCE_ROOT"], "1");
+    assert!(!RestartPolicy::Never.should_restart(1));
+    assert!(RestartPolicy::OnFailure.should_restart(1));
+    assert!(!RestartPolicy::OnFailure.should_restart(0));
+}
+
+#[test]
+fn test_venv_built_for_another_version_is_stale() {
+    use cyrus::core::venv::{self, VenvStatus};
+
+    let dir = tempfile::TempDir::new().unwrap();
+    assert_eq!(venv::status(dir.path(), "3.12"), VenvStatus::Missing);
+
+    let venv_path = venv::venv_path(dir.path());
+    std::fs::create_dir_all(&venv_path).unwrap();
+    std::fs::write(venv_path.join("pyvenv.cfg"), "include-system-site-packages = false\nversion = 3.11.9\n").unwrap();
+    venv::record_version(&venv_path, "3.11").unwrap();
+
+    assert_eq!(venv::status(dir.path(), "3.11"), VenvStatus::Current);
+    assert!(matches!(venv::status(dir.path(), "3.12"), VenvStatus::Stale { .. }));
+}
+
+#[test]
+fn test_lockfile_detects_drift_from_cyrus_toml() {
+    use cyrus::core::lockfile::{LockedToolchain, Lockfile, ToolchainRequest};
+    use std::collections::BTreeMap;
+
+    let request = |version: &str| ToolchainRequest {
+        language: "javascript".to_string(),
+        version: version.to_string(),
+        package_manager: "npm".to_string(),
+    };
+    let lock = Lockfile::default().relock(&[request("20")], |r| LockedToolchain {
+        language: r.language.clone(),
+        requested: r.version.clone(),
+        resolved: "20.0.0".to_string(),
+        package_manager: r.package_manager.clone(),
+        package_manager_version: None,
+        artifacts: BTreeMap::new(),
+    });
+
+    assert!(lock.mismatches(&[request("20")]).is_empty());
+    assert_eq!(lock.mismatches(&[request("22")]).len(), 1);
+
+    let round_trip: Lockfile = toml::from_str(&toml::to_string_pretty(&lock).unwrap()).unwrap();
+    assert_eq!(round_trip, lock);
+}
+
+#[test]
+fn test_version_files_prefer_tool_specific_pins() {
+    use cyrus::core::version_files::{parse_file, read_pins};
+
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(dir.path().join(".tool-versions"), "nodejs 18.19.0\npython 3.11.4 3.10.0\njava temurin-17.0.2+8\n").unwrap();
+    std::fs::write(dir.path().join(".nvmrc"), "v20.11.0\n").unwrap();
+
+    let pins = read_pins(dir.path()).unwrap();
+    let version = |language: &str| pins.iter().find(|p| p.language == language).map(|p| p.version.clone());
+    assert_eq!(pins[0].language, "javascript");
+    assert_eq!(version("javascript").as_deref(), Some("20.11.0"));
+    assert_eq!(version("python").as_deref(), Some("3.11.4"));
+    assert_eq!(version("java").as_deref(), Some("17.0.2+8"));
+
+    assert_eq!(parse_file("go.mod", "module x\n\ngo 1.21\n\ntoolchain go1.21.5\n"), [("golang", "1.21.5".to_string())]);
+    assert!(parse_file(".nvmrc", "lts/*\n").is_empty());
+}
+
+#[test]
+fn test_version_file_discovery_stops_at_repo_and_home() {
+    use cyrus::core::version_files::discover_from;
+
+    let home = tempfile::TempDir::new().unwrap();
+    let repo = home.path().join("code/app");
+    std::fs::create_dir_all(repo.join("src")).unwrap();
+    std::fs::write(home.path().join(".python-version"), "3.12.1\n").unwrap();
+    std::fs::write(home.path().join("code/.nvmrc"), "20\n").unwrap();
+
+    // A global pin in $HOME doesn't turn home into the project root
+    std::fs::create_dir(home.path().join("notes")).unwrap();
+    assert!(discover_from(&home.path().join("notes"), Some(home.path())).is_none());
+    assert_eq!(discover_from(&repo, Some(home.path())).map(|(root, _)| root), Some(home.path().join("code")));
+
+    // Nor does anything above the repository root
+    std::fs::create_dir(repo.join(".git")).unwrap();
+    assert!(discover_from(&repo.join("src"), Some(home.path())).is_none());
+
+    std::fs::write(repo.join(".nvmrc"), "18\n").unwrap();
+    let (root, pins) = discover_from(&repo.join("src"), Some(home.path())).unwrap();
+    assert_eq!((root, pins[0].version.as_str()), (repo, "18"));
+}
+
+#[test]
+fn test_external_versions_normalize_to_cyrus_form() {
+    use cyrus::core::external::short_version;
+    use cyrus::core::version_files::normalize_version;
+
+    assert_eq!(normalize_version("javascript", "v20.11.0").as_deref(), Some("20.11.0"));
+    assert_eq!(normalize_version("java", "17.0.2-tem").as_deref(), Some("17.0.2"));
+    assert_eq!(normalize_version("ruby", "ruby-3.2.2").as_deref(), Some("3.2.2"));
+    assert_eq!(normalize_version("python", "miniconda3-latest"), None);
+
+    assert_eq!(short_version("python", "3.11.4").as_deref(), Some("3.11"));
+    assert_eq!(short_version("javascript", "20.11.0").as_deref(), Some("20"));
+    assert_eq!(short_version("python", "3.11"), None);
+}
+
+#[test]
+fn test_unversioned_cyrus_toml_migrates_with_backup() {
+    use cyrus::core::migration::{migrate_file, CURRENT_SCHEMA_VERSION};
+    use cyrus::core::Project;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("cyrus.toml");
+    std::fs::write(&path, "name = \"app\"\nlanguage = \"python\"\nversion = \"3.11\"\npackage_manager = \"pip\"\n").unwrap();
+
+    let (_, report) = migrate_file(&path, true).unwrap();
+    assert!(report.is_needed());
+    assert!(report.backup.is_none());
+
+    // Loading migrates in memory and leaves the file alone
+    let original = std::fs::read_to_string(&path).unwrap();
+    let project = Project::load_from_file(&path).unwrap();
+    assert_eq!(project.schema_version, CURRENT_SCHEMA_VERSION);
+    assert!(!project.enable_aliases);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+    assert!(!dir.path().join("cyrus.toml.v1.bak").exists());
+
+    let (_, report) = migrate_file(&path, false).unwrap();
+    assert_eq!(report.backup, Some(dir.path().join("cyrus.toml.v1.bak")));
+    assert_eq!(std::fs::read_to_string(dir.path().join("cyrus.toml.v1.bak")).unwrap(), original);
+    assert!(!migrate_file(&path, true).unwrap().1.is_needed());
+}
+
+#[test]
+fn test_exec_toolchain_specs_and_cache_keys() {
+    use cyrus::commands::exec::{dependency_cache_dir, ToolchainSpec};
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    let defaults: HashMap<String, String> = [("python".to_string(), "3.11".to_string())].into_iter().collect();
+    let spec = |value: &str| ToolchainSpec::parse_with(value, &defaults).map(|s| (s.language, s.version));
+    assert_eq!(spec("node@20").unwrap(), ("javascript".to_string(), "20".to_string()));
+    assert_eq!(spec("py").unwrap(), ("python".to_string(), "3.11".to_string()));
+    assert_eq!(spec("ruby").unwrap().0, "ruby");
+    assert!(spec("python@").is_err());
+    assert!(spec("cobol@85").is_err());
+
+    let python = ToolchainSpec::parse_with("python@3.12", &defaults).unwrap();
+    let packages = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    let dir = dependency_cache_dir(Path::new("/c"), &python, None, &packages(&["requests", "rich"]));
+    assert!(dir.starts_with("/c/cache/exec") && dir.file_name().unwrap().to_string_lossy().starts_with("python-3.12-"));
+    assert_eq!(dir, dependency_cache_dir(Path::new("/c"), &python, None, &packages(&["rich", "requests"])));
+    assert_ne!(dir, dependency_cache_dir(Path::new("/c"), &python, Some(b"rich\n"), &packages(&["requests", "rich"])));
+}
+
+#[test]
+fn test_toolchains_table_adds_to_primary_language() {
+    use cyrus::core::lockfile::requested_toolchains;
+    use cyrus::core::Project;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("cyrus.toml");
+    std::fs::write(&path, r#"
+schema_version = 2
+name = "api"
+language = "python"
+version = "3.12"
+package_manager = "poetry"
+
+[toolchains]
+node = { version = "20", package_manager = "pnpm" }
+golang = "1.22"
+python = "3.11"
+"#).unwrap();
+
+    let project = Project::load_from_file(&path).unwrap();
+    let toolchains: Vec<(String, String, String)> = requested_toolchains(&project)
+        .into_iter()
+        .map(|t| (t.language, t.version, t.package_manager))
+        .collect();
+    assert_eq!(toolchains, [
+        ("python".to_string(), "3.12".to_string(), "poetry".to_string()),
+        ("golang".to_string(), "1.22".to_string(), "go mod".to_string()),
+        ("javascript".to_string(), "20".to_string(), "pnpm".to_string()),
+    ]);
+    // The dropped `python = "3.11"` is reported rather than silently ignored
+    let problems: Vec<String> = project.problems().into_iter().map(|(key, _)| key).collect();
+    assert_eq!(problems, ["toolchains.python"]);
+
+    std::fs::write(&path, "schema_version = 2\nname = \"svc\"\n\n[toolchains]\ngolang = \"1.22\"\n").unwrap();
+    let project = Project::load_from_file(&path).unwrap();
+    assert_eq!((project.language.as_str(), project.version.as_str()), ("golang", "1.22"));
+    assert!(project.toolchains.is_empty());
+}
+
+#[test]
+fn test_dependency_changes_map_to_package_managers() {
+    use cyrus::languages::java::pom_with_change;
+    use cyrus::languages::{get_language_handler, DependencyChange};
+
+    let change = DependencyChange::add("@types/node@20", true);
+    assert_eq!(change.name(), "@types/node");
+    let javascript = get_language_handler("javascript").unwrap();
+    assert_eq!(
+        javascript.dependency_command("pnpm", &change).unwrap(),
+        ["pnpm", "add", "-D", "@types/node@20"]
+    );
+
+    let pom = "<project>\n    <dependencies>\n    </dependencies>\n</project>\n";
+    let added = pom_with_change(pom, &DependencyChange::add("org.slf4j:slf4j-api@2.0.9", false)).unwrap();
+    assert!(added.contains("<artifactId>slf4j-api</artifactId>"));
+    let removed = pom_with_change(&added, &DependencyChange::Remove { name: "org.slf4j:slf4j-api".into(), dev: false }).unwrap();
+    assert_eq!(removed, pom);
+
+    let mut project = cyrus::core::Project::new("app".into(), "javascript".into(), "20".into(), "pnpm".into());
+    project.add_dependency("zod@3", false);
+    project.add_dependency("zod@3.22", true);
+    assert!(project.dependencies.is_empty());
+    assert_eq!(project.remove_dependency("zod"), Some(true));
+}
+
+#[test]
+fn test_rm_takes_dependency_group_from_manifest() {
+    use cyrus::core::manifest::{ManifestKind, NativeManifest};
+
+    // `cyrus rm` takes the dependency group from the manifest, not cyrus.toml
+    let pyproject = NativeManifest::parse(ManifestKind::Pyproject, "[tool.poetry.dependencies]\npython = \"^3.11\"\nfastapi = \"^0.110\"\n\n[tool.poetry.group.dev.dependencies]\npytest = \"^8.0\"\n").unwrap();
+    assert_eq!(pyproject.is_dev_dependency("pytest"), Some(true));
+    assert_eq!(pyproject.is_dev_dependency("fastapi"), Some(false));
+    assert_eq!(pyproject.is_dev_dependency("ruff"), None);
+}
+
+#[test]
+fn test_sync_imports_manifest_and_exports_cyrus_scripts() {
+    use cyrus::core::manifest::{self, ManifestKind, NativeManifest};
+    use cyrus::core::Project;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(dir.path().join("package.json"), r#"{
+  "name": "@acme/web",
+  "engines": { "node": ">=20" },
+  "scripts": {
+    "build": "vite build",
+    "test": "vitest"
+  },
+  "dependencies": { "react": "^18.2.0" }
+}
+"#).unwrap();
+
+    let native = NativeManifest::load(dir.path(), ManifestKind::PackageJson).unwrap();
+    assert_eq!(native.version.as_deref(), Some("20"));
+    assert_eq!(native.dependencies, ["react@^18.2.0"]);
+
+    let mut project = Project::new("web".into(), "javascript".into(), "20".into(), "npm".into());
+    project.scripts.clear();
+    project.scripts.insert("test".into(), "jest".into());
+    project.scripts.insert("lint".into(), "eslint .".into());
+    project.scripts.insert("dev".into(), "npm run dev".into());
+
+    let plan = manifest::plan(&project, &native, None);
+    assert_eq!(plan.import_scripts, [("build".to_string(), "npm run build".to_string())]);
+    assert_eq!(plan.export_scripts, [("lint".to_string(), "eslint .".to_string())]);
+    assert_eq!(plan.cyrus_only, ["dev"]);
+    assert_eq!(plan.conflicts.len(), 1);
+
+    // Stock wrappers match the native script instead of conflicting with it
+    project.scripts.insert("test".into(), "npm run test".into());
+    assert!(manifest::plan(&project, &native, Some(manifest::Prefer::Cyrus)).conflicts.is_empty());
+
+    native.write_scripts(&plan.export_scripts).unwrap();
+    let written = NativeManifest::load(dir.path(), ManifestKind::PackageJson).unwrap();
+    assert_eq!(written.scripts["lint"], "eslint .");
+    assert_eq!(written.scripts["build"], "vite build");
+}
+
+#[test]
+fn test_detect_infers_project_from_repository_files() {
+    use cyrus::core::detect;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(dir.path().join("pyproject.toml"), "[project]\nname = \"svc\"\nrequires-python = \">=3.11\"\ndependencies = [\"fastapi>=0.110\"]\n").unwrap();
+    std::fs::write(dir.path().join("poetry.lock"), "").unwrap();
+    std::fs::write(dir.path().join("Makefile"), ".PHONY: test\nVERSION := 1\ntest:\n\tpytest\nlint: test\n\truff .\n%.o: %.c\n").unwrap();
+
+    let detection = detect::detect(dir.path()).unwrap().unwrap();
+    assert_eq!(detection.language.value, "python");
+    assert_eq!(detection.version.value, "3.11");
+    assert_eq!((detection.package_manager.value.as_str(), detection.package_manager.reason.as_str()), ("poetry", "poetry.lock"));
+    assert_eq!(detection.scripts["lint"].value, "make lint");
+    assert_eq!(detection.dependencies, ["fastapi@>=0.110"]);
+
+    assert_eq!(detect::just_recipes("set shell := [\"bash\"]\nalias b := build\nbuild target:\n  cargo build\n_hidden:\n"), ["build"]);
+
+    let web = tempfile::TempDir::new().unwrap();
+    std::fs::write(web.path().join("package.json"), r#"{ "name": "web", "scripts": { "build": "vite build" } }"#).unwrap();
+    std::fs::write(web.path().join("yarn.lock"), "").unwrap();
+    let detection = detect::detect(web.path()).unwrap().unwrap();
+    assert_eq!(detection.scripts["build"].value, "yarn run build");
+}
+
+#[test]
+fn test_extends_merges_bases_and_detects_cycles() {
+    use cyrus::core::Project;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let shared = dir.path().join("shared");
+    let service = dir.path().join("service");
+    std::fs::create_dir_all(&shared).unwrap();
+    std::fs::create_dir_all(&service).unwrap();
+    std::fs::write(shared.join("cyrus.base.toml"), "schema_version = 2\nlanguage = \"python\"\nversion = \"3.12\"\npackage_manager = \"pip\"\n[custom_aliases]\nt = \"pytest\"\n[environment]\nLOG_LEVEL = \"info\"\nREGION = \"eu\"\n").unwrap();
+    std::fs::write(service.join("cyrus.toml"), "schema_version = 2\nextends = [\"../shared/cyrus.base.toml\", \"profile:enterprise\"]\nname = \"billing\"\n[environment]\nLOG_LEVEL = \"debug\"\n").unwrap();
+
+    let (project, merged) = Project::load_merged(service.join("cyrus.toml")).unwrap();
+    assert_eq!(project.language, "python");
+    assert_eq!(project.environment["LOG_LEVEL"], "debug");
+    assert_eq!(project.environment["REGION"], "eu");
+    assert_eq!(project.custom_aliases["t"], "pytest");
+    assert!(project.custom_aliases.contains_key("audit"));
+    assert_eq!(merged.origin("environment.LOG_LEVEL"), "cyrus.toml");
+    assert_eq!(merged.origin("environment.REGION"), "../shared/cyrus.base.toml");
+    assert_eq!(merged.origin("custom_aliases.audit"), "profile:enterprise");
+
+    // Saving keeps inherited values in the base
+    project.save_to_file(service.join("cyrus.toml")).unwrap();
+    let saved = std::fs::read_to_string(service.join("cyrus.toml")).unwrap();
+    assert!(!saved.contains("REGION") && !saved.contains("audit"));
+    assert_eq!(Project::load_from_file(service.join("cyrus.toml")).unwrap().environment["REGION"], "eu");
+
+    std::fs::write(shared.join("cyrus.base.toml"), "extends = [\"../service/cyrus.toml\"]\n").unwrap();
+    let error = Project::load_from_file(service.join("cyrus.toml")).unwrap_err();
+    assert!(format!("{:#}", error).contains("extends cycle"));
+}
+
+#[test]
+fn test_project_files_in_yaml_and_json() {
+    use cyrus::core::config_format::{self, ConfigFormat};
+    use cyrus::core::Project;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let mut project = Project::new("api".into(), "python".into(), "3.12".into(), "poetry".into());
+    project.environment.insert("PORT".into(), "8000".into());
+
+    project.save_to_file(dir.path().join("cyrus.yaml")).unwrap();
+    assert_eq!(config_format::find(dir.path()).unwrap(), Some(dir.path().join("cyrus.yaml")));
+    let loaded = Project::load_from_file(Project::config_path(dir.path()).unwrap()).unwrap();
+    assert_eq!((loaded.name.as_str(), loaded.package_manager.as_str()), ("api", "poetry"));
+    assert_eq!(loaded.environment["PORT"], "8000");
+
+    let table = config_format::read_table(&dir.path().join("cyrus.yaml")).unwrap();
+    let json = ConfigFormat::Json.serialize(&table).unwrap();
+    assert_eq!(ConfigFormat::Json.parse(&json).unwrap(), table);
+
+    std::fs::write(dir.path().join("cyrus.json"), json).unwrap();
+    assert!(config_format::find(dir.path()).is_err());
+    assert_eq!("yml".parse::<ConfigFormat>(), Ok(ConfigFormat::Yaml));
+}
+
+#[test]
+fn test_config_edits_preserve_comments() {
+    use cyrus::core::config_format::ConfigFormat;
+    use cyrus::core::document;
+
+    let content = "# Billing service\nname = \"billing\" # shown in logs\nversion = \"20\"\n\n[environment]\n# local only\nPORT = \"3000\"\n";
+
+    let edited = document::set(ConfigFormat::Toml, content, "environment.LOG_LEVEL", "debug".into()).unwrap();
+    let edited = document::set(ConfigFormat::Toml, &edited, "name", "payments".into()).unwrap();
+    assert!(edited.starts_with("# Billing service\nname = \"payments\" # shown in logs\n"));
+    assert!(edited.contains("# local only\nPORT = \"3000\"\nLOG_LEVEL = \"debug\""));
+
+    let unset = document::unset(ConfigFormat::Toml, &edited, "environment.PORT").unwrap().unwrap();
+    assert!(!unset.contains("PORT") && unset.contains("# Billing service"));
+    assert_eq!(document::unset(ConfigFormat::Toml, &unset, "environment.PORT").unwrap(), None);
+
+    let mut table: toml::value::Table = toml::from_str(content).unwrap();
+    table.insert("version".into(), "22".into());
+    assert_eq!(document::update(content, &table).unwrap(), content.replace("\"20\"", "\"22\""));
+
+    // Shorthand entries come back as written after a load and save
+    use cyrus::core::procfile::ProcessSpec;
+    use cyrus::core::toolchain::ToolchainSpec;
+    use std::collections::BTreeMap;
+
+    let content = "[toolchains]\ngolang = \"1.22\"\nnode = { version = \"20\", package_manager = \"pnpm\" }\n\n[processes]\nweb = \"npm start\" # port 3000\nworker = { run = \"celery worker\", cwd = \"api\" }\n";
+    let mut table: toml::value::Table = toml::from_str(content).unwrap();
+    let toolchains: BTreeMap<String, ToolchainSpec> = table["toolchains"].clone().try_into().unwrap();
+    let processes: BTreeMap<String, ProcessSpec> = table["processes"].clone().try_into().unwrap();
+    table.insert("toolchains".into(), toml::Value::try_from(&toolchains).unwrap());
+    table.insert("processes".into(), toml::Value::try_from(&processes).unwrap());
+    assert_eq!(document::update(content, &table).unwrap(), content);
+
+    assert_eq!(document::parse_value("3000"), Some(toml::Value::Integer(3000)));
+    assert_eq!(document::parse_value("npm run dev"), None);
+}
+
+#[test]
+fn test_schema_export_covers_config_types() {
+    use cyrus::config::schema::{self, SchemaKind};
+
+    let project = schema::generate(SchemaKind::Project);
+    assert!(project["properties"]["extends"].is_object());
+    assert!(project["required"].as_array().unwrap().iter().any(|key| key == "name"));
+
+    let global = schema::generate(SchemaKind::Global).to_string();
+    assert!(global.contains("Paranoid"));
+    assert!(schema::generate(SchemaKind::Template).to_string().contains("MachineLearning"));
+
+    assert_eq!("plugin".parse::<SchemaKind>(), Ok(SchemaKind::Plugin));
+    assert!(schema::editor_associations().contains("**/cyrus-plugin.toml"));
+}
+
+#[test]
+fn test_validate_reports_findings_by_key() {
+    use cyrus::commands::validate::{self, Severity, Validation};
+
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(dir.path().join("cyrus.toml"), r#"
+name = "billing"
+language = "javascript"
+version = "20"
+package_manager = "npm"
+
+[toolchains]
+cobol = "85"
+python = "3 12"
+
+[tasks.build]
+run = "npm run build"
+depends_on = ["test"]
+
+[tasks.test]
+depends_on = ["build"]
+"#).unwrap();
+
+    let mut validation = Validation::default();
+    assert!(validate::check_project(dir.path(), &mut validation).is_some());
+
+    let keys: Vec<&str> = validation.findings.iter()
+        .filter(|f| f.severity == Severity::Error)
+        .filter_map(|f| f.key.as_deref())
+        .collect();
+    assert!(keys.contains(&"toolchains.cobol"));
+    assert!(keys.contains(&"toolchains.python"));
+    assert_eq!(keys.iter().filter(|key| key.starts_with("tasks.")).count(), 1);
+    assert_eq!(validation.exit_code(false), 1);
+
+    // The old schema is reported, not migrated on disk
+    assert!(validation.findings.iter().any(|f| f.severity == Severity::Warning && f.key.as_deref() == Some("schema_version")));
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    assert!(!std::fs::read_to_string(dir.path().join("cyrus.toml")).unwrap().contains("schema_version"));
+
+    assert_eq!(Validation::default().exit_code(true), 0);
+}
+
+#[test]
+fn test_exit_code_keeps_child_code() {
+    use cyrus::error::{exit_code_for, CyrusError};
+
+    let failed: anyhow::Error = CyrusError::CommandFailed { command: "make test".into(), code: Some(3) }.into();
+    assert_eq!(exit_code_for(&failed), 3);
+    assert_eq!(exit_code_for(&failed.context("Running 'test'")), 3);
+
+    let other: anyhow::Error = CyrusError::CommandFailed { command: "make".into(), code: None }.into();
+    assert_eq!(exit_code_for(&other), 1);
+    assert_eq!(exit_code_for(&anyhow::anyhow!("no project")), 1);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_process_outcome_codes() {
+    use cyrus::runtime::process::{run_foreground, ProcessOutcome, TIMEOUT_EXIT_CODE};
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    let run = |script: &str, timeout: Option<Duration>| {
+        let args = vec!["-c".to_string(), script.to_string()];
+        async move { run_foreground("sh", &args, &HashMap::new(), timeout).await.unwrap() }
+    };
+
+    assert_eq!(run("exit 3", None).await, ProcessOutcome::Exited(3));
+    assert_eq!(run("kill -TERM $$", None).await.code(), 128 + 15);
+
+    let timed_out = run("sleep 5", Some(Duration::from_millis(200))).await;
+    assert_eq!(timed_out, ProcessOutcome::TimedOut);
+    assert_eq!(timed_out.code(), TIMEOUT_EXIT_CODE);
+    assert_eq!(TIMEOUT_EXIT_CODE, 124);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_doctor_path_checks_and_report() {
+    use cyrus::commands::doctor::{check_path, json_report, outcome, Check, CheckStatus};
+    use cyrus::core::CyrusCore;
+
+    let home = tempfile::TempDir::new().unwrap();
+    let cyrus_dir = home.path().join(".cyrus");
+    let core = CyrusCore {
+        home_dir: home.path().to_path_buf(),
+        config_dir: cyrus_dir.join("config"),
+        languages_dir: cyrus_dir.join("languages"),
+        cyrus_dir,
+    };
+    let toolchain_bin = core.languages_dir.join("zig/0.11.0/bin");
+    let system_bin = home.path().join("usr/bin");
+    for dir in [&toolchain_bin, &system_bin] {
+        std::fs::create_dir_all(dir).unwrap();
+        let tool = dir.join("zig");
+        std::fs::write(&tool, "#!/bin/sh\n").unwrap();
+        let mut permissions = std::fs::metadata(&tool).unwrap().permissions();
+        std::os::unix::fs::PermissionsExt::set_mode(&mut permissions, 0o755);
+        std::fs::set_permissions(&tool, permissions).unwrap();
+    }
+
+    // Not on PATH at all: nothing to warn about
+    let mut checks = Vec::new();
+    check_path(&core, &[system_bin.clone()], &mut checks);
+    assert!(checks.iter().all(|c| c.status == CheckStatus::Ok));
+
+    let mut checks = Vec::new();
+    check_path(&core, &[system_bin.clone(), toolchain_bin.clone()], &mut checks);
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].status, CheckStatus::Warning);
+    assert!(checks[0].message.contains("`zig` resolves to"));
+
+    checks.push(Check::error("Installations", "zig 0.11.0 has no executables", "cyrus install zig"));
+    let report = json_report(&checks);
+    assert_eq!((report["errors"].as_u64(), report["warnings"].as_u64()), (Some(1), Some(1)));
+    assert_eq!(report["checks"][1]["status"], "error");
+    assert_eq!(report["checks"][1]["fix"], "cyrus install zig");
+    assert!(outcome(&checks).is_err());
+    assert!(outcome(&checks[..1]).is_ok());
+}
+
+#[test]
+fn test_watch_filter_honours_nested_gitignores_and_globs() {
+    use cyrus::runtime::watch::WatchFilter;
+    use std::path::PathBuf;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    for sub in ["src", "dist", "web/dist", "web/src", "target/debug"] {
+        std::fs::create_dir_all(root.join(sub)).unwrap();
+    }
+    std::fs::write(root.join(".gitignore"), "target/\n*.log\n").unwrap();
+    std::fs::write(root.join("web/.gitignore"), "/dist\n!keep.log\n").unwrap();
+
+    let filter = WatchFilter::new(&root, &[]).unwrap();
+    let relevant = |path: &str| filter.relevant(&root.join(path));
+    assert_eq!(relevant("src/main.rs"), Some(PathBuf::from("src/main.rs")));
+    // `/dist` in web/.gitignore is anchored at web/, not at the project root
+    assert!(relevant("web/dist/app.js").is_none());
+    assert!(relevant("dist/app.js").is_some());
+    assert!(relevant("target/debug/app").is_none());
+    assert!(relevant("server.log").is_none());
+    assert!(relevant("web/keep.log").is_some());
+    assert!(relevant(".cyrus/tasks.json").is_none());
+
+    let filter = WatchFilter::new(&root, &["**/*.rs".to_string()]).unwrap();
+    assert!(filter.relevant(&root.join("src/main.rs")).is_some());
+    assert!(filter.relevant(&root.join("web/src/app.ts")).is_none());
+
+    // Ignored trees get no watch, from the root or from a directory created later
+    let mut watched = cyrus::runtime::watch::watched_dirs(&root);
+    watched.sort();
+    assert_eq!(watched, ["", "dist", "src", "web", "web/src"].map(|sub| root.join(sub)));
+    assert_eq!(cyrus::runtime::watch::watched_dirs(&root.join("web")), [root.join("web"), root.join("web/src")]);
+}
+
+#[test]
+fn test_releases_resolve_to_newest_matching_patch() {
+    use cyrus::languages::newest_matching;
+
+    let node = ["v21.6.0", "v20.11.1", "v20.11.0", "v20.9.0", "v18.19.0"];
+    assert_eq!(newest_matching("20", node).as_deref(), Some("20.11.1"));
+    assert_eq!(newest_matching("20.9", node).as_deref(), Some("20.9.0"));
+    assert_eq!(newest_matching("19", node), None);
+
+    let python = ["3.12.1", "3.11.9", "3.11.10", "3.13.0-rc1", "3.1.5"];
+    assert_eq!(newest_matching("3.11", python).as_deref(), Some("3.11.10"));
+    assert_eq!(newest_matching("3.1", python).as_deref(), Some("3.1.5"));
+    assert_eq!(newest_matching("3", python).as_deref(), Some("3.12.1"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_runtime_environment_over_install_layouts() {
+    use cyrus::languages::{distribution_root, get_language_handler};
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let touch = |path: std::path::PathBuf| {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "").unwrap();
+    };
+
+    // bin at the top, one level down, and inside a macOS JDK bundle
+    let flat = dir.path().join("rust");
+    touch(flat.join("bin/cargo"));
+    let go = dir.path().join("golang");
+    touch(go.join("go/bin/go"));
+    let jdk = dir.path().join("java");
+    touch(jdk.join("jdk-21/Contents/Home/bin/java"));
+    assert_eq!(distribution_root(&flat), flat);
+    assert_eq!(distribution_root(&go), go.join("go"));
+    assert_eq!(distribution_root(&jdk), jdk.join("jdk-21/Contents/Home"));
+    assert_eq!(distribution_root(&dir.path().join("missing")), dir.path().join("missing"));
+
+    let cache = dir.path().join("cache");
+    let environment = get_language_handler("golang").unwrap().runtime_environment("1.22.0", &go, &cache);
+    assert_eq!(environment.find_executable("go"), Some(go.join("go/bin/go")));
+    assert_eq!(environment.find_executable("gofmt"), None);
+    assert_eq!(environment.variables["GOROOT"], go.join("go").to_string_lossy());
+    assert_eq!(environment.variables["GOMODCACHE"], cache.join("go-mod").to_string_lossy());
+
+    let python = dir.path().join("python");
+    touch(python.join("bin/python3"));
+    std::fs::create_dir_all(python.join("lib")).unwrap();
+    let environment = get_language_handler("python").unwrap().runtime_environment("3.12.1", &python, &cache);
+    assert_eq!(environment.find_executable("python3"), Some(python.join("bin/python3")));
+    let vars = environment.to_env_vars().unwrap();
+    let library_var = if cfg!(target_os = "macos") { "DYLD_FALLBACK_LIBRARY_PATH" } else { "LD_LIBRARY_PATH" };
+    assert!(vars[library_var].starts_with(&*python.join("lib").to_string_lossy()));
+    assert!(vars["PATH"].starts_with(&*python.join("bin").to_string_lossy()));
+}
+
+#[test]
+fn test_download_record_rejects_archive_with_wrong_digest() {
+    use cyrus::utils::downloader::{read_download_record, record_download, sha256_file};
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let archive = dir.path().join("node.archive");
+    std::fs::write(&archive, "node").unwrap();
+    let digest = sha256_file(&archive).unwrap();
+    let url = "https://nodejs.org/dist/v20.11.1/node-v20.11.1-linux-x64.tar.xz";
+
+    assert!(record_download(dir.path(), url, &archive, Some("0000")).is_err());
+    assert!(!archive.exists());
+    assert_eq!(read_download_record(dir.path()), None);
+
+    std::fs::write(&archive, "node").unwrap();
+    let record = record_download(dir.path(), url, &archive, Some(&digest)).unwrap();
+    assert_eq!(record.sha256, digest);
+    assert_eq!(read_download_record(dir.path()), Some(record));
+}
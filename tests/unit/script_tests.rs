@@ -0,0 +1,90 @@
+//! Unit tests for project script parsing and composition
+
+use cyrus::core::script::{needs_shell, Invocation};
+use cyrus::core::{Project, Script};
+
+fn project_with_scripts(scripts: &[(&str, Script)]) -> Project {
+    let mut project = Project::new(
+        "demo".to_string(),
+        "javascript".to_string(),
+        "20".to_string(),
+        "npm".to_string(),
+    );
+    project.scripts.clear();
+    project.custom_aliases.clear();
+    for (name, script) in scripts {
+        project.scripts.insert(name.to_string(), script.clone());
+    }
+    project
+}
+
+#[test]
+fn test_quoted_globs_stay_exec_form() {
+    assert!(!needs_shell("eslint 'src/**/*.ts'"));
+    assert_eq!(
+        Invocation::parse("eslint 'src/**/*.ts'").unwrap(),
+        Invocation::Exec {
+            program: "eslint".to_string(),
+            args: vec!["src/**/*.ts".to_string()],
+        }
+    );
+}
+
+#[test]
+fn test_shell_syntax_uses_shell_form() {
+    assert!(needs_shell("rm -rf dist && tsc"));
+    assert!(needs_shell("cat log | grep error"));
+    assert!(needs_shell("eslint src/**/*.ts"));
+    assert!(needs_shell("NODE_ENV=test jest"));
+    assert!(needs_shell("echo \"$HOME\""));
+    assert!(!needs_shell("echo '$HOME'"));
+}
+
+#[test]
+fn test_hooks_and_sequences_expand_in_order() {
+    let project = project_with_scripts(&[
+        ("lint", Script::from("eslint .")),
+        ("prebuild", Script::from("rimraf dist")),
+        ("build", Script::from("tsc")),
+        ("postbuild", Script::from("echo done")),
+        ("ci", Script::Sequence(vec!["lint".to_string(), "build".to_string()])),
+    ]);
+
+    let steps = project.plan_command("ci", &[], &[]).unwrap();
+    let names: Vec<_> = steps.iter().map(|s| s.script.clone().unwrap()).collect();
+    assert_eq!(names, ["lint", "prebuild", "build", "postbuild"]);
+}
+
+#[test]
+fn test_script_cycles_are_rejected() {
+    let project = project_with_scripts(&[
+        ("a", Script::Sequence(vec!["b".to_string()])),
+        ("b", Script::Sequence(vec!["a".to_string()])),
+    ]);
+
+    assert!(project.plan_command("a", &[], &[]).is_err());
+}
+
+#[test]
+fn test_aliases_expanding_to_scripts_beat_inherited_aliases() {
+    use cyrus::core::alias::{AliasLayer, AliasSource};
+
+    let mut project = project_with_scripts(&[("build", Script::from("tsc"))]);
+    project.custom_aliases.insert("b".to_string(), "build --watch".to_string());
+    let global = AliasLayer {
+        source: AliasSource::Global,
+        aliases: [("build".to_string(), "make".to_string())].into_iter().collect(),
+    };
+
+    // `cyrus alias list --resolved` shows the order plan_command resolves in
+    let sources: Vec<AliasSource> = project.resolution_layers(&[global.clone()]).into_iter().map(|layer| layer.source).collect();
+    assert_eq!(sources, [AliasSource::ProjectAlias, AliasSource::ProjectScript, AliasSource::Global]);
+
+    let steps = project.plan_command("b", &[], &[global]).unwrap();
+    assert_eq!(steps.len(), 1);
+    assert_eq!(steps[0].script.as_deref(), Some("build"));
+    match &steps[0].invocation {
+        Invocation::Exec { program, args } => assert_eq!((program.as_str(), args.as_slice()), ("tsc", ["--watch".to_string()].as_slice())),
+        other => panic!("expected exec form, got {:?}", other),
+    }
+}
@@ -0,0 +1,54 @@
+//! Unit tests for the encrypted secrets vault
+
+#![cfg(feature = "security-audit")]
+
+use cyrus::secrets::{resolve_environment, SecretScope, SecretStore};
+use std::collections::HashMap;
+
+#[test]
+fn test_secret_roundtrip_is_encrypted_on_disk() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut store = SecretStore::open(dir.path(), &SecretScope::Global).unwrap();
+    store.set("db_password".to_string(), "hunter2".to_string()).unwrap();
+    store.save().unwrap();
+
+    let raw = std::fs::read(dir.path().join("secrets").join("global.vault")).unwrap();
+    assert!(!String::from_utf8_lossy(&raw).contains("hunter2"));
+
+    let reopened = SecretStore::open(dir.path(), &SecretScope::Global).unwrap();
+    assert_eq!(reopened.get("db_password"), Some("hunter2"));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let key = std::fs::metadata(dir.path().join("secrets").join("vault.key")).unwrap();
+        assert_eq!(key.permissions().mode() & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn test_project_secret_shadows_global() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_root = dir.path().join("app");
+    std::fs::create_dir_all(&project_root).unwrap();
+
+    let mut global = SecretStore::open(dir.path(), &SecretScope::Global).unwrap();
+    global.set("api_key".to_string(), "global".to_string()).unwrap();
+    global.save().unwrap();
+
+    let mut project = SecretStore::open(dir.path(), &SecretScope::Project(project_root.clone())).unwrap();
+    project.set("api_key".to_string(), "project".to_string()).unwrap();
+    project.save().unwrap();
+
+    let mut env = HashMap::new();
+    env.insert("API_KEY".to_string(), "secret://api_key".to_string());
+    env.insert("PORT".to_string(), "3000".to_string());
+
+    let resolved = resolve_environment(dir.path(), Some(&project_root), &env).unwrap();
+    assert_eq!(resolved["API_KEY"], "project");
+    assert_eq!(resolved["PORT"], "3000");
+
+    env.insert("MISSING".to_string(), "secret://nope".to_string());
+    assert!(resolve_environment(dir.path(), Some(&project_root), &env).is_err());
+}
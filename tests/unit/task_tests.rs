@@ -0,0 +1,59 @@
+//! Unit tests for task ordering and incremental fingerprints
+
+use cyrus::core::task::{execution_order, fingerprint, outputs_present, Task};
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+fn task(depends_on: &[&str]) -> Task {
+    Task {
+        run: Some("true".into()),
+        depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        ..Task::default()
+    }
+}
+
+#[test]
+fn test_dependencies_run_first_once() {
+    let mut tasks = HashMap::new();
+    tasks.insert("codegen".to_string(), task(&[]));
+    tasks.insert("lib".to_string(), task(&["codegen"]));
+    tasks.insert("app".to_string(), task(&["codegen", "lib"]));
+
+    assert_eq!(execution_order(&tasks, "app").unwrap(), ["codegen", "lib", "app"]);
+}
+
+#[test]
+fn test_cycles_and_unknown_dependencies_fail() {
+    let mut tasks = HashMap::new();
+    tasks.insert("a".to_string(), task(&["b"]));
+    tasks.insert("b".to_string(), task(&["a"]));
+    tasks.insert("c".to_string(), task(&["missing"]));
+
+    assert!(execution_order(&tasks, "a").is_err());
+    assert!(execution_order(&tasks, "c").is_err());
+}
+
+#[test]
+fn test_fingerprint_tracks_inputs_and_command() {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join("src/main.py"), "print('hi')").unwrap();
+
+    let task = Task {
+        inputs: vec!["src/**/*.py".to_string()],
+        outputs: vec!["dist".to_string()],
+        ..Task::default()
+    };
+
+    let first = fingerprint(dir.path(), &task, "python -m build").unwrap();
+    assert_eq!(first, fingerprint(dir.path(), &task, "python -m build").unwrap());
+    assert_ne!(first, fingerprint(dir.path(), &task, "python -m build --wheel").unwrap());
+
+    std::fs::write(dir.path().join("src/main.py"), "print('bye')").unwrap();
+    assert_ne!(first, fingerprint(dir.path(), &task, "python -m build").unwrap());
+
+    assert!(!outputs_present(dir.path(), &task).unwrap());
+    std::fs::create_dir(dir.path().join("dist")).unwrap();
+    std::fs::write(dir.path().join("dist/app.whl"), "").unwrap();
+    assert!(outputs_present(dir.path(), &task).unwrap());
+}